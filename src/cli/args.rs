@@ -1,11 +1,14 @@
-use clap::{Parser, Subcommand};
-use ansi_term::Style;
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use clap::{ArgMatches, Parser, Subcommand};
+use colored::Colorize;
+use std::path::PathBuf;
 
 fn get_after_help() -> String {
     format!(
         "\n{}\n  rustbuster dir -u http://example.com -w wordlist.txt\n  rustbuster dns -d example.com -w subdomains.txt\n  rustbuster vhost -u http://example.com -w vhosts.txt\n  rustbuster fuzz -u http://example.com/FUZZ -w wordlist.txt\n\n{}\n  --arguments    Show all available arguments and options\n  --examples     Show detailed usage examples for all modes\n  --info         Show additional information about Rustbuster\n\nFor mode-specific help: rustbuster <MODE> --help\n",
-        Style::new().bold().underline().paint("QUICK START:"),
-        Style::new().bold().underline().paint("EXTRA INFO:")
+        "QUICK START:".bold().underline(),
+        "EXTRA INFO:".bold().underline()
     )
 }
 
@@ -19,6 +22,14 @@ fn get_after_help() -> String {
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Config file to use instead of ~/.rustbuster.toml (also RUSTBUSTER_CONFIG)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Never color output (also NO_COLOR, or when stdout is not a terminal)
+    #[arg(long, global = true)]
+    pub no_color: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -27,19 +38,108 @@ pub enum Commands {
     Dns(DnsArgs),
     Vhost(VhostArgs),
     Fuzz(FuzzArgs),
+    Sessions(SessionsArgs),
+    /// Compare two json result files and list new, removed and changed URLs
+    Diff(DiffArgs),
+}
+
+impl Commands {
+    /// Options shared by the scan modes; `None` for subcommands that do not scan
+    pub fn common(&self) -> Option<&CommonArgs> {
+        match self {
+            Commands::Dir(args) => Some(&args.common),
+            Commands::Dns(args) => Some(&args.common),
+            Commands::Vhost(args) => Some(&args.common),
+            Commands::Fuzz(args) => Some(&args.common),
+            Commands::Sessions(_) | Commands::Diff(_) => None,
+        }
+    }
+
+    /// The -x value of the modes that take extensions
+    pub fn extensions_mut(&mut self) -> Option<&mut Option<String>> {
+        match self {
+            Commands::Dir(args) => Some(&mut args.extensions),
+            Commands::Fuzz(args) => Some(&mut args.extensions),
+            _ => None,
+        }
+    }
+
+    pub fn common_mut(&mut self) -> Option<&mut CommonArgs> {
+        match self {
+            Commands::Dir(args) => Some(&mut args.common),
+            Commands::Dns(args) => Some(&mut args.common),
+            Commands::Vhost(args) => Some(&mut args.common),
+            Commands::Fuzz(args) => Some(&mut args.common),
+            Commands::Sessions(_) | Commands::Diff(_) => None,
+        }
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct CommonArgs {
     #[arg(short = 'w', long, value_name = "FILE")]
-    pub wordlist: Option<String>,
+    pub wordlist: Vec<String>,
+
+    #[arg(long)]
+    pub wordlist_intersect: bool,
+
+    #[arg(long)]
+    pub unique: bool,
+
+    #[arg(long)]
+    pub sort: bool,
+
+    #[arg(long, value_name = "MUTATIONS", value_delimiter = ',', value_parser = ["case"])]
+    pub wordlist_mutate: Vec<String>,
+
+    #[arg(long, value_name = "TEXT")]
+    pub prefix: Vec<String>,
+
+    #[arg(long, value_name = "TEXT")]
+    pub suffix: Vec<String>,
+
+    /// Skip this many words of the final wordlist
+    #[arg(long, value_name = "N")]
+    pub wordlist_offset: Option<usize>,
+
+    /// Use at most this many words, starting at --wordlist-offset
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub wordlist_limit: Option<u64>,
+
+    /// Keep every n-th word starting at the i-th, as `(i, n)`
+    #[arg(long, value_name = "I/N", value_parser = parse_shard)]
+    pub shard: Option<(usize, usize)>,
 
     #[arg(short = 't', long, default_value = "10", value_name = "NUM")]
     pub threads: usize,
 
+    #[arg(long, value_name = "NUM")]
+    pub dns_threads: Option<usize>,
+
     #[arg(long, default_value = "10", value_name = "SECS")]
     pub timeout: u64,
 
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub connect_timeout: Option<u64>,
+
+    #[arg(long, conflicts_with = "http1_only")]
+    pub http2_only: bool,
+
+    #[arg(long)]
+    pub http1_only: bool,
+
+    #[arg(long, value_name = "NUM")]
+    pub pool_max_idle: Option<usize>,
+
+    #[arg(long)]
+    pub no_compression: bool,
+
+    #[arg(long, default_value = "1048576", value_name = "BYTES")]
+    pub max_body_size: usize,
+
+    #[arg(long)]
+    pub include_body: bool,
+
     #[arg(long)]
     pub no_tui: bool,
 
@@ -49,9 +149,36 @@ pub struct CommonArgs {
     #[arg(short = 'n', long, value_name = "CODES")]
     pub negative_status_codes: Option<String>,
 
+    #[arg(long)]
+    pub normalize_status: bool,
+
+    #[arg(long, value_name = "EXT:CODES", num_args = 1.., value_parser = parse_ext_status)]
+    pub ext_status: Vec<(String, Vec<u16>)>,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub limit_per_status: Option<u64>,
+
+    #[arg(long, value_name = "CODES", value_delimiter = ',')]
+    pub notify_on: Vec<u16>,
+
+    #[arg(long, value_name = "CMD")]
+    pub on_hit: Option<String>,
+
+    #[arg(long)]
+    pub fail_on_findings: bool,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_errors: Option<u64>,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_consecutive_errors: Option<u64>,
+
     #[arg(short = 'r', long)]
     pub follow_redirects: bool,
 
+    #[arg(long, value_name = "N", requires = "follow_redirects")]
+    pub max_redirects: Option<usize>,
+
     #[arg(short = 'a', long, default_value = "rustbuster/0.1.0", value_name = "STRING")]
     pub user_agent: String,
 
@@ -64,21 +191,78 @@ pub struct CommonArgs {
     #[arg(short = 'c', long, value_name = "STRING")]
     pub cookies: Option<String>,
 
+    #[arg(long)]
+    pub cookie_jar: bool,
+
+    #[arg(long, value_name = "FILE")]
+    pub cookies_file: Option<String>,
+
+    #[arg(long)]
+    pub spoof_ip: bool,
+
+    #[arg(long, value_name = "FILE")]
+    pub spoof_ip_file: Option<String>,
+
     #[arg(short = 'H', long, value_name = "HEADER")]
     pub headers: Vec<String>,
 
+    #[arg(long, value_name = "USER:PASS", conflicts_with = "bearer")]
+    pub basic_auth: Option<String>,
+
+    #[arg(long, value_name = "TOKEN")]
+    pub bearer: Option<String>,
+
+    #[arg(long)]
+    pub json: bool,
+
+    #[arg(long, value_name = "VALUE")]
+    pub accept: Option<String>,
+
+    #[arg(long, value_name = "VALUE")]
+    pub content_type: Option<String>,
+
+    #[arg(long, value_name = "BODY")]
+    pub data: Option<String>,
+
+    #[arg(long, value_name = "FILE", conflicts_with = "data")]
+    pub data_file: Option<String>,
+
     #[arg(short = 'p', long, value_name = "URL")]
     pub proxy: Option<String>,
 
+    #[arg(long)]
+    pub skip_preflight: bool,
+
+    #[arg(long)]
+    pub skip_check: bool,
+
     #[arg(long)]
     pub no_tls_validation: bool,
 
+    #[arg(long, value_name = "FILE", requires = "client_key")]
+    pub client_cert: Option<String>,
+
+    #[arg(long, value_name = "FILE", requires = "client_cert")]
+    pub client_key: Option<String>,
+
+    #[arg(long, conflicts_with = "ipv6_only")]
+    pub ipv4_only: bool,
+
+    #[arg(long)]
+    pub ipv6_only: bool,
+
+    #[arg(long, value_name = "IP", conflicts_with_all = ["ipv4_only", "ipv6_only"])]
+    pub interface: Option<std::net::IpAddr>,
+
     #[arg(short = 'e', long)]
     pub expanded: bool,
 
     #[arg(short = 'q', long)]
     pub quiet: bool,
 
+    #[arg(long, alias = "raw")]
+    pub url_only: bool,
+
     #[arg(short = 'v', long)]
     pub verbose: bool,
 
@@ -88,9 +272,18 @@ pub struct CommonArgs {
     #[arg(short = 'o', long, value_name = "FILE")]
     pub output: Option<String>,
 
-    #[arg(long, default_value = "plain", value_name = "FORMAT")]
+    #[arg(long, default_value = "plain", value_name = "FORMAT", value_parser = ["plain", "json", "jsonl", "csv"])]
     pub output_format: String,
 
+    #[arg(long)]
+    pub json_metadata: bool,
+
+    #[arg(long, requires = "output", conflicts_with = "json_metadata")]
+    pub output_append: bool,
+
+    #[arg(long)]
+    pub summary_line: bool,
+
     #[arg(long)]
     pub wildcard: bool,
 
@@ -103,45 +296,158 @@ pub struct CommonArgs {
     #[arg(long, value_name = "SIZES")]
     pub filter_size: Option<String>,
 
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "50", value_parser = clap::value_parser!(u64).range(1..))]
+    pub auto_filter: Option<u64>,
+
+    #[arg(long, value_name = "MS")]
+    pub min_time: Option<u64>,
+
+    #[arg(long, value_name = "MS")]
+    pub max_time: Option<u64>,
+
+    #[arg(long, conflicts_with = "only_files")]
+    pub only_dirs: bool,
+
+    #[arg(long)]
+    pub only_files: bool,
+
     #[arg(long, value_name = "MS")]
     pub delay: Option<u64>,
+
+    #[arg(long, value_name = "MIN-MAX", conflicts_with = "delay", value_parser = parse_delay_range)]
+    pub random_delay: Option<(u64, u64)>,
+
+    #[arg(long, value_name = "RPS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub rate_limit: Option<u32>,
     
     #[arg(long, value_name = "NAME")]
     pub save_session: Option<String>,
     
     #[arg(long, value_name = "NAME")]
     pub resume_session: Option<String>,
+
+    #[arg(long, value_name = "DIR")]
+    pub session_dir: Option<String>,
+
+    #[arg(long, value_name = "FILE")]
+    pub delta_output: Option<String>,
+
+    #[arg(long, value_name = "FILE")]
+    pub resume_state: Option<String>,
     
     #[arg(long)]
     pub smart_404: bool,
+
+    #[arg(long)]
+    pub head_first: bool,
+
+    #[arg(long)]
+    pub probe_methods: bool,
     
     #[arg(long, value_name = "FILE")]
     pub targets: Option<String>,
     
     #[arg(long, value_name = "FILE")]
     pub report: Option<String>,
+
+    #[arg(long, value_name = "FORMAT", value_parser = ["html", "md"])]
+    pub report_format: Option<String>,
     
-    #[arg(long, value_name = "FLOAT")]
+    #[arg(long, value_name = "FLOAT", value_parser = parse_ratio)]
     pub similarity_threshold: Option<f32>,
+
+    #[arg(long, value_name = "DIR")]
+    pub golden_dir: Option<String>,
+
+    #[arg(long, value_name = "DIR")]
+    pub save_bodies: Option<String>,
+
+    #[arg(long)]
+    pub show_timing: bool,
+
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    pub show_headers: Vec<String>,
+
+    #[arg(long)]
+    pub fingerprint: bool,
+
+    #[arg(long, value_name = "FILE")]
+    pub fingerprint_rules: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct SessionsArgs {
+    #[command(subcommand)]
+    pub action: SessionsAction,
+
+    /// Directory holding the sessions (default: ~/.rustbuster/sessions)
+    #[arg(long, value_name = "DIR", global = true)]
+    pub session_dir: Option<String>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SessionsAction {
+    /// Show saved sessions with their target, progress and last update
+    List,
+    /// Remove a saved session
+    Delete {
+        #[arg(value_name = "NAME")]
+        name: String,
+    },
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct DiffArgs {
+    /// Results of the earlier scan (json, --json-metadata json, or jsonl)
+    #[arg(value_name = "OLD")]
+    pub old: String,
+
+    /// Results of the later scan
+    #[arg(value_name = "NEW")]
+    pub new: String,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct DirArgs {
-    #[arg(short = 'u', long, value_name = "URL")]
+    #[arg(short = 'u', long, value_name = "URL", value_parser = parse_target_url)]
     pub url: String,
 
     #[arg(short = 'x', long, value_name = "EXTS")]
     pub extensions: Option<String>,
 
+    #[arg(long, value_name = "FILE")]
+    pub extensions_file: Option<String>,
+
     #[arg(short = 'R', long)]
     pub recursive: bool,
 
     #[arg(long, default_value = "3", value_name = "NUM")]
     pub depth: usize,
 
+    #[arg(long, value_name = "FILE")]
+    pub seed_urls: Option<String>,
+
+    #[arg(long, value_name = "SECS")]
+    pub per_dir_timeout: Option<u64>,
+
+    #[arg(long, requires = "recursive")]
+    pub stop_on_first: bool,
+
+    #[arg(long, value_name = "CODES")]
+    pub recurse_on_status: Option<String>,
+
     #[arg(long)]
     pub backup_extensions: bool,
 
+    #[arg(long)]
+    pub no_bare_word: bool,
+
+    #[arg(long, conflicts_with = "slash_only")]
+    pub add_slash: bool,
+
+    #[arg(long)]
+    pub slash_only: bool,
+
     #[command(flatten)]
     pub common: CommonArgs,
 }
@@ -157,32 +463,87 @@ pub struct DnsArgs {
     #[arg(long)]
     pub show_ips: bool,
 
+    #[arg(long)]
+    pub force: bool,
+
+    #[arg(long, value_name = "TYPE", ignore_case = true, value_parser = ["A", "AAAA", "MX", "TXT", "NS"])]
+    pub record_type: Option<String>,
+
+    #[arg(long, value_name = "IP[,IP...]")]
+    pub resolver: Option<String>,
+
+    #[arg(long, value_name = "FILE")]
+    pub resolvers_file: Option<String>,
+
+    #[arg(long, value_name = "URL", conflicts_with_all = ["resolver", "resolvers_file"])]
+    pub doh: Option<String>,
+
     #[command(flatten)]
     pub common: CommonArgs,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct VhostArgs {
-    #[arg(short = 'u', long, value_name = "URL")]
+    #[arg(short = 'u', long, value_name = "URL", value_parser = parse_target_url)]
     pub url: String,
 
+    #[arg(long)]
+    pub no_baseline_filter: bool,
+
     #[command(flatten)]
     pub common: CommonArgs,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct FuzzArgs {
-    #[arg(short = 'u', long, value_name = "URL")]
+    #[arg(short = 'u', long, value_name = "URL", value_parser = parse_target_url)]
     pub url: String,
 
     #[arg(short = 'x', long, value_name = "EXTS")]
     pub extensions: Option<String>,
 
+    #[arg(long, value_name = "FILE")]
+    pub extensions_file: Option<String>,
+
+    #[arg(long)]
+    pub no_bare_word: bool,
+
+    #[arg(long, value_name = "KEY=FILE")]
+    pub fuzz_wordlist: Vec<String>,
+
+    #[arg(long, default_value = "clusterbomb", value_name = "MODE", value_parser = ["clusterbomb", "pitchfork"])]
+    pub fuzz_mode: String,
+
+    #[arg(short = 'R', long)]
+    pub recursive: bool,
+
+    #[arg(long, default_value = "3", value_name = "NUM")]
+    pub depth: usize,
+
+    #[arg(long, requires = "recursive")]
+    pub stop_on_first: bool,
+
     #[command(flatten)]
     pub common: CommonArgs,
 }
 
+impl DirArgs {
+    pub fn get_recurse_statuses(&self) -> Option<Vec<u16>> {
+        self.recurse_on_status.as_ref().map(|codes| {
+            codes
+                .split(',')
+                .filter_map(|s| s.trim().parse::<u16>().ok())
+                .collect()
+        })
+    }
+}
+
 impl CommonArgs {
+    /// Concurrency for DNS resolution, which falls back to `--threads`
+    pub fn get_dns_threads(&self) -> usize {
+        self.dns_threads.unwrap_or(self.threads)
+    }
+
     pub fn get_status_codes(&self) -> Vec<u16> {
         self.status_codes
             .split(',')
@@ -202,21 +563,212 @@ impl CommonArgs {
             .unwrap_or_default()
     }
 
-    pub fn get_extensions(&self, extensions_arg: &Option<String>) -> Vec<String> {
-        extensions_arg
-            .as_ref()
-            .map(|exts| {
-                exts.split(',')
-                    .map(|s| {
-                        let trimmed = s.trim();
-                        if trimmed.starts_with('.') {
-                            trimmed.to_string()
-                        } else {
-                            format!(".{}", trimmed)
-                        }
-                    })
-                    .collect()
+    pub fn get_headers(&self) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .filter_map(|h| {
+                let parts: Vec<&str> = h.splitn(2, ':').collect();
+                if parts.len() == 2 {
+                    Some((parts[0].trim().to_string(), parts[1].trim().to_string()))
+                } else {
+                    None
+                }
             })
-            .unwrap_or_default()
+            .collect()
+    }
+
+    /// Header names from `--show-headers`, trimmed and without case-insensitive repeats
+    pub fn get_show_headers(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for name in self.show_headers.iter().map(|name| name.trim()).filter(|name| !name.is_empty()) {
+            if !names.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    /// The -w files for display, joined with commas
+    pub fn wordlist_label(&self) -> String {
+        self.wordlist.join(", ")
+    }
+
+    /// Accept and Content-Type from `--accept`, `--content-type` and `--json`, sent unless -H sets them
+    pub fn get_default_headers(&self) -> Vec<(&'static str, String)> {
+        let json = self.json.then(|| "application/json".to_string());
+        let mut headers = Vec::new();
+        if let Some(accept) = self.accept.clone().or_else(|| json.clone()) {
+            headers.push(("Accept", accept));
+        }
+        if let Some(content_type) = self.content_type.clone().or(json) {
+            headers.push(("Content-Type", content_type));
+        }
+        headers
+    }
+
+    /// The Authorization value built from `--basic-auth` or `--bearer`, which may not be
+    /// combined with an Authorization header given through -H
+    pub fn get_authorization(&self) -> Result<Option<String>> {
+        let value = match (&self.basic_auth, &self.bearer) {
+            (Some(credentials), _) => {
+                if !credentials.contains(':') {
+                    anyhow::bail!("--basic-auth expects USER:PASS");
+                }
+                format!("Basic {}", BASE64_STANDARD.encode(credentials))
+            }
+            (None, Some(token)) => format!("Bearer {}", token),
+            (None, None) => return Ok(None),
+        };
+
+        if self.get_headers().iter().any(|(key, _)| key.eq_ignore_ascii_case("Authorization")) {
+            anyhow::bail!("--basic-auth and --bearer cannot be combined with an Authorization header from -H");
+        }
+        Ok(Some(value))
+    }
+
+    pub fn get_data(&self) -> Result<Option<String>> {
+        if let Some(path) = &self.data_file {
+            let body = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read data file: {}", path))?;
+            return Ok(Some(body));
+        }
+        Ok(self.data.clone())
+    }
+
+    /// Extensions from `-x` followed by those in `--extensions-file`, each with a leading dot
+    pub fn get_extensions(
+        &self,
+        extensions_arg: &Option<String>,
+        extensions_file: &Option<String>,
+    ) -> Result<Vec<String>> {
+        let mut raw: Vec<String> = extensions_arg
+            .as_ref()
+            .map(|exts| exts.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+
+        if let Some(path) = extensions_file {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read extensions file: {}", path))?;
+            raw.extend(content.lines().map(str::to_string));
+        }
+
+        let mut extensions: Vec<String> = Vec::new();
+        for ext in raw {
+            let trimmed = ext.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let ext = if trimmed.starts_with('.') {
+                trimmed.to_string()
+            } else {
+                format!(".{}", trimmed)
+            };
+            if !extensions.contains(&ext) {
+                extensions.push(ext);
+            }
+        }
+
+        Ok(extensions)
+    }
+}
+
+/// Parses an `--ext-status` rule such as `.bak:200,403` into a normalized extension and its codes
+fn parse_ext_status(value: &str) -> std::result::Result<(String, Vec<u16>), String> {
+    let (ext, codes) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected EXT:CODES (e.g. .bak:200,403), got '{}'", value))?;
+
+    let ext = ext.trim().trim_start_matches('.').to_lowercase();
+    if ext.is_empty() {
+        return Err(format!("missing extension in '{}'", value));
+    }
+
+    let codes = codes
+        .split(',')
+        .map(|code| {
+            code.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("invalid status code '{}' in '{}'", code.trim(), value))
+        })
+        .collect::<std::result::Result<Vec<u16>, String>>()?;
+
+    Ok((format!(".{}", ext), codes))
+}
+
+/// Parses a ratio between 0.0 and 1.0
+fn parse_ratio(value: &str) -> std::result::Result<f32, String> {
+    let ratio: f32 = value
+        .parse()
+        .map_err(|_| format!("'{}' is not a number", value))?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(format!("{} is not between 0.0 and 1.0", ratio));
+    }
+    Ok(ratio)
+}
+
+/// Warns that `http://` was assumed for a `-u` target typed without a scheme; done after
+/// parsing, since a value parser has no business printing
+pub fn warn_missing_scheme(matches: &ArgMatches) {
+    let Ok(Some(mut raw)) = matches.try_get_raw("url") else {
+        return;
+    };
+    if let Some(value) = raw.next().and_then(|value| value.to_str()) {
+        if !value.contains("://") {
+            eprintln!("[!] No scheme in '{}', assuming http://{}", value, value);
+        }
+    }
+}
+
+/// Checks a `-u` target, assuming `http://` when no scheme is given and rejecting anything
+/// other than http and https. The text is otherwise kept as typed so FUZZ markers survive.
+fn parse_target_url(value: &str) -> std::result::Result<String, String> {
+    let url = if value.contains("://") {
+        value.to_string()
+    } else {
+        format!("http://{}", value)
+    };
+
+    let parsed = url::Url::parse(&url).map_err(|e| format!("'{}' is not a valid URL: {}", value, e))?;
+    match parsed.scheme() {
+        "http" | "https" => {}
+        scheme => {
+            return Err(format!(
+                "unsupported scheme '{}://'; targets must start with http:// or https://",
+                scheme
+            ))
+        }
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(format!("'{}' has no host", value));
+    }
+    Ok(url)
+}
+
+/// Parses a `--random-delay` range such as `100-500` (milliseconds)
+fn parse_delay_range(value: &str) -> std::result::Result<(u64, u64), String> {
+    let (min, max) = value
+        .split_once('-')
+        .ok_or_else(|| format!("expected MIN-MAX in milliseconds (e.g. 100-500), got '{}'", value))?;
+    let min: u64 = min.trim().parse().map_err(|_| format!("invalid minimum '{}'", min.trim()))?;
+    let max: u64 = max.trim().parse().map_err(|_| format!("invalid maximum '{}'", max.trim()))?;
+
+    if min > max {
+        return Err(format!("minimum {} is larger than maximum {}", min, max));
+    }
+    Ok((min, max))
+}
+
+/// Parses a `--shard` such as `2/5`: the second of five shards
+fn parse_shard(value: &str) -> std::result::Result<(usize, usize), String> {
+    let (index, count) = value
+        .split_once('/')
+        .ok_or_else(|| format!("expected I/N (e.g. 2/5), got '{}'", value))?;
+    let index: usize = index.trim().parse().map_err(|_| format!("invalid shard '{}'", index.trim()))?;
+    let count: usize = count.trim().parse().map_err(|_| format!("invalid shard count '{}'", count.trim()))?;
+
+    if index < 1 || index > count {
+        return Err(format!("shard {} must be between 1 and {}", index, count));
     }
+    Ok((index, count))
 }
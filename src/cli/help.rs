@@ -1,85 +1,180 @@
-use ansi_term::Style;
+use colored::Colorize;
 
 pub fn print_arguments_help() {
-    println!("\n{}", Style::new().bold().paint("Rustbuster - ALL ARGUMENTS"));
+    println!("\n{}", "Rustbuster - ALL ARGUMENTS".bold());
     println!("═══════════════════════════════════════════════════════════════════════════════\n");
 
     print_section("CORE OPTIONS", vec![
-        ("-w, --wordlist <FILE>", "Path to wordlist file (one entry per line)"),
+        ("-w, --wordlist <FILE>", "Path to wordlist file (one entry per line); repeat to combine several"),
+        ("--wordlist-intersect", "Keep only words found in every -w file (default: all words, once each)"),
+        ("--unique", "Drop repeated words, keeping the first occurrence"),
+        ("--sort", "Sort the words alphabetically before scanning"),
+        ("--wordlist-offset <N>", "Skip the first N words of the final wordlist, to split a scan across machines"),
+        ("--wordlist-limit <N>", "Use at most N words (after --wordlist-offset)"),
+        ("--shard <I/N>", "Scan only every N-th word starting at the I-th, e.g. 2/5 on the second of five machines"),
+        ("--wordlist-mutate <MUTATIONS>", "Add word variants: case (lower, upper and capitalized)"),
+        ("--prefix <TEXT>", "Put TEXT before every word (repeatable)"),
+        ("--suffix <TEXT>", "Put TEXT after every word (repeatable)"),
         ("-t, --threads <NUM>", "Number of concurrent threads (default: 10)"),
+        ("--dns-threads <NUM>", "Concurrent DNS lookups, separate from HTTP threads (default: --threads)"),
         ("--timeout <SECS>", "HTTP request timeout in seconds (default: 10)"),
+        ("--connect-timeout <SECS>", "Time allowed to establish a connection (default: --timeout)"),
+        ("--http2-only", "Speak HTTP/2 without negotiating (prior knowledge)"),
+        ("--http1-only", "Never use HTTP/2"),
+        ("--pool-max-idle <NUM>", "Idle connections kept per host (default: unlimited)"),
+        ("--no-compression", "Don't request or decode gzip/brotli/deflate; sizes are raw bytes"),
+        ("--max-body-size <BYTES>", "Stop reading response bodies past this size (default: 1048576)"),
+        ("--include-body", "Store each hit's response body (up to --max-body-size) in json/jsonl output"),
+        ("--config <FILE>", "Config file to use instead of ~/.rustbuster.toml (env: RUSTBUSTER_CONFIG)"),
+        ("--no-color", "Never color output; also off with NO_COLOR set or when stdout is not a terminal"),
     ]);
 
     print_section("STATUS CODE FILTERING", vec![
         ("-s, --status-codes <CODES>", "Positive status codes to report (default: 200,204,301,302,307,401,403)"),
         ("-n, --negative-status-codes <CODES>", "Negative status codes to exclude"),
+        ("--normalize-status", "Filter and count by status family (2xx/3xx/4xx/5xx)"),
+        ("--ext-status <EXT:CODES>...", "Status codes to report for one extension (e.g. .bak:200,403 .php:200)"),
+        ("--limit-per-status <N>", "Show at most N results per status; the rest are only counted"),
+        ("--notify-on <CODES>", "Ring the terminal bell on the first result with each of these statuses"),
+        ("--on-hit <CMD>", "Run CMD for every hit (dir/fuzz), with {url}, {status} and {size} filled in"),
+        ("", "Its output goes to stdout, so hits can be piped into another tool"),
     ]);
 
     print_section("HTTP OPTIONS", vec![
         ("-r, --follow-redirects", "Follow HTTP redirects (3xx responses)"),
+        ("--max-redirects <N>", "Stop following after N hops and count the request as failed (default: 10)"),
         ("-a, --user-agent <STRING>", "User-Agent string (default: rustbuster/0.1.0)"),
         ("--user-agents-file <FILE>", "File with multiple User-Agents for rotation"),
         ("--method <METHOD>", "HTTP method (default: GET)"),
+        ("--spoof-ip", "Send a random X-Forwarded-For/X-Real-IP/X-Originating-IP per request"),
+        ("--spoof-ip-file <FILE>", "Rotate the spoofed IP through a file, one per line"),
+        ("--cookies-file <FILE>", "Rotate cookie strings from a file, one per line (-c takes precedence)"),
         ("-c, --cookies <STRING>", "Cookies to send (format: \"name1=value1; name2=value2\")"),
+        ("--cookie-jar", "Keep cookies set by responses and send them back, including across redirects"),
         ("-H, --headers <HEADER>", "Custom HTTP headers (can be used multiple times)"),
+        ("--basic-auth <USER:PASS>", "Send HTTP basic authentication"),
+        ("--bearer <TOKEN>", "Send an Authorization: Bearer token"),
+        ("--json", "Send Accept and Content-Type: application/json"),
+        ("--accept <VALUE>", "Accept header to send (overrides --json; -H wins)"),
+        ("--content-type <VALUE>", "Content-Type header to send (overrides --json; -H wins)"),
+        ("--data <BODY>", "Request body to send (FUZZ is replaced in fuzz mode)"),
+        ("--data-file <FILE>", "Read the request body from a file"),
     ]);
 
     print_section("PROXY & TLS OPTIONS", vec![
         ("-p, --proxy <URL>", "Proxy URL (HTTP/HTTPS/SOCKS4/SOCKS5)"),
+        ("--skip-preflight", "Skip the proxy reachability check before scanning"),
+        ("--skip-check", "Skip the target connectivity check before scanning (also skipped with -q)"),
         ("--no-tls-validation", "Skip TLS certificate validation"),
+        ("--client-cert <FILE>", "PEM client certificate for mutual TLS (needs --client-key)"),
+        ("--client-key <FILE>", "PEM PKCS#8 private key for --client-cert"),
+        ("--ipv4-only", "Only connect over IPv4, ignoring AAAA records"),
+        ("--ipv6-only", "Only connect over IPv6, ignoring A records"),
+        ("--interface <IP>", "Send requests from this local address (also limits to its family)"),
     ]);
 
     print_section("OUTPUT OPTIONS", vec![
         ("-e, --expanded", "Show all responses including negative status codes"),
         ("-q, --quiet", "Suppress banner and reduce output verbosity"),
         ("-v, --verbose", "Show detailed errors and debug output"),
+        ("--show-headers <LIST>", "Capture these response headers and show them (e.g. X-Powered-By,Via)"),
+        ("--fingerprint", "Tag hits with detected technologies (server, framework, CMS)"),
+        ("--fingerprint-rules <FILE>", "Add fingerprint rules from a TOML or JSON file (implies --fingerprint)"),
         ("--no-progress", "Disable progress bar display"),
         ("-o, --output <FILE>", "Save results to output file"),
-        ("--output-format <FORMAT>", "Output format: plain, json, csv (default: plain)"),
+        ("--output-format <FORMAT>", "Output format: plain, json, jsonl, csv (default: plain)"),
+        ("--json-metadata", "Wrap JSON results in an object with scan metadata"),
+        ("--output-append", "Add to an existing output file instead of replacing it"),
+        ("--summary-line", "Print a one-line plain summary at the end, for chat"),
     ]);
 
     print_section("FILTERING OPTIONS", vec![
-        ("--wildcard", "Force continue on wildcard responses"),
+        ("--wildcard", "Keep results matching the wildcard response instead of filtering them"),
+        ("--url-only, --raw", "Print only the URL of each hit, one per line, for piping (implies -q)"),
         ("--filter-regex <REGEX>", "Filter responses by regex pattern (exclude matches)"),
         ("--match-regex <REGEX>", "Match responses by regex pattern (only show matches)"),
-        ("--filter-size <SIZES>", "Filter responses by content length (comma-separated)"),
+        ("--filter-size <SIZES>", "Filter responses by content length (decoded size unless --no-compression)"),
+        ("--auto-filter [N]", "Hide the most common size among the first N results (default: 50)"),
+        ("--min-time <MS>", "Only show responses that took at least MS milliseconds"),
+        ("--max-time <MS>", "Only show responses that took at most MS milliseconds"),
+        ("--only-dirs", "Only show directories: paths ending in / or redirects to one"),
+        ("--only-files", "Only show results that are not directories"),
     ]);
 
     print_section("RATE LIMITING", vec![
         ("--delay <MS>", "Delay between requests in milliseconds"),
+        ("--random-delay <MIN-MAX>", "Random delay in this range (ms) before each request"),
+        ("--rate-limit <RPS>", "Cap total requests per second across all threads (overrides --delay and --random-delay)"),
+        ("--max-errors <N>", "Abort the scan after N failed requests, keeping the results so far"),
+        ("--max-consecutive-errors <N>", "Abort the scan after N failed requests in a row"),
     ]);
 
     print_section("SESSION MANAGEMENT", vec![
         ("--save-session <NAME>", "Save scan session to resume later"),
         ("--resume-session <NAME>", "Resume a previously saved session"),
+        ("--session-dir <DIR>", "Keep sessions in DIR instead of ~/.rustbuster/sessions (also for sessions)"),
+        ("--resume-state <FILE>", "Checkpoint progress to FILE every few seconds and skip requests it lists as done"),
+        ("--delta-output <FILE>", "Append only findings new to the session to this file"),
+        ("sessions list", "Show saved sessions with their target, progress and last update"),
+        ("sessions delete <NAME>", "Remove a saved session"),
+        ("diff <OLD> <NEW>", "Compare two json result files: added, removed and changed URLs"),
     ]);
 
     print_section("ADVANCED FEATURES", vec![
         ("--smart-404", "Enable smart 404 detection"),
+        ("--probe-methods", "Send OPTIONS to each finding and show its Allow header"),
+        ("--head-first", "Probe with HEAD and only GET when the body is needed or HEAD is refused"),
         ("--targets <FILE>", "File with multiple target URLs/domains"),
-        ("--report <FILE>", "Generate professional HTML report"),
-        ("--similarity-threshold <FLOAT>", "Response similarity detection (0.0-1.0)"),
+        ("--report <FILE>", "Generate an HTML or Markdown (.md) report"),
+        ("--report-format <FORMAT>", "Report format: html, md (default: from the file extension)"),
+        ("--similarity-threshold <FLOAT>", "Drop 200s this similar to the soft-404 baseline (0.0-1.0)"),
+        ("--golden-dir <DIR>", "Store response bodies and flag changes on later runs"),
+        ("--save-bodies <DIR>", "Save the response body of each finding to this directory"),
+        ("--show-timing", "Show time to first byte and total time in the TUI"),
+        ("--fail-on-findings", "Exit with status 2 when anything is found (0 otherwise, 1 on errors)"),
     ]);
 
     print_section("MODE-SPECIFIC OPTIONS", vec![
-        ("", &format!("{}", Style::new().bold().paint("DIR MODE:"))),
+        ("", &format!("{}", "DIR MODE:".bold())),
         ("  -u, --url <URL>", "Target base URL to scan"),
         ("  -x, --extensions <EXTS>", "File extensions (comma-separated)"),
+        ("  --extensions-file <FILE>", "Load extensions from a file, one per line (merged with -x)"),
         ("  -R, --recursive", "Enable recursive scanning"),
         ("  --depth <NUM>", "Maximum recursion depth (default: 3)"),
+        ("  --seed-urls <FILE>", "Start recursion from known directories instead of the root"),
+        ("  --per-dir-timeout <SECS>", "Move on when a single directory exceeds this time budget"),
+        ("  --stop-on-first", "Stop scanning a directory at its first hit, then recurse as usual"),
+        ("  --recurse-on-status <CODES>", "Statuses that trigger recursion on directory-like hits"),
         ("  --backup-extensions", "Try common backup file extensions"),
+        ("  --no-bare-word", "Only request words with an extension added (admin.bak, not admin)"),
+        ("  --add-slash", "Also request each word with a trailing slash (/admin and /admin/)"),
+        ("  --slash-only", "Only request the trailing-slash form of each word"),
         ("", ""),
-        ("", &format!("{}", Style::new().bold().paint("DNS MODE:"))),
+        ("", &format!("{}", "DNS MODE:".bold())),
         ("  -d, --domain <DOMAIN>", "Target domain to enumerate"),
         ("  --show-cname", "Display CNAME records"),
         ("  --show-ips", "Display resolved IP addresses"),
+        ("  --force", "Scan even if the base domain does not resolve"),
+        ("  --resolver <IP[,IP...]>", "Nameservers to query instead of the system resolver (IP or IP:port)"),
+        ("  --resolvers-file <FILE>", "Load nameservers from a file, one per line"),
+        ("  --doh <URL>", "Resolve over DNS-over-HTTPS (e.g. https://cloudflare-dns.com/dns-query)"),
+        ("  --record-type <TYPE>", "Record type to look up: A, AAAA, MX, TXT, NS (default: A/AAAA)"),
         ("", ""),
-        ("", &format!("{}", Style::new().bold().paint("VHOST MODE:"))),
+        ("", &format!("{}", "VHOST MODE:".bold())),
         ("  -u, --url <URL>", "Target URL to test virtual hosts"),
+        ("  --no-baseline-filter", "Report vhosts matching the default (unknown Host) response"),
         ("", ""),
-        ("", &format!("{}", Style::new().bold().paint("FUZZ MODE:"))),
-        ("  -u, --url <URL>", "Target URL with FUZZ keyword(s)"),
+        ("", &format!("{}", "FUZZ MODE:".bold())),
+        ("  -u, --url <URL>", "Target URL with FUZZ keyword(s); FUZZ also works in --data, -H and -c"),
         ("  -x, --extensions <EXTS>", "File extensions (comma-separated)"),
+        ("  --extensions-file <FILE>", "Load extensions from a file, one per line (merged with -x)"),
+        ("  --no-bare-word", "Only substitute FUZZ words with an extension added"),
+        ("  --fuzz-wordlist <KEY=FILE>", "Bind another keyword (e.g. FUZZ2) to its own wordlist"),
+        ("  --fuzz-mode <MODE>", "clusterbomb: every combination, pitchfork: pair lines (default: clusterbomb)"),
+        ("", "Combinations past 5,000,000 requests are refused"),
+        ("  -R, --recursive", "Re-fuzz the last path segment inside each discovered directory"),
+        ("  --depth <NUM>", "Maximum recursion depth (default: 3)"),
+        ("  --stop-on-first", "Stop fuzzing a directory at its first hit, then recurse as usual"),
     ]);
 
     println!("TIP: Use 'rustbuster <MODE> --help' for mode-specific help");
@@ -88,15 +183,19 @@ pub fn print_arguments_help() {
 }
 
 pub fn print_examples() {
-    println!("\n{}", Style::new().bold().paint("rustbuster - USAGE EXAMPLES"));
+    println!("\n{}", "rustbuster - USAGE EXAMPLES".bold());
     println!("═══════════════════════════════════════════════════════════════════════════════\n");
 
     print_example_section("DIRECTORY ENUMERATION", vec![
         ("Basic scan", "rustbuster dir -u http://example.com -w wordlist.txt"),
         ("With extensions", "rustbuster dir -u http://example.com -w wordlist.txt -x php,html,txt"),
         ("Recursive scan", "rustbuster dir -u http://example.com -w wordlist.txt -R --depth 3"),
+        ("Recurse into indexes", "rustbuster dir -u http://example.com -w wordlist.txt -R --recurse-on-status 200,301,403"),
+        ("Seeded recursion", "rustbuster dir -u http://example.com -w wordlist.txt -R --seed-urls known_dirs.txt"),
         ("Find backups", "rustbuster dir -u http://example.com -w wordlist.txt --backup-extensions"),
-        ("With auth", "rustbuster dir -u http://example.com -w wordlist.txt -H \"Authorization: Bearer TOKEN\""),
+        ("With auth", "rustbuster dir -u http://example.com -w wordlist.txt --bearer TOKEN"),
+        ("Basic auth", "rustbuster dir -u http://example.com -w wordlist.txt --basic-auth admin:secret"),
+        ("JSON API", "rustbuster fuzz -u http://api.example.com/v1/FUZZ -w endpoints.txt --json"),
         ("Through proxy", "rustbuster dir -u http://example.com -w wordlist.txt -p http://127.0.0.1:8080"),
     ]);
 
@@ -104,6 +203,7 @@ pub fn print_examples() {
         ("Basic scan", "rustbuster dns -d example.com -w subdomains.txt"),
         ("Show IPs", "rustbuster dns -d example.com -w subdomains.txt --show-ips"),
         ("Show all info", "rustbuster dns -d example.com -w subdomains.txt --show-ips --show-cname"),
+        ("Mail servers", "rustbuster dns -d example.com -w subdomains.txt --record-type MX"),
     ]);
 
     print_example_section("VIRTUAL HOST DISCOVERY", vec![
@@ -116,7 +216,12 @@ pub fn print_examples() {
         ("Basic fuzz", "rustbuster fuzz -u http://example.com/FUZZ -w wordlist.txt"),
         ("API fuzzing", "rustbuster fuzz -u http://example.com/api/FUZZ -w params.txt"),
         ("With extensions", "rustbuster fuzz -u http://example.com/FUZZ -w wordlist.txt -x json,xml"),
+        ("Recursive fuzz", "rustbuster fuzz -u http://example.com/FUZZ -w wordlist.txt -R --depth 2"),
         ("Multiple FUZZ", "rustbuster fuzz -u http://example.com/FUZZ/FUZZ -w wordlist.txt"),
+        ("Cluster bomb", "rustbuster fuzz -u http://example.com/FUZZ?id=FUZZ2 -w paths.txt --fuzz-wordlist FUZZ2=ids.txt"),
+        ("Pitchfork", "rustbuster fuzz -u http://example.com/login?u=USER&p=PASS --fuzz-wordlist USER=users.txt --fuzz-wordlist PASS=pass.txt --fuzz-mode pitchfork"),
+        ("Header fuzzing", "rustbuster fuzz -u http://example.com/api -w keys.txt -H \"X-Api-Key: FUZZ\""),
+        ("POST body", "rustbuster fuzz -u http://example.com/api/search -w params.txt --method POST --data '{\"q\":\"FUZZ\"}'"),
     ]);
 
     print_example_section("PROXY USAGE", vec![
@@ -129,13 +234,19 @@ pub fn print_examples() {
     print_example_section("SESSION MANAGEMENT", vec![
         ("Save session", "rustbuster dir -u http://example.com -w wordlist.txt --save-session scan1"),
         ("Resume session", "rustbuster dir --resume-session scan1"),
+        ("Resume, keep new hits", "rustbuster dir -u http://example.com -w wordlist.txt --resume-session scan1 --delta-output new.txt"),
+        ("List sessions", "rustbuster sessions list"),
+        ("Delete session", "rustbuster sessions delete scan1"),
+        ("Compare two scans", "rustbuster diff january.json february.json"),
     ]);
 
     print_example_section("ADVANCED FEATURES", vec![
         ("Multi-target", "rustbuster dir -w wordlist.txt --targets targets.txt"),
         ("Smart 404", "rustbuster dir -u http://example.com -w wordlist.txt --smart-404"),
         ("HTML report", "rustbuster dir -u http://example.com -w wordlist.txt --report report.html"),
+        ("Markdown report", "rustbuster dir -u http://example.com -w wordlist.txt --report findings.md"),
         ("Rate limiting", "rustbuster dir -u http://example.com -w wordlist.txt --delay 100"),
+        ("Global rate limit", "rustbuster dir -u http://example.com -w wordlist.txt -t 50 --rate-limit 20"),
         ("User-Agent rotation", "rustbuster dir -u http://example.com -w wordlist.txt --user-agents-file ua.txt"),
         ("Response filtering", "rustbuster dir -u http://example.com -w wordlist.txt --filter-size 1234 --match-regex \"admin\""),
     ]);
@@ -143,6 +254,7 @@ pub fn print_examples() {
     print_example_section("OUTPUT FORMATS", vec![
         ("JSON output", "rustbuster dir -u http://example.com -w wordlist.txt -o results.json --output-format json"),
         ("CSV output", "rustbuster dir -u http://example.com -w wordlist.txt -o results.csv --output-format csv"),
+        ("JSON Lines output", "rustbuster dir -u http://example.com -w wordlist.txt -o results.jsonl --output-format jsonl"),
         ("Quiet mode", "rustbuster dir -u http://example.com -w wordlist.txt -q -o results.txt"),
         ("Verbose mode", "rustbuster dir -u http://example.com -w wordlist.txt -v"),
     ]);
@@ -152,7 +264,7 @@ pub fn print_examples() {
 }
 
 pub fn print_info() {
-    println!("\n{}", Style::new().bold().paint("rustbuster - ADDITIONAL INFORMATION"));
+    println!("\n{}", "rustbuster - ADDITIONAL INFORMATION".bold());
     println!("═══════════════════════════════════════════════════════════════════════════════\n");
 
     print_section("ABOUT", vec![
@@ -183,6 +295,16 @@ pub fn print_info() {
         ("", "  • SecLists: https://github.com/danielmiessler/SecLists"),
         ("", "  • FuzzDB: https://github.com/fuzzdb-project/fuzzdb"),
         ("", "  • Assetnote: https://wordlists.assetnote.io/"),
+        ("", "Gzipped wordlists are read directly. Words containing %EXT% get each"),
+        ("", "-x extension in that spot instead of at the end (e.g. config.%EXT%.bak)."),
+    ]);
+
+    print_section("CONFIG FILE", vec![
+        ("", "~/.rustbuster.toml supplies defaults for options left off the command line;"),
+        ("", "--config <FILE> or RUSTBUSTER_CONFIG points at another file instead."),
+        ("", "Keys: default_threads, default_timeout, default_user_agent, default_wordlist, proxy,"),
+        ("", "default_status_codes, default_headers, default_output_format, default_extensions."),
+        ("", "The same keys under [dir], [dns], [vhost] or [fuzz] apply to that mode only."),
     ]);
 
     print_section("PERFORMANCE TIPS", vec![
@@ -211,7 +333,7 @@ pub fn print_info() {
 }
 
 fn print_section(title: &str, items: Vec<(&str, &str)>) {
-    println!("{}", Style::new().bold().paint(title));
+    println!("{}", title.bold());
     println!("───────────────────────────────────────────────────────────────────────────────");
     for (flag, desc) in items {
         if flag.is_empty() {
@@ -228,7 +350,7 @@ fn print_section(title: &str, items: Vec<(&str, &str)>) {
 }
 
 fn print_example_section(title: &str, examples: Vec<(&str, &str)>) {
-    println!("{}", Style::new().bold().paint(title));
+    println!("{}", title.bold());
     println!("───────────────────────────────────────────────────────────────────────────────");
     for (desc, cmd) in examples {
         println!("  → {}", desc);
@@ -1,3 +1,3 @@
 mod args;
 pub mod help;
-pub use args::{Cli, Commands, CommonArgs, DirArgs, DnsArgs, VhostArgs, FuzzArgs};
+pub use args::{Cli, Commands, CommonArgs, DiffArgs, DirArgs, DnsArgs, VhostArgs, FuzzArgs, SessionsAction, SessionsArgs, warn_missing_scheme};
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Learns the most common response size from the first results and hides it afterwards
+///
+/// The first `sample` results are always shown. If one size is more common among them
+/// than any other and appears more than once, later results of that size are dropped.
+pub struct AutoFilter {
+    sample: usize,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    sizes: HashMap<u64, usize>,
+    sampled: usize,
+    size: Option<u64>,
+    filtered: usize,
+}
+
+impl AutoFilter {
+    pub fn new(sample: usize) -> Self {
+        AutoFilter {
+            sample,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Feeds one result's size and tells whether it should be hidden
+    pub fn filters(&self, content_length: u64) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+
+        if state.sampled < self.sample {
+            state.sampled += 1;
+            *state.sizes.entry(content_length).or_insert(0) += 1;
+            if state.sampled == self.sample {
+                state.size = most_common(&state.sizes);
+            }
+            return false;
+        }
+
+        let hide = state.size == Some(content_length);
+        if hide {
+            state.filtered += 1;
+        }
+        hide
+    }
+
+    /// The size picked after warmup and how many results it has hidden
+    pub fn filtered(&self) -> Option<(u64, usize)> {
+        let state = self.state.lock().ok()?;
+        state.size.map(|size| (size, state.filtered))
+    }
+}
+
+/// The size seen most often, unless another ties with it or it was only seen once
+fn most_common(sizes: &HashMap<u64, usize>) -> Option<u64> {
+    let mut counts: Vec<(u64, usize)> = sizes.iter().map(|(size, count)| (*size, *count)).collect();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    match counts.as_slice() {
+        [(size, count), rest @ ..] if *count > 1 && rest.first().is_none_or(|(_, next)| next < count) => {
+            Some(*size)
+        }
+        _ => None,
+    }
+}
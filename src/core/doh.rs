@@ -0,0 +1,57 @@
+use crate::core::HttpClient;
+use anyhow::{anyhow, Context, Result};
+use trust_dns_resolver::proto::rr::RecordType;
+use url::Url;
+
+/// Resolves names over DNS-over-HTTPS using the JSON API of Cloudflare, Google and others
+#[derive(Clone)]
+pub struct DohResolver {
+    client: HttpClient,
+    endpoint: Url,
+}
+
+impl DohResolver {
+    pub fn new(client: HttpClient, endpoint: &str) -> Result<Self> {
+        let endpoint = Url::parse(endpoint).with_context(|| format!("Invalid DoH URL: {}", endpoint))?;
+        Ok(DohResolver { client, endpoint })
+    }
+
+    /// Returns the answers of one record type, failing like the system resolver when there are none
+    pub async fn lookup(&self, name: &str, record_type: RecordType) -> Result<Vec<String>> {
+        let mut url = self.endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("name", name)
+            .append_pair("type", &record_type.to_string());
+
+        let headers = [("Accept".to_string(), "application/dns-json".to_string())];
+        let response = self.client.request(url.as_str(), "GET", &headers, None, None).await?;
+        let reply: serde_json::Value = response
+            .json()
+            .await
+            .with_context(|| format!("Invalid DoH response for {}", name))?;
+
+        let status = reply["Status"].as_u64().unwrap_or(2);
+        if status != 0 {
+            return Err(anyhow!("DoH lookup of {} failed with rcode {}", name, status));
+        }
+
+        let wanted = u16::from(record_type) as u64;
+        let records: Vec<String> = reply["Answer"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|answer| answer["type"].as_u64() == Some(wanted))
+            .filter_map(|answer| answer["data"].as_str())
+            .map(|data| match record_type {
+                // Some providers leave TXT data unquoted
+                RecordType::TXT if !data.starts_with('"') => format!("\"{}\"", data),
+                _ => data.to_string(),
+            })
+            .collect();
+
+        if records.is_empty() {
+            return Err(anyhow!("No {} records found for {}", record_type, name));
+        }
+        Ok(records)
+    }
+}
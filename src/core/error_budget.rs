@@ -0,0 +1,79 @@
+use crate::cli::CommonArgs;
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Stops a scan once too many requests fail, so a target that went down is not hammered
+///
+/// `--max-errors` counts every failure, `--max-consecutive-errors` only an unbroken run of
+/// them. Workers check `is_exhausted` before each request and skip the rest once it trips.
+#[derive(Default)]
+pub struct ErrorBudget {
+    max_total: Option<usize>,
+    max_consecutive: Option<usize>,
+    total: AtomicUsize,
+    consecutive: AtomicUsize,
+    exhausted: AtomicBool,
+}
+
+impl ErrorBudget {
+    pub fn new(max_total: Option<usize>, max_consecutive: Option<usize>) -> Self {
+        ErrorBudget {
+            max_total,
+            max_consecutive,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_common(common: &CommonArgs) -> Self {
+        Self::new(
+            common.max_errors.map(|max| max as usize),
+            common.max_consecutive_errors.map(|max| max as usize),
+        )
+    }
+
+    /// Counts a failed request, tripping the budget when a limit is reached
+    pub fn record_error(&self) {
+        let total = self.total.fetch_add(1, Ordering::SeqCst) + 1;
+        let consecutive = self.consecutive.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.max_total.is_some_and(|max| total >= max)
+            || self.max_consecutive.is_some_and(|max| consecutive >= max)
+        {
+            self.exhausted.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Ends a run of consecutive failures
+    pub fn record_success(&self) {
+        self.consecutive.store(0, Ordering::SeqCst);
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted.load(Ordering::SeqCst)
+    }
+
+    pub fn errors(&self) -> usize {
+        self.total.load(Ordering::SeqCst)
+    }
+
+    /// Fails with the limit that stopped the scan, once partial results have been written
+    pub fn check(&self) -> Result<()> {
+        if !self.is_exhausted() {
+            return Ok(());
+        }
+
+        let total = self.errors();
+        match (self.max_total, self.max_consecutive) {
+            (Some(max), _) if total >= max => Err(anyhow!(
+                "Scan aborted after {} failed requests (--max-errors {}); results so far were kept",
+                total,
+                max
+            )),
+            (_, Some(max)) => Err(anyhow!(
+                "Scan aborted after {} consecutive failed requests (--max-consecutive-errors {}); results so far were kept",
+                max,
+                max
+            )),
+            _ => Err(anyhow!("Scan aborted after {} failed requests", total)),
+        }
+    }
+}
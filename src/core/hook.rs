@@ -0,0 +1,148 @@
+//! `--on-hit`: runs an external command for every hit, e.g. to hand it to another tool.
+//!
+//! The command is split into arguments without a shell, and `{url}`, `{status}` and `{size}`
+//! are substituted inside each argument, so a URL never needs quoting. Commands run in the
+//! background, at most `MAX_RUNNING_HOOKS` at a time, while the scan goes on.
+
+use crate::cli::CommonArgs;
+use crate::core::http_client::ScanResult;
+use crate::utils::template::substitute;
+use anyhow::{bail, Result};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Most hook commands running at once; later hits wait for a free slot
+pub const MAX_RUNNING_HOOKS: usize = 4;
+
+pub struct HitHook {
+    /// Program and arguments, placeholders still in place
+    argv: Vec<String>,
+    verbose: bool,
+    slots: Arc<Semaphore>,
+    running: Mutex<JoinSet<()>>,
+}
+
+impl HitHook {
+    pub fn new(command: &str, verbose: bool) -> Result<Self> {
+        let argv = split_command(command)?;
+        if argv.is_empty() {
+            bail!("--on-hit needs a command to run");
+        }
+
+        Ok(HitHook {
+            argv,
+            verbose,
+            slots: Arc::new(Semaphore::new(MAX_RUNNING_HOOKS)),
+            running: Mutex::new(JoinSet::new()),
+        })
+    }
+
+    pub fn from_common(common: &CommonArgs) -> Result<Option<Self>> {
+        common
+            .on_hit
+            .as_deref()
+            .map(|command| HitHook::new(command, common.verbose))
+            .transpose()
+    }
+
+    /// The program and arguments run for `result`
+    ///
+    /// Placeholders are filled in one pass, so a URL holding the text `{size}` stays as it is.
+    pub fn command_for(&self, result: &ScanResult) -> Vec<String> {
+        let status = result.status_code.to_string();
+        let size = result.content_length.to_string();
+        let bindings = [("{url}", result.url.as_str()), ("{status}", &status), ("{size}", &size)];
+        self.argv.iter().map(|arg| substitute(arg, &bindings)).collect()
+    }
+
+    /// Starts the command for a hit without waiting for it
+    ///
+    /// The command writes to rustbuster's own stdout, so its output can be piped on.
+    /// Failures to start are always reported; non-zero exits only with `--verbose`.
+    pub fn spawn(&self, result: &ScanResult) {
+        let argv = self.command_for(result);
+        let url = result.url.clone();
+        let slots = Arc::clone(&self.slots);
+        let verbose = self.verbose;
+
+        let mut running = self.running.lock().unwrap();
+        // Drop finished commands so a long scan does not keep one entry per hit
+        while running.try_join_next().is_some() {}
+        running.spawn(async move {
+            let Ok(_slot) = slots.acquire_owned().await else {
+                return;
+            };
+            // `output()` would capture stdout too, so only stderr is piped
+            let child = Command::new(&argv[0])
+                .args(&argv[1..])
+                .stdin(Stdio::null())
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::piped())
+                .spawn();
+            let output = match child {
+                Ok(child) => child.wait_with_output().await,
+                Err(e) => Err(e),
+            };
+            match output {
+                Ok(output) if output.status.success() => {}
+                Ok(output) => {
+                    if verbose {
+                        eprintln!(
+                            "[!] --on-hit command {} for {}: {}",
+                            output.status,
+                            url,
+                            String::from_utf8_lossy(&output.stderr).trim()
+                        );
+                    }
+                }
+                Err(e) => eprintln!("[!] --on-hit could not run {}: {}", argv[0], e),
+            }
+        });
+    }
+
+    /// Waits for every command started so far
+    pub async fn wait(&self) {
+        let mut running = std::mem::take(&mut *self.running.lock().unwrap());
+        while running.join_next().await.is_some() {}
+    }
+}
+
+/// Splits a command line on whitespace; single or double quotes keep spaces in one argument
+fn split_command(command: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+
+    for c in command.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            Some(_) => current.push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            None if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        bail!("--on-hit has an unclosed quote: {}", command);
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
@@ -1,15 +1,107 @@
 use crate::cli::CommonArgs;
+use crate::core::rate_limit::RateLimiter;
 use anyhow::{Result, Context};
-use reqwest::{Client, ClientBuilder, Response};
+use rand::Rng;
+use reqwest::cookie::{CookieStore, Jar};
+use reqwest::header::HeaderValue;
+use reqwest::redirect::Policy;
+use reqwest::{Client, ClientBuilder, Identity, Response, Url};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+/// Headers that carry the client address through proxies, set by `--spoof-ip`
+const SPOOF_HEADERS: [&str; 3] = ["X-Forwarded-For", "X-Real-IP", "X-Originating-IP"];
+
+/// Services that answer with the caller's address as plain text
+const EXTERNAL_IP_SERVICES: [&str; 3] = [
+    "https://api.ipify.org",
+    "https://ifconfig.me/ip",
+    "https://icanhazip.com",
+];
+
+/// Hops followed when `--follow-redirects` is set without `--max-redirects`, as reqwest does
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+tokio::task_local! {
+    /// Hops taken by the request being sent, updated by the redirect policy as it follows them
+    static REDIRECT_HOPS: Cell<usize>;
+
+    /// With `--cookie-jar`, the `-c` value or rotation entry of the request being sent and
+    /// the host it was sent to
+    static REQUEST_COOKIES: (String, String);
+}
+
+/// Redirect hops behind a response, stored in its extensions by `HttpClient::request`
+#[derive(Clone, Copy)]
+struct RedirectHops(usize);
+
+/// The `--cookie-jar` store: only Set-Cookie from responses is kept, while each request's
+/// own cookies are merged in for that request alone, so concurrent requests never see
+/// each other's FUZZ value or rotation entry
+#[derive(Default)]
+struct RequestCookieJar {
+    jar: Jar,
+}
+
+impl CookieStore for RequestCookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        self.jar.set_cookies(cookie_headers, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let stored = self.jar.cookies(url);
+        let own = REQUEST_COOKIES
+            .try_with(|(cookies, host)| (url.host_str() == Some(host.as_str())).then(|| cookies.clone()))
+            .ok()
+            .flatten();
+        let Some(own) = own else {
+            return stored;
+        };
+        let stored = stored.as_ref().and_then(|value| value.to_str().ok()).unwrap_or("");
+        HeaderValue::from_str(&merge_cookies(&own, stored)).ok()
+    }
+}
+
+/// Joins two Cookie header values; a name in `own` wins over the same name in `stored`
+fn merge_cookies(own: &str, stored: &str) -> String {
+    let pairs = |value: &str| -> Vec<String> {
+        value.split(';').map(str::trim).filter(|pair| !pair.is_empty()).map(str::to_string).collect()
+    };
+    let name = |pair: &str| pair.split('=').next().unwrap_or("").trim().to_string();
+
+    let mut merged = pairs(own);
+    let own_names: Vec<String> = merged.iter().map(|pair| name(pair)).collect();
+    merged.extend(pairs(stored).into_iter().filter(|pair| !own_names.contains(&name(pair))));
+    merged.join("; ")
+}
+
 #[derive(Clone)]
 pub struct HttpClient {
     client: Client,
     user_agents: Option<Arc<Vec<String>>>,
     user_agent_index: Arc<AtomicUsize>,
+    cookies: Option<Arc<Vec<String>>>,
+    cookie_index: Arc<AtomicUsize>,
+    /// Set by `--spoof-ip`; addresses are random unless `--spoof-ip-file` lists them
+    spoof_ip: bool,
+    spoof_ips: Option<Arc<Vec<String>>>,
+    spoof_ip_index: Arc<AtomicUsize>,
+    /// Shared across clones, so `--rate-limit` holds for every worker combined
+    rate_limiter: Option<RateLimiter>,
+    delay: Option<Duration>,
+    /// Bounds in milliseconds of the jitter from `--random-delay`
+    random_delay: Option<(u64, u64)>,
+    max_body_size: usize,
+    /// Authorization value from `--basic-auth` or `--bearer`
+    authorization: Option<String>,
+    /// Accept and Content-Type from `--json`, `--accept` and `--content-type`
+    default_headers: Arc<Vec<(&'static str, String)>>,
+    /// Set by `--cookie-jar`; cookies from responses are then kept and sent again
+    cookie_jar: bool,
 }
 
 impl HttpClient {
@@ -19,8 +111,55 @@ impl HttpClient {
             .user_agent(&args.user_agent)
             .danger_accept_invalid_certs(args.no_tls_validation);
 
-        if !args.follow_redirects {
-            builder = builder.redirect(reqwest::redirect::Policy::none());
+        // Without it, connecting may use up the whole --timeout
+        if let Some(secs) = args.connect_timeout {
+            builder = builder.connect_timeout(Duration::from_secs(secs));
+        }
+
+        // The protocol is negotiated unless one is forced
+        if args.http2_only {
+            builder = builder.http2_prior_knowledge();
+        } else if args.http1_only {
+            builder = builder.http1_only();
+        }
+        // A bound local address also makes the connector skip addresses of the other family
+        if let Some(ip) = args.interface {
+            builder = builder.local_address(ip);
+        } else if args.ipv4_only {
+            builder = builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        } else if args.ipv6_only {
+            builder = builder.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED));
+        }
+        if let Some(idle) = args.pool_max_idle {
+            builder = builder.pool_max_idle_per_host(idle);
+        }
+
+        // Decoded bodies let regexes see text; raw bytes keep sizes as sent on the wire
+        if args.no_compression {
+            builder = builder.no_gzip().no_brotli().no_deflate();
+        }
+
+        if let (Some(cert), Some(key)) = (&args.client_cert, &args.client_key) {
+            builder = builder.identity(load_identity(cert, key)?);
+        }
+
+        if args.cookie_jar {
+            builder = builder.cookie_provider(Arc::new(RequestCookieJar::default()));
+        }
+
+        if args.follow_redirects {
+            let max = args.max_redirects.unwrap_or(DEFAULT_MAX_REDIRECTS);
+            builder = builder.redirect(Policy::custom(move |attempt| {
+                // The first of the previous URLs is the original request, not a hop
+                let hop = attempt.previous().len();
+                if hop > max {
+                    return attempt.error(format!("stopped after {} redirects (--max-redirects)", max));
+                }
+                let _ = REDIRECT_HOPS.try_with(|hops| hops.set(hop));
+                attempt.follow()
+            }));
+        } else {
+            builder = builder.redirect(Policy::none());
         }
 
         if let Some(proxy_url) = &args.proxy {
@@ -66,10 +205,61 @@ impl HttpClient {
             None
         };
 
+        let cookies = if let Some(cookies_file) = &args.cookies_file {
+            let content = std::fs::read_to_string(cookies_file)
+                .with_context(|| format!("Failed to read cookies file: {}", cookies_file))?;
+            let cookies: Vec<String> = content
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.trim().to_string())
+                .collect();
+
+            if cookies.is_empty() {
+                None
+            } else {
+                if args.verbose {
+                    eprintln!("[+] Loaded {} cookie strings for rotation", cookies.len());
+                }
+                Some(Arc::new(cookies))
+            }
+        } else {
+            None
+        };
+
+        let spoof_ips = if let Some(ip_file) = &args.spoof_ip_file {
+            let content = std::fs::read_to_string(ip_file)
+                .with_context(|| format!("Failed to read spoof IP file: {}", ip_file))?;
+            let ips: Vec<String> = content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+
+            if ips.is_empty() {
+                return Err(anyhow::anyhow!("Spoof IP file is empty: {}", ip_file));
+            }
+            Some(Arc::new(ips))
+        } else {
+            None
+        };
+
         Ok(HttpClient {
             client,
             user_agents,
             user_agent_index: Arc::new(AtomicUsize::new(0)),
+            cookies,
+            cookie_index: Arc::new(AtomicUsize::new(0)),
+            spoof_ip: args.spoof_ip || spoof_ips.is_some(),
+            spoof_ips,
+            spoof_ip_index: Arc::new(AtomicUsize::new(0)),
+            rate_limiter: args.rate_limit.map(RateLimiter::new),
+            delay: args.delay.map(Duration::from_millis),
+            random_delay: args.random_delay,
+            max_body_size: args.max_body_size,
+            authorization: args.get_authorization()?,
+            default_headers: Arc::new(args.get_default_headers()),
+            cookie_jar: args.cookie_jar,
         })
     }
 
@@ -80,13 +270,46 @@ impl HttpClient {
         })
     }
 
+    fn get_cookie(&self) -> Option<String> {
+        self.cookies.as_ref().map(|cookies| {
+            let index = self.cookie_index.fetch_add(1, Ordering::SeqCst);
+            cookies[index % cookies.len()].clone()
+        })
+    }
+
+    /// The next address to claim in forwarding headers, from the file or made up
+    fn get_spoof_ip(&self) -> Option<String> {
+        if !self.spoof_ip {
+            return None;
+        }
+
+        Some(match &self.spoof_ips {
+            Some(ips) => {
+                let index = self.spoof_ip_index.fetch_add(1, Ordering::SeqCst);
+                ips[index % ips.len()].clone()
+            }
+            None => random_public_ip(),
+        })
+    }
+
     pub async fn request(
         &self,
         url: &str,
         method: &str,
         headers: &[(String, String)],
         cookies: Option<&str>,
+        body: Option<&str>,
     ) -> Result<Response> {
+        // The global rate limit wins over the per-task --delay
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        } else if let Some(delay) = self.delay {
+            tokio::time::sleep(delay).await;
+        } else if let Some((min, max)) = self.random_delay {
+            let millis = rand::thread_rng().gen_range(min..=max);
+            tokio::time::sleep(Duration::from_millis(millis)).await;
+        }
+
         let mut request = match method.to_uppercase().as_str() {
             "GET" => self.client.get(url),
             "POST" => self.client.post(url),
@@ -94,6 +317,7 @@ impl HttpClient {
             "PUT" => self.client.put(url),
             "DELETE" => self.client.delete(url),
             "PATCH" => self.client.patch(url),
+            "OPTIONS" => self.client.request(reqwest::Method::OPTIONS, url),
             _ => self.client.get(url),
         };
 
@@ -101,20 +325,59 @@ impl HttpClient {
             request = request.header("User-Agent", ua);
         }
 
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+
         for (key, value) in headers {
             request = request.header(key, value);
         }
 
-        if let Some(cookie_str) = cookies {
-            request = request.header("Cookie", cookie_str);
+        for (name, value) in self.default_headers.iter() {
+            if !headers.iter().any(|(key, _)| key.eq_ignore_ascii_case(name)) {
+                request = request.header(*name, value);
+            }
+        }
+
+        // Spoofed headers only fill in what the user did not set with -H
+        if let Some(ip) = self.get_spoof_ip() {
+            for name in SPOOF_HEADERS {
+                if !headers.iter().any(|(key, _)| key.eq_ignore_ascii_case(name)) {
+                    request = request.header(name, &ip);
+                }
+            }
+        }
+
+        // An explicit cookie string wins over the rotation. A Cookie header would stop the
+        // jar from adding its own, so with the jar these are merged in by `RequestCookieJar`
+        let mut jar_cookies = None;
+        if let Some(cookie_str) = cookies.map(str::to_string).or_else(|| self.get_cookie()) {
+            let host = Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string));
+            match host {
+                Some(host) if self.cookie_jar => jar_cookies = Some((cookie_str, host)),
+                _ => request = request.header("Cookie", cookie_str),
+            }
         }
 
-        let response = request.send().await?;
+        if let Some(body) = body {
+            request = request.body(body.to_string());
+        }
+
+        let send = REDIRECT_HOPS.scope(Cell::new(0), async {
+            let response = request.send().await;
+            (REDIRECT_HOPS.with(Cell::get), response)
+        });
+        let (hops, response) = match jar_cookies {
+            Some(jar_cookies) => REQUEST_COOKIES.scope(jar_cookies, send).await,
+            None => send.await,
+        };
+        let mut response = response?;
+        response.extensions_mut().insert(RedirectHops(hops));
         Ok(response)
     }
 
-    #[allow(dead_code)]
-    pub async fn test_connection(&self, test_url: &str, verbose: bool) -> Result<bool> {
+    /// Sends one request to the target; any response passes, a failure explains its kind
+    pub async fn test_connection(&self, test_url: &str, verbose: bool) -> Result<()> {
         if verbose {
             eprintln!("[*] Testing connection to: {}", test_url);
         }
@@ -124,37 +387,52 @@ impl HttpClient {
                 if verbose {
                     eprintln!("[+] Connection test successful (Status: {})", response.status());
                 }
-                Ok(true)
+                Ok(())
             }
             Err(e) => {
                 if verbose {
                     eprintln!("[!] Connection test failed: {}", e);
-                    if e.is_timeout() {
-                        eprintln!("[!] Error type: Timeout");
-                    } else if e.is_connect() {
-                        eprintln!("[!] Error type: Connection failed");
-                    } else if e.is_request() {
-                        eprintln!("[!] Error type: Request error");
-                    }
                 }
-                Ok(false)
+                Err(anyhow::anyhow!(
+                    "Cannot reach {}: {}; check the URL or pass --skip-check",
+                    test_url,
+                    connection_error_kind(&e)
+                ))
             }
         }
     }
 
-    #[allow(dead_code)]
-    pub async fn check_external_ip(&self) -> Result<String> {
-        let ip_services = vec![
-            "https://api.ipify.org",
-            "https://ifconfig.me/ip",
-            "https://icanhazip.com",
-        ];
-
-        for service in ip_services {
-            if let Ok(response) = self.client.get(service).send().await {
-                if let Ok(ip) = response.text().await {
-                    let ip = ip.trim().to_string();
-                    if !ip.is_empty() {
+    /// Reads a response body, stopping at the `--max-body-size` cap
+    pub async fn read_body(&self, response: Response) -> LimitedBody {
+        read_limited_body(response, self.max_body_size).await
+    }
+
+    /// Sends one request to the target to confirm the configured proxy answers
+    pub async fn check_proxy(&self, proxy: &str, target: &str) -> Result<()> {
+        self.client
+            .get(target)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!(
+                "Proxy {} unreachable ({}); check --proxy or pass --skip-preflight",
+                proxy,
+                e
+            ))?;
+
+        Ok(())
+    }
+
+    /// The address the internet sees this client come from, asked of public echo services
+    pub async fn check_external_ip(&self) -> Result<IpAddr> {
+        self.check_external_ip_from(&EXTERNAL_IP_SERVICES).await
+    }
+
+    /// Asks each service in turn and returns the first answer that is an IP address
+    pub async fn check_external_ip_from(&self, services: &[&str]) -> Result<IpAddr> {
+        for service in services {
+            if let Ok(response) = self.client.get(*service).send().await {
+                if let Ok(text) = response.text().await {
+                    if let Ok(ip) = text.trim().parse() {
                         return Ok(ip);
                     }
                 }
@@ -165,22 +443,219 @@ impl HttpClient {
     }
 }
 
+/// Reads a PEM certificate and PKCS#8 key into a TLS client identity
+fn load_identity(cert_path: &str, key_path: &str) -> Result<Identity> {
+    let cert = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read client certificate: {}", cert_path))?;
+    let key = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read client key: {}", key_path))?;
+    Identity::from_pkcs8_pem(&cert, &key).with_context(|| {
+        format!(
+            "Failed to load client identity from {} and {} (expected PEM certificate and PKCS#8 key)",
+            cert_path, key_path
+        )
+    })
+}
+
+/// A random IPv4 address outside the private, loopback and other reserved ranges
+fn random_public_ip() -> String {
+    let mut rng = rand::thread_rng();
+    loop {
+        let ip = Ipv4Addr::from(rng.gen::<u32>());
+        let [a, b, ..] = ip.octets();
+        let reserved = ip.is_private()
+            || ip.is_loopback()
+            || ip.is_link_local()
+            || ip.is_multicast()
+            || ip.is_broadcast()
+            || ip.is_documentation()
+            || ip.is_unspecified()
+            || a == 0
+            || a >= 240
+            || (a == 100 && (64..128).contains(&b));
+        if !reserved {
+            return ip.to_string();
+        }
+    }
+}
+
+/// What went wrong with a request that got no response, going by its error chain
+fn connection_error_kind(error: &reqwest::Error) -> &'static str {
+    let mut chain = String::new();
+    let mut source: Option<&dyn std::error::Error> = Some(error);
+    while let Some(err) = source {
+        chain.push_str(&err.to_string().to_lowercase());
+        chain.push('\n');
+        source = err.source();
+    }
+
+    if error.is_timeout() {
+        "timed out"
+    } else if chain.contains("dns error") || chain.contains("failed to lookup address") {
+        "DNS lookup failed"
+    } else if chain.contains("certificate") || chain.contains("ssl") || chain.contains("tls") {
+        "TLS handshake failed (--no-tls-validation skips certificate checks)"
+    } else if error.is_connect() {
+        "connection refused or unreachable"
+    } else {
+        "request failed"
+    }
+}
+
+/// Aborts early when the target does not answer, unless `--skip-check` or `--quiet`
+pub async fn preflight_target(args: &CommonArgs, target: &str) -> Result<()> {
+    if args.skip_check || args.quiet {
+        return Ok(());
+    }
+    HttpClient::new_from_common(args)?
+        .test_connection(target, args.verbose)
+        .await
+}
+
+/// Aborts early when `--proxy` is set but does not answer, unless `--skip-preflight`
+///
+/// With `--verbose` it also prints the external IP seen through the proxy, so a proxy that
+/// is bypassed shows up as the machine's own address. That lookup never fails the scan.
+pub async fn preflight_proxy(args: &CommonArgs, target: &str) -> Result<()> {
+    let Some(proxy) = &args.proxy else {
+        return Ok(());
+    };
+    if args.skip_preflight && !args.verbose {
+        return Ok(());
+    }
+
+    let client = HttpClient::new_from_common(args)?;
+    if !args.skip_preflight {
+        client.check_proxy(proxy, target).await?;
+    }
+    if args.verbose {
+        match client.check_external_ip().await {
+            Ok(ip) => eprintln!("[+] External IP through proxy: {}", ip),
+            Err(_) => eprintln!("[!] Could not determine the external IP through the proxy"),
+        }
+    }
+    Ok(())
+}
+
+/// A response body read up to a size cap
+pub struct LimitedBody {
+    pub bytes: Vec<u8>,
+    /// Whether the body continued past the cap
+    pub truncated: bool,
+}
+
+impl LimitedBody {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+}
+
+/// Reads the body chunk by chunk and stops once `limit` bytes are buffered
+pub async fn read_limited_body(mut response: Response, limit: usize) -> LimitedBody {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut truncated = false;
+
+    while let Ok(Some(chunk)) = response.chunk().await {
+        let remaining = limit - bytes.len();
+        if chunk.len() > remaining {
+            bytes.extend_from_slice(&chunk[..remaining]);
+            truncated = true;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    LimitedBody { bytes, truncated }
+}
+
+/// The values of the named headers that are present; names match case-insensitively and
+/// repeated headers are joined with commas
+pub fn capture_headers(headers: &reqwest::header::HeaderMap, names: &[String]) -> HashMap<String, String> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let values: Vec<&str> = headers
+                .get_all(name.as_str())
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .collect();
+            (!values.is_empty()).then(|| (name.clone(), values.join(", ")))
+        })
+        .collect()
+}
+
+/// Scheme names from every `WWW-Authenticate` header, without their parameters
+pub fn auth_schemes(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    let schemes: Vec<&str> = headers
+        .get_all("www-authenticate")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .filter_map(|v| v.split_whitespace().next())
+        .map(|scheme| scheme.trim_end_matches(','))
+        .collect();
+
+    if schemes.is_empty() {
+        None
+    } else {
+        Some(schemes.join(", "))
+    }
+}
+
+#[derive(Clone)]
 pub struct ScanResult {
     pub url: String,
     pub status_code: u16,
     pub content_length: u64,
     pub redirect_location: Option<String>,
-    #[allow(dead_code)]
     pub body: Option<String>,
     pub content_type: Option<String>,
     pub server: Option<String>,
     pub duration_ms: u64,
+    /// Wordlist the request was generated from, if known
+    pub source: Option<String>,
+    /// Summary of the difference from the stored golden body, if it changed
+    pub golden_diff: Option<String>,
+    /// Time to the response headers, captured with `--show-timing`
+    pub ttfb_ms: Option<u64>,
+    /// Bytes kept when the body was cut off at `--max-body-size`
+    pub body_truncated_at: Option<u64>,
+    /// `Allow` header of an OPTIONS probe, captured with `--probe-methods`
+    pub allowed_methods: Option<String>,
+    /// Similarity of a 200 body to the soft-404 baseline, with `--similarity-threshold`
+    pub similarity: Option<f32>,
+    /// Schemes offered in `WWW-Authenticate`, such as `NTLM, Negotiate`
+    pub auth_scheme: Option<String>,
+    /// Where `--follow-redirects` ended up, when at least one redirect was followed
+    pub final_url: Option<String>,
+    pub redirect_count: usize,
+    /// Response headers picked with `--show-headers`, keyed by the name as given there
+    pub headers: HashMap<String, String>,
+    /// Technologies detected with `--fingerprint`
+    pub technologies: Vec<String>,
 }
 
 impl ScanResult {
+    /// Whether the captured body looks like a server-generated directory index
+    pub fn is_directory_listing(&self) -> bool {
+        self.body.as_deref().is_some_and(|body| {
+            body.contains("<title>Index of /") || body.contains("<title>Directory listing for /")
+        })
+    }
+
     pub fn from_response(url: String, response: &Response, duration_ms: u64) -> Self {
         let status_code = response.status().as_u16();
-        let content_length = response.content_length().unwrap_or(0);
+        // HEAD responses have an empty body, so the size comes from the header
+        let content_length = response
+            .content_length()
+            .filter(|&length| length > 0)
+            .or_else(|| {
+                response
+                    .headers()
+                    .get("content-length")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse().ok())
+            })
+            .unwrap_or(0);
         let redirect_location = response
             .headers()
             .get("location")
@@ -199,6 +674,9 @@ impl ScanResult {
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string());
 
+        let redirect_count = response.extensions().get::<RedirectHops>().map_or(0, |hops| hops.0);
+        let final_url = (redirect_count > 0).then(|| response.url().to_string());
+
         ScanResult {
             url,
             status_code,
@@ -208,69 +686,66 @@ impl ScanResult {
             content_type,
             server,
             duration_ms,
+            source: None,
+            golden_diff: None,
+            ttfb_ms: None,
+            body_truncated_at: None,
+            allowed_methods: None,
+            similarity: None,
+            auth_scheme: auth_schemes(response.headers()),
+            final_url,
+            redirect_count,
+            headers: HashMap::new(),
+            technologies: Vec::new(),
         }
     }
 
+    /// Builds the result and keeps the body, read up to `max_body_size` bytes
     #[allow(dead_code)]
-    pub async fn from_response_with_body(url: String, response: Response, duration_ms: u64) -> Self {
-        let status_code = response.status().as_u16();
-        let content_length = response.content_length().unwrap_or(0);
-        let redirect_location = response
-            .headers()
-            .get("location")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
-        
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.split(';').next().unwrap_or(s).trim().to_string());
-        
-        let server = response
-            .headers()
-            .get("server")
-            .and_then(|v| v.to_str().ok())
-            .map(|s| s.to_string());
-
-        let body = response.text().await.ok();
-
-        ScanResult {
-            url,
-            status_code,
-            content_length,
-            redirect_location,
-            body,
-            content_type,
-            server,
-            duration_ms,
+    pub async fn from_response_with_body(
+        url: String,
+        response: Response,
+        duration_ms: u64,
+        max_body_size: usize,
+    ) -> Self {
+        let mut result = Self::from_response(url, &response, duration_ms);
+        let body = read_limited_body(response, max_body_size).await;
+        if body.truncated {
+            result.body_truncated_at = Some(body.bytes.len() as u64);
         }
+        result.body = Some(body.text());
+        result
     }
     
     pub fn status_text(&self) -> &'static str {
-        match self.status_code {
-            200 => "OK",
-            201 => "Created",
-            204 => "No Content",
-            301 => "Moved Permanently",
-            302 => "Found",
-            303 => "See Other",
-            304 => "Not Modified",
-            307 => "Temporary Redirect",
-            308 => "Permanent Redirect",
-            400 => "Bad Request",
-            401 => "Unauthorized",
-            403 => "Forbidden",
-            404 => "Not Found",
-            405 => "Method Not Allowed",
-            408 => "Request Timeout",
-            429 => "Too Many Requests",
-            500 => "Internal Server Error",
-            501 => "Not Implemented",
-            502 => "Bad Gateway",
-            503 => "Service Unavailable",
-            504 => "Gateway Timeout",
-            _ => "Unknown",
-        }
+        status_text(self.status_code)
+    }
+}
+
+/// The reason phrase shown next to a status code
+pub fn status_text(status_code: u16) -> &'static str {
+    match status_code {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
     }
 }
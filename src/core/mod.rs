@@ -1,7 +1,14 @@
+pub mod auto_filter;
+pub mod doh;
+pub mod error_budget;
+pub mod hook;
 pub mod http_client;
+pub mod rate_limit;
 pub mod scanner;
+pub mod status;
 pub mod wordlist;
 
 pub use http_client::HttpClient;
-pub use scanner::Scanner;
+pub use scanner::{ScanTarget, Scanner};
+pub use status::StatusFilter;
 pub use wordlist::Wordlist;
@@ -1,18 +1,213 @@
 use crate::cli::CommonArgs;
-use crate::core::http_client::{HttpClient, ScanResult};
+use crate::core::auto_filter::AutoFilter;
+use crate::core::error_budget::ErrorBudget;
+use crate::core::hook::HitHook;
+use crate::core::http_client::{capture_headers, HttpClient, ScanResult};
+use crate::core::status::StatusFilter;
 use crate::output::handler::OutputHandler;
-use crate::output::tui::{TuiMessage, TuiResult};
-use anyhow::Result;
+use crate::output::{progress, serialize};
+use crate::output::tui::{PauseGate, TuiResult, TuiSender};
+use crate::utils::body_store::BodyStore;
+use crate::utils::checkpoint::Checkpoint;
+use crate::utils::fingerprint::Fingerprinter;
+use crate::utils::golden::{GoldenStatus, GoldenStore};
+use crate::utils::report::TimingStats;
+use crate::utils::session::{Session, SessionResult};
+use crate::utils::smart_404::Smart404Detector;
+use anyhow::{Context, Result};
 use futures::stream::{self, StreamExt};
-use std::sync::Arc;
+use regex::Regex;
+use indicatif::ProgressBar;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tokio::sync::mpsc;
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// A single URL to request, tagged with the wordlist it was generated from
+#[derive(Clone, Debug)]
+pub struct ScanTarget {
+    pub url: String,
+    pub source: Option<Arc<str>>,
+    /// Request body overriding the scanner-wide `--data`
+    pub body: Option<String>,
+    /// Headers overriding the scanner-wide `-H` values
+    pub headers: Option<Vec<(String, String)>>,
+    /// Cookie string overriding the scanner-wide `-c` value
+    pub cookies: Option<String>,
+}
+
+impl ScanTarget {
+    pub fn new(url: String, source: Option<Arc<str>>) -> Self {
+        ScanTarget {
+            url,
+            source,
+            body: None,
+            headers: None,
+            cookies: None,
+        }
+    }
+
+    pub fn with_body(mut self, body: Option<String>) -> Self {
+        self.body = body;
+        self
+    }
+
+    pub fn with_headers(mut self, headers: Option<Vec<(String, String)>>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_cookies(mut self, cookies: Option<String>) -> Self {
+        self.cookies = cookies;
+        self
+    }
+
+    /// What a session or state file records once this target is done: the bare URL for a
+    /// plain GET, otherwise also the method and any body, headers or cookies fuzz mode set,
+    /// since those targets can all share one URL
+    pub fn completion_key(&self, method: &str) -> String {
+        let mut key = if method.eq_ignore_ascii_case("GET") {
+            self.url.clone()
+        } else {
+            format!("{} {}", method.to_uppercase(), self.url)
+        };
+        if self.body.is_some() || self.headers.is_some() || self.cookies.is_some() {
+            let request = serde_json::json!({
+                "body": self.body,
+                "headers": self.headers,
+                "cookies": self.cookies,
+            });
+            key.push(' ');
+            key.push_str(&request.to_string());
+        }
+        key
+    }
+}
+
+/// Compiles a body pattern given with `flag`
+fn compile_regex(pattern: Option<&str>, flag: &str) -> Result<Option<Regex>> {
+    pattern
+        .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid {} pattern: {}", flag, pattern)))
+        .transpose()
+}
+
+/// Status and size of the response to a path that should not exist
+#[derive(Clone, Debug)]
+struct WildcardSignature {
+    status_code: u16,
+    content_length: u64,
+}
+
+/// Calibrated responses of one directory that its results are compared against
+#[derive(Default)]
+pub struct Baseline {
+    /// Status and size a random path answered with
+    wildcard: Option<WildcardSignature>,
+    /// Soft-404 bodies that 200 responses are compared against
+    soft_404: Option<Smart404Detector>,
+}
+
+/// What one pass over a set of targets turned up
+///
+/// Filled as responses arrive, so a pass abandoned by a time budget keeps what it found.
+#[derive(Default)]
+pub struct ScanPass {
+    results: Mutex<Vec<ScanResult>>,
+    dirs: Mutex<Vec<String>>,
+    responses: AtomicUsize,
+    stop_on_first: bool,
+    /// Set once a pass that stops on its first hit has one; targets not yet sent are skipped
+    stopped: AtomicBool,
+}
+
+impl ScanPass {
+    /// A pass that ends at its first hit, for `--stop-on-first`
+    ///
+    /// Requests already in flight still complete and are reported.
+    pub fn stop_on_first() -> Self {
+        ScanPass { stop_on_first: true, ..ScanPass::default() }
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    /// Shown results, discovered directories and the number of responses received
+    pub fn into_parts(self) -> (Vec<ScanResult>, Vec<String>, usize) {
+        (
+            self.results.into_inner().unwrap_or_default(),
+            self.dirs.into_inner().unwrap_or_default(),
+            self.responses.into_inner(),
+        )
+    }
+}
 
 pub struct Scanner {
     client: HttpClient,
     output: OutputHandler,
     threads: usize,
-    discovered_dirs: Vec<String>,
+    /// Caps requests in flight at `--threads`, even across passes running side by side
+    slots: Semaphore,
+    show_progress: bool,
+    show_timing: bool,
+    method: String,
+    head_first: bool,
+    /// Follow each finding with an OPTIONS request to capture its `Allow` header
+    probe_methods: bool,
+    /// Options that inspect the response body, which rules out HEAD probes
+    needs_body: bool,
+    headers: Vec<(String, String)>,
+    /// Response headers to keep on each result, from `--show-headers`
+    show_headers: Vec<String>,
+    cookies: Option<String>,
+    body: Option<String>,
+    golden: Option<GoldenStore>,
+    bodies: Option<BodyStore>,
+    fingerprinter: Option<Fingerprinter>,
+    status_filter: StatusFilter,
+    /// Show every response regardless of `-s`/`-n`, from `-e`
+    expanded: bool,
+    /// Responses per status, or per status family with `--normalize-status`
+    status_counts: Mutex<BTreeMap<String, usize>>,
+    /// Results shown per status before the rest are only counted, from `--limit-per-status`
+    limit_per_status: Option<usize>,
+    shown_counts: Mutex<BTreeMap<String, usize>>,
+    /// Most common size learned from the first results, from `--auto-filter`
+    auto_filter: Option<AutoFilter>,
+    /// Keep the body on results for the output file, from `--include-body`
+    include_body: bool,
+    /// Body patterns a result must match (`--match-regex`) or must not (`--filter-regex`)
+    match_regex: Option<Regex>,
+    filter_regex: Option<Regex>,
+    /// Response time bounds in milliseconds, from `--min-time` and `--max-time`
+    min_time: Option<u64>,
+    max_time: Option<u64>,
+    /// Keep only directory hits (`Some(true)`) or only file hits (`Some(false)`)
+    only_dirs: Option<bool>,
+    suppressed_counts: Mutex<BTreeMap<String, usize>>,
+    /// Baseline of the scanned URL, set by `detect_wildcard` and `calibrate_soft_404`
+    baseline: Baseline,
+    /// Drop results matching the wildcard baseline; `--wildcard` keeps them
+    filter_wildcards: bool,
+    wildcard_hits: AtomicUsize,
+    similarity_threshold: Option<f32>,
+    /// Session shared by every scanner of a run, so recursion records into one file
+    session: Option<Arc<Mutex<Session>>>,
+    /// State file from `--resume-state`
+    checkpoint: Option<Checkpoint>,
+    delta_output: Option<String>,
+    /// Statuses that trigger recursion when the response looks like a directory
+    recurse_statuses: Vec<u16>,
+    /// Set by `--recurse-on-status`, which also inspects bodies for directory indexes
+    recurse_configured: bool,
+    /// Results a TUI scan has shown, for the report written once the TUI closes
+    tui_results: Mutex<Vec<ScanResult>>,
+    /// `--max-errors` / `--max-consecutive-errors`; once spent, remaining targets are skipped
+    error_budget: ErrorBudget,
+    /// Command run for every hit (`--on-hit`)
+    on_hit: Option<HitHook>,
 }
 
 impl Scanner {
@@ -21,87 +216,528 @@ impl Scanner {
 
         let output = OutputHandler::new(
             common.output.clone(),
-            common.quiet,
+            common.quiet || common.url_only,
             common.output_format.clone(),
             common.verbose,
-        );
+        )
+        .with_notify_on(&common.notify_on)
+        .with_append(common.output_append)
+        .with_url_only(common.url_only)
+        .with_include_body(common.include_body);
 
         Ok(Self {
             client,
             output,
             threads: common.threads,
-            discovered_dirs: Vec::new(),
+            slots: Semaphore::new(common.threads.max(1)),
+            show_progress: !common.no_progress && !common.quiet && !common.url_only,
+            show_timing: common.show_timing,
+            method: common.method.clone(),
+            head_first: common.head_first,
+            probe_methods: common.probe_methods,
+            needs_body: common.match_regex.is_some()
+                || common.filter_regex.is_some()
+                || common.smart_404
+                || common.golden_dir.is_some()
+                || common.save_bodies.is_some()
+                || common.show_timing
+                || common.similarity_threshold.is_some()
+                || common.fingerprint
+                || common.fingerprint_rules.is_some()
+                || common.include_body,
+            headers: common.get_headers(),
+            show_headers: common.get_show_headers(),
+            cookies: common.cookies.clone(),
+            body: common.get_data()?,
+            golden: common.golden_dir.as_deref().map(GoldenStore::new).transpose()?,
+            bodies: common.save_bodies.as_deref().map(BodyStore::new).transpose()?,
+            fingerprinter: Fingerprinter::from_common(&common)?,
+            status_filter: StatusFilter::from_common(&common),
+            expanded: common.expanded,
+            status_counts: Mutex::new(BTreeMap::new()),
+            limit_per_status: common.limit_per_status.map(|limit| limit as usize),
+            shown_counts: Mutex::new(BTreeMap::new()),
+            auto_filter: common.auto_filter.map(|sample| AutoFilter::new(sample as usize)),
+            include_body: common.include_body,
+            match_regex: compile_regex(common.match_regex.as_deref(), "--match-regex")?,
+            filter_regex: compile_regex(common.filter_regex.as_deref(), "--filter-regex")?,
+            min_time: common.min_time,
+            max_time: common.max_time,
+            only_dirs: if common.only_dirs {
+                Some(true)
+            } else if common.only_files {
+                Some(false)
+            } else {
+                None
+            },
+            suppressed_counts: Mutex::new(BTreeMap::new()),
+            baseline: Baseline::default(),
+            filter_wildcards: !common.wildcard,
+            wildcard_hits: AtomicUsize::new(0),
+            similarity_threshold: common.similarity_threshold,
+            session: None,
+            checkpoint: common.resume_state.as_deref().map(Checkpoint::open).transpose()?,
+            delta_output: common.delta_output.clone(),
+            recurse_statuses: vec![200, 301, 302],
+            recurse_configured: false,
+            tui_results: Mutex::new(Vec::new()),
+            error_budget: ErrorBudget::from_common(&common),
+            on_hit: HitHook::from_common(&common)?,
         })
     }
 
-    pub async fn scan_urls(&mut self, urls: Vec<String>) -> Result<()> {
-        let client = Arc::new(self.client.clone());
-        let output = Arc::new(self.output.clone());
+    /// Records completed URLs and findings into a session, skipping URLs it already covers
+    pub fn set_session(&mut self, session: Arc<Mutex<Session>>) {
+        self.session = Some(session);
+    }
 
-        stream::iter(urls)
-            .map(|url| {
-                let client = Arc::clone(&client);
-                let output = Arc::clone(&output);
-                async move {
-                    let start = Instant::now();
-                    match client.request(&url, "GET", &[], None).await {
-                        Ok(response) => {
-                            let duration_ms = start.elapsed().as_millis() as u64;
-                            let result = ScanResult::from_response(url.clone(), &response, duration_ms);
-
-                            if result.status_code == 301 || result.status_code == 302 {
-                                // Note: Can't modify self.discovered_dirs from here due to Arc
-                            }
+    /// Marks a target as done in the session and state file, and keeps findings the session has not seen yet
+    ///
+    /// Returns whether the result is such a new finding, which `write_delta` takes.
+    fn record(&self, target: &ScanTarget, result: &ScanResult, suppressed: bool) -> bool {
+        let key = target.completion_key(&self.method);
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.record(&key, Some(result).filter(|_| !suppressed));
+        }
 
-                            output.print_result(&result, false);
-                        }
-                        Err(_) => {
-                            // Error handling - could send to output if needed
+        match &self.session {
+            Some(session) => {
+                let Ok(mut session) = session.lock() else {
+                    return false;
+                };
+                session.add_completed_word(key);
+
+                let is_new = !suppressed
+                    && self.status_filter.matches_url(&result.url, result.status_code)
+                    && !session.has_result(&result.url);
+                if is_new {
+                    session.add_result(SessionResult {
+                        url: result.url.clone(),
+                        status_code: result.status_code,
+                        content_length: result.content_length,
+                    });
+                }
+                is_new
+            }
+            None => !suppressed && self.status_filter.matches_url(&result.url, result.status_code),
+        }
+    }
+
+    /// Writes a new finding to `--delta-output`, so a resumed run only lists what it discovered
+    fn write_delta(&self, result: &ScanResult) {
+        if let Some(path) = &self.delta_output {
+            let _ = serialize::append_plain(&result.into(), path);
+        }
+    }
+
+    /// The targets an earlier run has not covered, going by the session and the state file
+    ///
+    /// With a session, its total is updated to the completed words plus what is left.
+    pub fn remaining_targets(&self, targets: Vec<ScanTarget>) -> Vec<ScanTarget> {
+        let targets: Vec<ScanTarget> = match &self.session {
+            Some(session) => {
+                let mut session = session.lock().unwrap();
+                let completed: HashSet<&str> =
+                    session.completed_words.iter().map(String::as_str).collect();
+                let remaining: Vec<ScanTarget> = targets
+                    .into_iter()
+                    .filter(|target| !completed.contains(target.completion_key(&self.method).as_str()))
+                    .collect();
+                session.total_words = session.completed_words.len() + remaining.len();
+                remaining
+            }
+            None => targets,
+        };
+        match &self.checkpoint {
+            Some(checkpoint) => targets
+                .into_iter()
+                .filter(|target| !checkpoint.is_completed(&target.completion_key(&self.method)))
+                .collect(),
+            None => targets,
+        }
+    }
+
+    /// Saves the session and the state file as they stand
+    pub fn save_progress(&self) -> Result<()> {
+        if let Some(session) = &self.session {
+            session.lock().unwrap().save().context("Failed to save session")?;
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            checkpoint.save()?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the default redirect heuristic with an explicit list of recursion statuses
+    pub fn set_recurse_statuses(&mut self, statuses: Vec<u16>) {
+        self.recurse_statuses = statuses;
+        self.recurse_configured = true;
+    }
+
+    /// Sends the request for a single target and builds its result
+    ///
+    /// The raw body of a possible finding comes back too when `--golden-dir` or `--save-bodies`
+    /// needs it, which
+    /// `keep_finding_body` takes once the result is admitted.
+    async fn execute(&self, target: &ScanTarget, baseline: &Baseline) -> Result<(ScanResult, Option<Vec<u8>>)> {
+        let body = target.body.as_deref().or(self.body.as_deref());
+        let headers = target.headers.as_deref().unwrap_or(&self.headers);
+        let cookies = target.cookies.as_deref().or(self.cookies.as_deref());
+
+        let probe_with_head = self.head_first
+            && self.method.eq_ignore_ascii_case("GET")
+            && body.is_none()
+            && !self.needs_body
+            && !self.recurse_configured;
+        let method = if probe_with_head { "HEAD" } else { self.method.as_str() };
+
+        let _slot = self.slots.acquire().await?;
+        let start = Instant::now();
+        let mut response = self.client.request(&target.url, method, headers, cookies, body).await?;
+        if probe_with_head && matches!(response.status().as_u16(), 405 | 501) {
+            response = self.client.request(&target.url, &self.method, headers, cookies, body).await?;
+        }
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        let mut result = ScanResult::from_response(target.url.clone(), &response, duration_ms);
+        result.source = target.source.as_deref().map(str::to_string);
+        result.headers = capture_headers(response.headers(), &self.show_headers);
+
+        let check_listing = self.recurse_configured
+            && self.recurse_statuses.contains(&result.status_code)
+            && result.redirect_location.is_none();
+
+        let keep_raw = (self.golden.is_some() || self.bodies.is_some())
+            && self.status_filter.matches_url(&result.url, result.status_code);
+
+        // Only hits are fingerprinted, and their headers are gone once the body is read
+        let fingerprint = self
+            .fingerprinter
+            .as_ref()
+            .filter(|_| self.status_filter.matches_url(&result.url, result.status_code))
+            .map(|fingerprinter| (fingerprinter, response.headers().clone()));
+
+        // Decoded bodies carry no length header, so their size is only known once read
+        let size_unknown = method != "HEAD"
+            && response.content_length().is_none()
+            && !response.headers().contains_key("content-length");
+
+        let compare_similarity = result.status_code == 200
+            && self.similarity_threshold.is_some()
+            && baseline.soft_404.as_ref().is_some_and(Smart404Detector::is_calibrated);
+
+        // The body patterns are checked when the result is admitted
+        let keep_body = check_listing
+            || self.match_regex.is_some()
+            || self.filter_regex.is_some()
+            || (self.include_body && self.status_filter.matches_url(&result.url, result.status_code));
+
+        // Timing needs the whole body to tell a slow start from a slow transfer
+        let mut raw_body = None;
+        if keep_raw
+            || keep_body
+            || check_listing
+            || self.show_timing
+            || compare_similarity
+            || fingerprint.is_some()
+            || size_unknown
+        {
+            let limited = self.client.read_body(response).await;
+            if size_unknown {
+                result.content_length = limited.bytes.len() as u64;
+            }
+            if limited.truncated {
+                result.body_truncated_at = Some(limited.bytes.len() as u64);
+            }
+            let body = limited.text();
+            if self.show_timing {
+                result.ttfb_ms = Some(duration_ms);
+                result.duration_ms = start.elapsed().as_millis() as u64;
+            }
+            if let Some((fingerprinter, headers)) = &fingerprint {
+                result.technologies = fingerprinter.detect(headers, &body);
+            }
+            if compare_similarity {
+                result.similarity = baseline.soft_404.as_ref().map(|detector| detector.similarity(&body));
+            }
+            if keep_body {
+                result.body = Some(body);
+            }
+            if keep_raw {
+                raw_body = Some(limited.bytes);
+            }
+        }
+
+        // Only findings are probed, so the request count grows with hits rather than words
+        if self.probe_methods && self.status_filter.matches_url(&result.url, result.status_code) {
+            if let Ok(response) = self.client.request(&target.url, "OPTIONS", headers, cookies, None).await {
+                result.allowed_methods = response
+                    .headers()
+                    .get("allow")
+                    .and_then(|v| v.to_str().ok())
+                    .map(|s| s.to_string());
+            }
+        }
+
+        Ok((result, raw_body))
+    }
+
+    /// Writes the body of an admitted finding for `--save-bodies` and compares it with its
+    /// `--golden-dir` copy
+    ///
+    /// A disk error is reported on its own rather than failing the request.
+    fn keep_finding_body(&self, result: &mut ScanResult, raw_body: Option<Vec<u8>>) {
+        let Some(raw_body) = raw_body else {
+            return;
+        };
+        if let Some(bodies) = &self.bodies {
+            if let Err(err) = bodies.save(&result.url, &raw_body) {
+                eprintln!("[!] {:#}", err);
+            }
+        }
+        if let Some(golden) = &self.golden {
+            match golden.compare(&result.url, &String::from_utf8_lossy(&raw_body)) {
+                Ok(GoldenStatus::Changed(summary)) => result.golden_diff = Some(summary),
+                Ok(_) => {}
+                Err(err) => eprintln!("[!] {:#}", err),
+            }
+        }
+    }
+
+    /// Returns the directory to recurse into when a result looks like one
+    ///
+    /// A directory is a path ending in `/`, a redirect to a trailing-slash path on the
+    /// same host, or an autoindex page. Explicitly configured error statuses such as
+    /// 403 carry no useful body, so those also count for extension-less paths.
+    fn directory_for(&self, result: &ScanResult) -> Option<String> {
+        if !self.recurse_statuses.contains(&result.status_code) {
+            return None;
+        }
+
+        if let Some(dir) = directory_url(result) {
+            return Some(dir.to_string());
+        }
+        if result.redirect_location.is_some() {
+            return None;
+        }
+
+        let url = Url::parse(&result.url).ok()?;
+        let extensionless = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .is_some_and(|last| !last.contains('.'));
+        let error_status = result.status_code >= 400;
+
+        if result.is_directory_listing() || (self.recurse_configured && error_status && extensionless) {
+            let mut dir = url;
+            let path = format!("{}/", dir.path());
+            dir.set_path(&path);
+            return Some(dir.to_string());
+        }
+
+        None
+    }
+
+    /// Builds the progress bar for a plain scan, unless `--no-progress` or `--quiet` is set
+    pub fn progress_bar(&self, total: usize) -> Option<ProgressBar> {
+        if !self.show_progress {
+            return None;
+        }
+
+        let pb = ProgressBar::new(total as u64);
+        pb.set_style(progress::progress_style());
+        Some(pb)
+    }
+
+    /// Requests every target, prints the responses and returns them
+    pub async fn scan_urls(&self, targets: Vec<ScanTarget>) -> Result<Vec<ScanResult>> {
+        let progress = self.progress_bar(0);
+        let pass = ScanPass::default();
+        self.scan_pass(targets, &self.baseline, progress.as_ref(), &pass).await;
+
+        if let Some(pb) = &progress {
+            pb.finish_and_clear();
+        }
+
+        let (results, _, responses) = pass.into_parts();
+
+        self.wait_for_hooks().await;
+        self.finish(responses, &results)?;
+        Ok(results)
+    }
+
+    /// Waits for the `--on-hit` commands still running, so none is cut off when the scan ends
+    pub async fn wait_for_hooks(&self) {
+        if let Some(hook) = &self.on_hit {
+            hook.wait().await;
+        }
+    }
+
+    /// Requests every target not yet covered by the session, judging responses against `baseline`
+    ///
+    /// Shown results and discovered directories are collected into `pass`, and `progress`
+    /// grows by the number of targets so several passes can share one bar.
+    pub async fn scan_pass(
+        &self,
+        targets: Vec<ScanTarget>,
+        baseline: &Baseline,
+        progress: Option<&ProgressBar>,
+        pass: &ScanPass,
+    ) {
+        let targets = self.remaining_targets(targets);
+
+        if let Some(pb) = progress {
+            pb.inc_length(targets.len() as u64);
+        }
+
+        stream::iter(targets)
+            .for_each_concurrent(self.threads, |target| async move {
+                if self.error_budget.is_exhausted() || pass.is_stopped() {
+                    return;
+                }
+
+                let outcome = self.execute(&target, baseline).await;
+                if let Some(pb) = progress {
+                    pb.inc(1);
+                }
+
+                let Ok((mut result, raw_body)) = outcome else {
+                    self.error_budget.record_error();
+                    return;
+                };
+                self.error_budget.record_success();
+                pass.responses.fetch_add(1, Ordering::Relaxed);
+
+                let hidden = !self.admit(&result, baseline);
+                if !hidden {
+                    self.keep_finding_body(&mut result, raw_body);
+                }
+                if self.record(&target, &result, hidden) {
+                    self.write_delta(&result);
+                }
+
+                // A catch-all response is no evidence of a directory
+                let dir = Some(&result)
+                    .filter(|result| !self.matches_baseline(result, baseline))
+                    .and_then(|result| self.directory_for(result));
+                if let Some(dir) = dir {
+                    if let Ok(mut dirs) = pass.dirs.lock() {
+                        if !dirs.contains(&dir) {
+                            dirs.push(dir);
                         }
                     }
                 }
+
+                if hidden {
+                    return;
+                }
+
+                match progress {
+                    Some(pb) => pb.suspend(|| self.output.print_result(&result, false)),
+                    None => self.output.print_result(&result, false),
+                }
+                // Results only shown by -e are not hits
+                if self.status_filter.matches_url(&result.url, result.status_code) {
+                    if let Some(hook) = &self.on_hit {
+                        hook.spawn(&result);
+                    }
+                    if pass.stop_on_first {
+                        pass.stopped.store(true, Ordering::Relaxed);
+                    }
+                }
+                if let Ok(mut results) = pass.results.lock() {
+                    results.push(result);
+                }
             })
-            .buffer_unordered(self.threads)
-            .collect::<Vec<_>>()
             .await;
+    }
 
-        Ok(())
+    /// How many results match the status filters, leaving out those only shown by -e
+    pub fn count_found(&self, results: &[ScanResult]) -> usize {
+        results
+            .iter()
+            .filter(|result| self.status_filter.matches_url(&result.url, result.status_code))
+            .count()
     }
 
+    /// Prints the summary of a finished scan and saves the session
+    ///
+    /// `responses` counts every response received, including hidden ones.
+    pub fn finish(&self, responses: usize, results: &[ScanResult]) -> Result<()> {
+        let found = self.count_found(results);
+        self.output.print_summary_with_counts(responses, found, &self.status_counts());
+        let found_durations = results
+            .iter()
+            .filter(|result| self.status_filter.matches_url(&result.url, result.status_code))
+            .map(|result| result.duration_ms);
+        self.output.print_timing(TimingStats::from_durations(found_durations));
+        self.output.print_suppressed(&self.suppressed_counts());
+        self.output.print_wildcard_filtered(self.wildcard_hits.load(Ordering::Relaxed));
+        if let Some((size, count)) = self.auto_filter.as_ref().and_then(AutoFilter::filtered) {
+            self.output.print_auto_filtered(size, count);
+        }
+
+        self.save_progress()?;
+        self.output.finalize().context("Failed to write output file")?;
+
+        self.error_budget.check()
+    }
+
+    /// The results shown so far by `scan_urls_with_tui`, in the order they arrived
+    pub fn tui_results(&self) -> Vec<ScanResult> {
+        self.tui_results.lock().map(|shown| shown.clone()).unwrap_or_default()
+    }
+
+    /// Runs a scan for the TUI, sending it each shown result
+    ///
+    /// `targets` should already have been through `remaining_targets`, so the TUI's total
+    /// matches what is requested. Call `save_progress` once the TUI closes.
     pub async fn scan_urls_with_tui(
         &self,
-        urls: Vec<String>,
-        tx: mpsc::Sender<TuiMessage>,
+        targets: Vec<ScanTarget>,
+        tx: TuiSender,
+        pause: PauseGate,
     ) -> Result<()> {
-        let client = Arc::new(self.client.clone());
-
-        stream::iter(urls)
-            .map(|url| {
-                let client = Arc::clone(&client);
+        stream::iter(targets)
+            .map(|target| {
                 let tx = tx.clone();
+                let pause = &pause;
                 async move {
-                    let _ = tx.send(TuiMessage::Scanned).await;
-
-                    let start = Instant::now();
-                    match client.request(&url, "GET", &[], None).await {
-                        Ok(response) => {
-                            let duration_ms = start.elapsed().as_millis() as u64;
-                            let result = ScanResult::from_response(url.clone(), &response, duration_ms);
-
-                            let tui_result = TuiResult {
-                                url: result.url,
-                                status_code: result.status_code,
-                                content_length: result.content_length,
-                                redirect_location: result.redirect_location,
-                                content_type: result.content_type,
-                                server: result.server,
-                                duration_ms: result.duration_ms,
-                            };
-
-                            let _ = tx.send(TuiMessage::Result(tui_result)).await;
+                    pause.wait().await;
+                    if self.error_budget.is_exhausted() {
+                        return;
+                    }
+                    tx.scanned();
+
+                    let outcome = self.execute(&target, &self.baseline).await;
+                    match &outcome {
+                        Ok(_) => self.error_budget.record_success(),
+                        Err(_) => self.error_budget.record_error(),
+                    }
+                    match outcome {
+                        Ok((result, _)) if !self.admit(&result, &self.baseline) => {
+                            self.record(&target, &result, true);
+                        }
+                        Ok((mut result, raw_body)) => {
+                            self.keep_finding_body(&mut result, raw_body);
+                            if self.record(&target, &result, false) {
+                                self.write_delta(&result);
+                            }
+                            if let Some(hook) = &self.on_hit {
+                                if self.status_filter.matches_url(&result.url, result.status_code) {
+                                    hook.spawn(&result);
+                                }
+                            }
+                            // Bodies read for the regex filters are not written out
+                            let body = result.body.clone().filter(|_| self.include_body);
+                            let result = ScanResult { body, ..result };
+                            if let Ok(mut shown) = self.tui_results.lock() {
+                                shown.push(result.clone());
+                            }
+                            tx.result(TuiResult::from(result)).await;
                         }
-                        Err(_) => {
-                            let _ = tx.send(TuiMessage::Error).await;
+                        Err(err) => {
+                            tx.error(&target.url, &err);
                         }
                     }
                 }
@@ -110,28 +746,177 @@ impl Scanner {
             .collect::<Vec<_>>()
             .await;
 
-        let _ = tx.send(TuiMessage::Done).await;
+        self.wait_for_hooks().await;
+        tx.done().await;
+        self.error_budget.check()
+    }
+
+    /// Requests a random path and keeps its status and size as the wildcard baseline
+    pub async fn detect_wildcard(&mut self, base_url: &str) -> Result<()> {
+        self.baseline.wildcard = self.wildcard_for(base_url).await;
         Ok(())
     }
 
-    pub async fn detect_wildcard(&self, base_url: &str) -> Result<()> {
-        let random_path = format!("{}/rustbuster-{}", base_url, uuid::Uuid::new_v4());
-        
-        match self.client.request(&random_path, "GET", &[], None).await {
-            Ok(response) => {
-                let status = response.status().as_u16();
-                if status == 200 {
-                    println!("[!] Warning: Wildcard response detected (Status: {})", status);
-                    println!("[!] This may produce false positives");
-                }
+    /// Captures soft-404 bodies from random paths when `--similarity-threshold` is set
+    pub async fn calibrate_soft_404(&mut self, base_url: &str) -> Result<()> {
+        self.baseline.soft_404 = self.soft_404_for(base_url).await?;
+        Ok(())
+    }
+
+    /// Calibrates the wildcard and soft-404 baselines of one directory without storing them
+    pub async fn baseline_for(&self, base_url: &str) -> Result<Baseline> {
+        Ok(Baseline {
+            wildcard: self.wildcard_for(base_url).await,
+            soft_404: self.soft_404_for(base_url).await?,
+        })
+    }
+
+    /// Status and size of a random path under `base_url`
+    ///
+    /// Only responses that would count as findings are kept; a plain 404 needs no filtering.
+    async fn wildcard_for(&self, base_url: &str) -> Option<WildcardSignature> {
+        let random_path = format!("{}/rustbuster-{}", base_url.trim_end_matches('/'), uuid::Uuid::new_v4());
+
+        let response = self
+            .client
+            .request(&random_path, &self.method, &self.headers, self.cookies.as_deref(), self.body.as_deref())
+            .await
+            .ok()?;
+        let result = ScanResult::from_response(random_path.clone(), &response, 0);
+        if !self.status_filter.matches_url(&random_path, result.status_code) {
+            return None;
+        }
+
+        println!(
+            "[!] Warning: Wildcard response detected (Status: {}, Size: {})",
+            result.status_code, result.content_length
+        );
+        if self.filter_wildcards {
+            println!("[!] Results with the same status and size will be filtered");
+        } else {
+            println!("[!] This may produce false positives");
+        }
+
+        Some(WildcardSignature {
+            status_code: result.status_code,
+            content_length: result.content_length,
+        })
+    }
+
+    async fn soft_404_for(&self, base_url: &str) -> Result<Option<Smart404Detector>> {
+        if self.similarity_threshold.is_none() {
+            return Ok(None);
+        }
+
+        let mut detector = Smart404Detector::new(true);
+        detector.calibrate(&self.client, base_url).await?;
+        Ok(Some(detector))
+    }
+
+    /// Whether a result has the status and size of the wildcard baseline
+    fn is_wildcard(&self, result: &ScanResult, baseline: &Baseline) -> bool {
+        self.filter_wildcards
+            && baseline.wildcard.as_ref().is_some_and(|signature| {
+                signature.status_code == result.status_code
+                    && signature.content_length == result.content_length
+            })
+    }
+
+    /// Whether a result looks like the wildcard or soft-404 response rather than real content
+    fn matches_baseline(&self, result: &ScanResult, baseline: &Baseline) -> bool {
+        let soft_404 = matches!(
+            (result.similarity, self.similarity_threshold),
+            (Some(similarity), Some(threshold)) if similarity > threshold
+        );
+        self.is_wildcard(result, baseline) || soft_404
+    }
+
+    /// Counts a response and tells whether it should be shown
+    ///
+    /// Wildcard and soft-404 responses are dropped, as are statuses outside `-s`/`-n` unless
+    /// `-e` is set, those outside `--min-time`/`--max-time`, `--only-dirs`/`--only-files`
+    /// and the size `--auto-filter` picks once warmed up. Responses past
+    /// `--limit-per-status` are tallied as suppressed instead of being shown.
+    fn admit(&self, result: &ScanResult, baseline: &Baseline) -> bool {
+        let group = self.status_filter.group(result.status_code);
+        if let Ok(mut counts) = self.status_counts.lock() {
+            *counts.entry(group.clone()).or_insert(0) += 1;
+        }
+
+        if self.matches_baseline(result, baseline) {
+            self.wildcard_hits.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+
+        if !self.expanded && !self.status_filter.matches_url(&result.url, result.status_code) {
+            return false;
+        }
+
+        let body = result.body.as_deref().unwrap_or("");
+        if self.match_regex.as_ref().is_some_and(|regex| !regex.is_match(body)) {
+            return false;
+        }
+        if self.filter_regex.as_ref().is_some_and(|regex| regex.is_match(body)) {
+            return false;
+        }
+
+        let too_fast = self.min_time.is_some_and(|min| result.duration_ms < min);
+        let too_slow = self.max_time.is_some_and(|max| result.duration_ms > max);
+        if too_fast || too_slow {
+            return false;
+        }
+
+        if self.only_dirs.is_some_and(|dirs| is_directory(result) != dirs) {
+            return false;
+        }
+
+        if self.auto_filter.as_ref().is_some_and(|auto| auto.filters(result.content_length)) {
+            return false;
+        }
+
+        let shown = match self.shown_counts.lock() {
+            Ok(mut counts) => {
+                let count = counts.entry(group.clone()).or_insert(0);
+                *count += 1;
+                *count
+            }
+            Err(_) => return true,
+        };
+
+        let within = self.limit_per_status.is_none_or(|limit| shown <= limit);
+        if !within {
+            if let Ok(mut suppressed) = self.suppressed_counts.lock() {
+                *suppressed.entry(group).or_insert(0) += 1;
             }
-            Err(_) => {}
         }
+        within
+    }
 
-        Ok(())
+    /// Responses hidden by `--limit-per-status`, keyed like `status_counts`
+    pub fn suppressed_counts(&self) -> BTreeMap<String, usize> {
+        self.suppressed_counts.lock().map(|counts| counts.clone()).unwrap_or_default()
     }
 
-    pub fn get_discovered_dirs(&self) -> Vec<String> {
-        self.discovered_dirs.clone()
+    /// Response counts keyed by status code, or by family with `--normalize-status`
+    pub fn status_counts(&self) -> BTreeMap<String, usize> {
+        self.status_counts.lock().map(|counts| counts.clone()).unwrap_or_default()
     }
 }
+
+/// The directory a result points at: its own `/`-terminated path, or a same-host redirect
+/// to one
+fn directory_url(result: &ScanResult) -> Option<Url> {
+    let url = Url::parse(&result.url).ok()?;
+    if url.path().ends_with('/') {
+        return Some(url);
+    }
+
+    let target = url.join(result.redirect_location.as_deref()?).ok()?;
+    let same_host = target.host_str() == url.host_str() && target.port() == url.port();
+    (same_host && target.path().ends_with('/')).then_some(target)
+}
+
+/// Whether a result is a directory hit rather than a file, for `--only-dirs`/`--only-files`
+pub fn is_directory(result: &ScanResult) -> bool {
+    directory_url(result).is_some() || result.is_directory_listing()
+}
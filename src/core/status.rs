@@ -0,0 +1,78 @@
+use crate::cli::CommonArgs;
+
+/// Decides which status codes count as findings and how they are grouped in counts
+///
+/// With `--normalize-status` every code stands for its whole family, so `-s 200`
+/// matches any 2xx and `-n 404` drops any 4xx. Rules from `--ext-status` replace
+/// the global lists for URLs with that extension.
+#[derive(Clone, Debug)]
+pub struct StatusFilter {
+    codes: Vec<u16>,
+    negative: Vec<u16>,
+    normalize: bool,
+    ext_rules: Vec<(String, Vec<u16>)>,
+}
+
+impl StatusFilter {
+    pub fn from_common(common: &CommonArgs) -> Self {
+        StatusFilter {
+            codes: common.get_status_codes(),
+            negative: common.get_negative_status_codes(),
+            normalize: common.normalize_status,
+            ext_rules: common.ext_status.clone(),
+        }
+    }
+
+    fn in_list(&self, list: &[u16], status: u16) -> bool {
+        if self.normalize {
+            list.iter().any(|code| code / 100 == status / 100)
+        } else {
+            list.contains(&status)
+        }
+    }
+
+    /// Negative codes win when given, otherwise the positive list applies (2xx if empty)
+    pub fn matches(&self, status: u16) -> bool {
+        if !self.negative.is_empty() {
+            !self.in_list(&self.negative, status)
+        } else if !self.codes.is_empty() {
+            self.in_list(&self.codes, status)
+        } else {
+            (200..300).contains(&status)
+        }
+    }
+
+    /// Like `matches`, but applies the `--ext-status` rule for the URL's extension if there is one
+    pub fn matches_url(&self, url: &str, status: u16) -> bool {
+        let rule = extension_of(url)
+            .and_then(|ext| self.ext_rules.iter().find(|(rule_ext, _)| *rule_ext == ext));
+
+        match rule {
+            Some((_, codes)) => self.in_list(codes, status),
+            None => self.matches(status),
+        }
+    }
+
+    /// The key a status is counted under: the exact code, or its family such as `4xx`
+    pub fn group(&self, status: u16) -> String {
+        if self.normalize {
+            format!("{}xx", status / 100)
+        } else {
+            status.to_string()
+        }
+    }
+}
+
+/// The lowercased extension of the last path segment, with its leading dot
+fn extension_of(url: &str) -> Option<String> {
+    let path = url::Url::parse(url)
+        .map(|parsed| parsed.path().to_string())
+        .unwrap_or_else(|_| url.to_string());
+    let segment = path.rsplit('/').next()?;
+    let (name, ext) = segment.rsplit_once('.')?;
+
+    if name.is_empty() || ext.is_empty() {
+        return None;
+    }
+    Some(format!(".{}", ext.to_lowercase()))
+}
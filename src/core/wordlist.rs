@@ -3,27 +3,53 @@
 //! This module handles loading wordlists from files and expanding them with extensions.
 //! Wordlists are used as the basis for brute-forcing directories, files, subdomains, and vhosts.
 
+use crate::cli::CommonArgs;
 use anyhow::{Context, Result};
+use flate2::bufread::MultiGzDecoder;
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::iter;
+use std::sync::Arc;
+
+/// First bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Marks where an extension goes inside a word instead of at its end
+const EXT_PLACEHOLDER: &str = "%EXT%";
 
 /// Represents a wordlist loaded from a file
 pub struct Wordlist {
     pub words: Vec<String>,
+    /// The file each entry of `words` was loaded from, index for index
+    sources: Vec<Arc<str>>,
 }
 
 impl Wordlist {
     /// Loads a wordlist from a file path
     /// 
-    /// Filters out empty lines and comments (lines starting with #)
+    /// Filters out empty lines, comments (lines starting with #) and lines that are not UTF-8.
+    /// Gzipped files are decompressed on the fly, whatever their extension.
     pub fn from_file(path: &str) -> Result<Self> {
         let file = File::open(path)
             .with_context(|| format!("Failed to open wordlist file: {}", path))?;
         
-        let reader = BufReader::new(file);
+        let mut reader = BufReader::new(file);
+        let is_gzip = reader
+            .fill_buf()
+            .with_context(|| format!("Failed to read wordlist file: {}", path))?
+            .starts_with(&GZIP_MAGIC);
+        let reader: Box<dyn BufRead> = if is_gzip {
+            Box::new(BufReader::new(MultiGzDecoder::new(reader)))
+        } else {
+            Box::new(reader)
+        };
+
         let words: Vec<String> = reader
-            .lines()
-            .filter_map(|line| line.ok())
+            .split(b'\n')
+            // A read error ends the list; a line that is not UTF-8 is skipped
+            .map_while(|line| line.ok())
+            .filter_map(|line| String::from_utf8(line).ok())
             .map(|line| line.trim().to_string())
             .filter(|line| !line.is_empty() && !line.starts_with('#'))
             .collect();
@@ -32,7 +58,106 @@ impl Wordlist {
             anyhow::bail!("Wordlist is empty or contains no valid entries");
         }
 
-        Ok(Wordlist { words })
+        let source: Arc<str> = Arc::from(path);
+        let sources = vec![source; words.len()];
+
+        Ok(Wordlist { words, sources })
+    }
+
+    /// Loads every -w file and combines them: a union by default, or only the words common
+    /// to all of them with `--wordlist-intersect`. The wordlist options are applied after.
+    pub fn from_common(common: &CommonArgs) -> Result<Self> {
+        let mut paths = common.wordlist.iter();
+        let first = paths.next().ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
+
+        let mut wordlist = Wordlist::from_file(first)?;
+        for path in paths {
+            let other = Wordlist::from_file(path)?;
+            wordlist = if common.wordlist_intersect {
+                wordlist.intersect(other)
+            } else {
+                wordlist.union(other)
+            };
+        }
+
+        if wordlist.is_empty() {
+            anyhow::bail!("The wordlists have no words in common");
+        }
+
+        wordlist.with_options(common).with_scan_range(common)
+    }
+
+    /// Applies `--wordlist-offset`/`--wordlist-limit` and then `--shard`
+    pub fn with_scan_range(self, common: &CommonArgs) -> Result<Wordlist> {
+        let mut wordlist = self;
+        if common.wordlist_offset.is_some() || common.wordlist_limit.is_some() {
+            let offset = common.wordlist_offset.unwrap_or(0);
+            wordlist = wordlist.slice(offset, common.wordlist_limit.map(|limit| limit as usize))?;
+        }
+        if let Some((index, count)) = common.shard {
+            wordlist = wordlist.shard(index, count);
+        }
+        Ok(wordlist)
+    }
+
+    /// Keeps `limit` words starting at `offset`, for `--wordlist-offset`/`--wordlist-limit`
+    ///
+    /// The slice is taken after the other wordlist options, so every machine splitting a scan
+    /// sees the same list. A limit past the end keeps the rest of the words.
+    pub fn slice(mut self, offset: usize, limit: Option<usize>) -> Result<Self> {
+        let len = self.words.len();
+        if offset >= len {
+            anyhow::bail!(
+                "--wordlist-offset {} is past the end of the wordlist ({} words)",
+                offset,
+                len
+            );
+        }
+        if limit == Some(0) {
+            anyhow::bail!("--wordlist-limit must be at least 1");
+        }
+
+        let end = limit.map_or(len, |limit| offset.saturating_add(limit).min(len));
+        self.words = self.words.drain(offset..end).collect();
+        self.sources = self.sources.drain(offset..end).collect();
+        Ok(self)
+    }
+
+    /// Keeps the words whose position modulo `count` is `index - 1`, for `--shard index/count`
+    ///
+    /// The `count` shards of a list are disjoint and together hold every word.
+    pub fn shard(self, index: usize, count: usize) -> Wordlist {
+        let (words, sources) = self
+            .words
+            .into_iter()
+            .zip(self.sources)
+            .enumerate()
+            .filter(|(position, _)| position % count == index - 1)
+            .map(|(_, entry)| entry)
+            .unzip();
+        Wordlist { words, sources }
+    }
+
+    /// Every word of either list once, in order of first appearance; each keeps the file
+    /// it was first seen in
+    pub fn union(mut self, other: Wordlist) -> Wordlist {
+        self.merge(other);
+        self.dedup();
+        self
+    }
+
+    /// The words of this list that `other` also contains, without repeats
+    pub fn intersect(self, other: Wordlist) -> Wordlist {
+        let common: HashSet<&str> = other.words.iter().map(String::as_str).collect();
+        let mut seen = HashSet::new();
+        let (words, sources) = self
+            .words
+            .iter()
+            .zip(&self.sources)
+            .filter(|(word, _)| common.contains(word.as_str()) && seen.insert(word.as_str()))
+            .map(|(word, source)| (word.clone(), Arc::clone(source)))
+            .unzip();
+        Wordlist { words, sources }
     }
 
     /// Returns the number of words in the wordlist
@@ -42,26 +167,185 @@ impl Wordlist {
     }
 
     /// Checks if the wordlist is empty
-    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.words.is_empty()
     }
 
+    /// Appends the entries of another wordlist, keeping track of where each came from
+    pub fn merge(&mut self, other: Wordlist) {
+        self.words.extend(other.words);
+        self.sources.extend(other.sources);
+    }
+
+    /// Returns the file the word at `index` was loaded from
+    #[allow(dead_code)]
+    pub fn source(&self, index: usize) -> Option<&str> {
+        self.sources.get(index).map(|s| s.as_ref())
+    }
+
+    /// Applies the wordlist options: `--unique`, `--wordlist-mutate`, `--prefix`/`--suffix`
+    /// and `--sort`
+    pub fn with_options(self, common: &CommonArgs) -> Wordlist {
+        let mut wordlist = self;
+        if common.unique {
+            let removed = wordlist.dedup();
+            if common.verbose {
+                eprintln!("[*] Removed {} duplicate words from the wordlist", removed);
+            }
+        }
+        if common.wordlist_mutate.iter().any(|mutation| mutation == "case") {
+            wordlist = wordlist.with_case_permutations();
+        }
+        if !common.prefix.is_empty() || !common.suffix.is_empty() {
+            wordlist = wordlist.with_affixes(&common.prefix, &common.suffix);
+        }
+        if common.sort {
+            wordlist.sort();
+        }
+        wordlist
+    }
+
+    /// Drops repeated words, keeping the first of each, and returns how many were removed
+    pub fn dedup(&mut self) -> usize {
+        let before = self.words.len();
+        *self = self.mutated(|word| vec![word.to_string()]);
+        before - self.words.len()
+    }
+
+    /// Sorts the words alphabetically, keeping each word's source
+    pub fn sort(&mut self) {
+        let mut entries: Vec<(String, Arc<str>)> = self.words.drain(..).zip(self.sources.drain(..)).collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        (self.words, self.sources) = entries.into_iter().unzip();
+    }
+
+    /// Adds the lowercase, uppercase and capitalized form of each word after it
+    ///
+    /// For example, "Admin" becomes ["Admin", "admin", "ADMIN"]; duplicates are dropped.
+    pub fn with_case_permutations(&self) -> Wordlist {
+        self.mutated(|word| {
+            vec![
+                word.to_string(),
+                word.to_lowercase(),
+                word.to_uppercase(),
+                capitalize(word),
+            ]
+        })
+    }
+
+    /// Replaces each word with every prefix and suffix combination around it
+    ///
+    /// An empty list leaves that side bare, so prefixes ["_", "."] alone turn "env" into
+    /// ["_env", ".env"]. Duplicates are dropped.
+    pub fn with_affixes(&self, prefixes: &[String], suffixes: &[String]) -> Wordlist {
+        let or_bare = |affixes: &[String]| -> Vec<String> {
+            if affixes.is_empty() {
+                vec![String::new()]
+            } else {
+                affixes.to_vec()
+            }
+        };
+        let prefixes = or_bare(prefixes);
+        let suffixes = or_bare(suffixes);
+
+        self.mutated(|word| {
+            prefixes
+                .iter()
+                .flat_map(|prefix| suffixes.iter().map(move |suffix| format!("{}{}{}", prefix, word, suffix)))
+                .collect()
+        })
+    }
+
+    /// Replaces each word with its variants, keeping the first of any repeated entry
+    fn mutated<F>(&self, variants: F) -> Wordlist
+    where
+        F: Fn(&str) -> Vec<String>,
+    {
+        let mut seen = HashSet::new();
+        let mut words = Vec::new();
+        let mut sources = Vec::new();
+
+        for (word, source) in self.words.iter().zip(&self.sources) {
+            for variant in variants(word) {
+                if seen.insert(variant.clone()) {
+                    words.push(variant);
+                    sources.push(Arc::clone(source));
+                }
+            }
+        }
+
+        Wordlist { words, sources }
+    }
+
     /// Expands the wordlist by appending file extensions to each word
     /// 
     /// For example, if the wordlist contains "admin" and extensions are [".php", ".html"],
-    /// the result will be ["admin", "admin.php", "admin.html"]
+    /// the result will be ["admin", "admin.php", "admin.html"].
+    /// Words containing `%EXT%` instead get it replaced by each extension without its dot,
+    /// so "config.%EXT%.bak" becomes ["config.php.bak", "config.html.bak"]; without extensions
+    /// such words are skipped with a warning.
+    #[allow(dead_code)]
     pub fn expand_with_extensions(&self, extensions: &[String]) -> Vec<String> {
+        self.expand_with_sources(extensions)
+            .into_iter()
+            .map(|(word, _)| word)
+            .collect()
+    }
+
+    /// Same as `expand_with_extensions`, but pairs every entry with its source wordlist
+    pub fn expand_with_sources(&self, extensions: &[String]) -> Vec<(String, Arc<str>)> {
+        self.expand(extensions, true)
+    }
+
+    /// Same as `expand_with_sources` without the bare words, so "admin" with [".bak"] only
+    /// yields "admin.bak"; used by `--no-bare-word`
+    pub fn expand_extensions_only(&self, extensions: &[String]) -> Vec<(String, Arc<str>)> {
+        self.expand(extensions, false)
+    }
+
+    fn expand(&self, extensions: &[String], include_bare: bool) -> Vec<(String, Arc<str>)> {
         let mut expanded = Vec::new();
+
+        if extensions.is_empty() {
+            let skipped = self.words.iter().filter(|word| word.contains(EXT_PLACEHOLDER)).count();
+            if skipped > 0 {
+                eprintln!(
+                    "[!] Skipping {} words containing {}: no extensions given",
+                    skipped, EXT_PLACEHOLDER
+                );
+            }
+        }
         
-        for word in &self.words {
-            expanded.push(word.clone());
+        for (word, source) in self.words.iter().zip(&self.sources) {
+            if word.contains(EXT_PLACEHOLDER) {
+                for ext in extensions {
+                    let ext = ext.strip_prefix('.').unwrap_or(ext);
+                    expanded.push((word.replace(EXT_PLACEHOLDER, ext), Arc::clone(source)));
+                }
+                continue;
+            }
+
+            if include_bare {
+                expanded.push((word.clone(), Arc::clone(source)));
+            }
             
             for ext in extensions {
-                expanded.push(format!("{}{}", word, ext));
+                expanded.push((format!("{}{}", word, ext), Arc::clone(source)));
             }
         }
         
         expanded
     }
 }
+
+/// Uppercases the first character and lowercases the rest
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => iter::once(first)
+            .flat_map(char::to_uppercase)
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
@@ -8,3 +8,5 @@ pub mod core;
 pub mod modes;
 // Output handling and TUI
 pub mod output;
+// Config, sessions, reports and other helpers
+pub mod utils;
@@ -6,38 +6,72 @@ mod utils;
 
 use anyhow::Result;
 use cli::{Cli, Commands};
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
+use modes::ScanSummary;
 use std::env;
+use std::process::ExitCode;
+
+/// Exit status for `--fail-on-findings` when something was found; errors exit with 1
+const FINDINGS_EXIT_CODE: u8 = 2;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let args: Vec<String> = env::args().collect();
+    // Set before anything prints, including the help pages handled ahead of clap
+    output::handler::init_color(args.iter().any(|arg| arg == "--no-color"));
     
     if args.contains(&"--show-args".to_string()) || args.contains(&"--arguments".to_string()) {
         cli::help::print_arguments_help();
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
     
     if args.contains(&"--examples".to_string()) {
         cli::help::print_examples();
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
     
     if args.contains(&"--info".to_string()) {
         cli::help::print_info();
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
     
-    utils::config::load_config();
-    
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    if let Some((_, mode_matches)) = matches.subcommand() {
+        cli::warn_missing_scheme(mode_matches);
+    }
+    let config = utils::config::load_config(cli.config.as_deref())?;
+    if let (Some(config), Some((mode, mode_matches))) = (&config, matches.subcommand()) {
+        let config = config.for_mode(mode);
+        if let Some(common) = cli.command.common_mut() {
+            config.apply(common, mode_matches)?;
+        }
+        if let Some(extensions) = cli.command.extensions_mut() {
+            config.apply_extensions(extensions, mode_matches);
+        }
+    }
+    if let Some(common) = cli.command.common_mut() {
+        common.quiet |= common.url_only;
+    }
+    let fail_on_findings = cli.command.common().is_some_and(|common| common.fail_on_findings);
     
-    match cli.command {
+    let summary = match cli.command {
         Commands::Dir(args) => modes::dir::run(args).await?,
         Commands::Dns(args) => modes::dns::run(args).await?,
         Commands::Vhost(args) => modes::vhost::run(args).await?,
         Commands::Fuzz(args) => modes::fuzz::run(args).await?,
-    }
+        Commands::Sessions(args) => {
+            modes::sessions::run(args)?;
+            ScanSummary::default()
+        }
+        Commands::Diff(args) => {
+            modes::diff::run(args)?;
+            ScanSummary::default()
+        }
+    };
     
-    Ok(())
+    if fail_on_findings && summary.found > 0 {
+        return Ok(ExitCode::from(FINDINGS_EXIT_CODE));
+    }
+    Ok(ExitCode::SUCCESS)
 }
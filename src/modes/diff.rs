@@ -0,0 +1,140 @@
+//! `rustbuster diff`: compares the json output of two scans by URL.
+//!
+//! Only the status and size decide whether a URL changed; timings and headers vary
+//! between runs and are ignored.
+
+use crate::cli::DiffArgs;
+use crate::output::serialize::ResultRecord;
+use anyhow::{Context, Result};
+use colored::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// How a URL differs between the old and new scan
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added(ResultRecord),
+    Removed(ResultRecord),
+    /// Same URL with a different status or size
+    Changed { old: Box<ResultRecord>, new: Box<ResultRecord> },
+}
+
+impl Change {
+    pub fn url(&self) -> &str {
+        match self {
+            Change::Added(entry) | Change::Removed(entry) => &entry.url,
+            Change::Changed { new, .. } => &new.url,
+        }
+    }
+}
+
+/// A json output file: a plain array, or an object with `results` from `--json-metadata`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ResultFile {
+    Array(Vec<ResultRecord>),
+    WithMetadata { results: Vec<ResultRecord> },
+}
+
+/// Reads the results of a json or jsonl output file
+pub fn load_results(path: &str) -> Result<Vec<ResultRecord>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read results: {}", path))?;
+
+    match serde_json::from_str::<ResultFile>(&content) {
+        Ok(ResultFile::Array(results)) | Ok(ResultFile::WithMetadata { results }) => Ok(results),
+        // Anything else has to be jsonl, one result per line
+        Err(_) => content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str(line).with_context(|| {
+                    format!("Failed to parse results: {} (line {})", path, index + 1)
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Added, removed and changed URLs, sorted by URL; a URL listed twice counts by its last entry
+pub fn diff(old: &[ResultRecord], new: &[ResultRecord]) -> Vec<Change> {
+    let by_url = |entries: &[ResultRecord]| -> BTreeMap<String, ResultRecord> {
+        entries.iter().map(|entry| (entry.url.clone(), entry.clone())).collect()
+    };
+    let old = by_url(old);
+    let mut new = by_url(new);
+
+    let mut changes = Vec::new();
+    for (url, old_entry) in old {
+        match new.remove(&url) {
+            None => changes.push(Change::Removed(old_entry)),
+            Some(new_entry)
+                if new_entry.status_code != old_entry.status_code
+                    || new_entry.content_length != old_entry.content_length =>
+            {
+                changes.push(Change::Changed {
+                    old: Box::new(old_entry),
+                    new: Box::new(new_entry),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    changes.extend(new.into_values().map(Change::Added));
+    changes.sort_by(|a, b| a.url().cmp(b.url()));
+    changes
+}
+
+pub fn run(args: DiffArgs) -> Result<()> {
+    let old = load_results(&args.old)?;
+    let new = load_results(&args.new)?;
+    let changes = diff(&old, &new);
+
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    for change in &changes {
+        match change {
+            Change::Added(entry) => {
+                added += 1;
+                println!(
+                    "{} {} [{}] [Size: {}]",
+                    "+".green().bold(),
+                    entry.url.green(),
+                    entry.status_code,
+                    entry.content_length
+                );
+            }
+            Change::Removed(entry) => {
+                removed += 1;
+                println!(
+                    "{} {} [{}] [Size: {}]",
+                    "-".red().bold(),
+                    entry.url.red(),
+                    entry.status_code,
+                    entry.content_length
+                );
+            }
+            Change::Changed { old, new } => {
+                changed += 1;
+                println!(
+                    "{} {} [{} -> {}] [Size: {} -> {}]",
+                    "~".yellow().bold(),
+                    new.url.yellow(),
+                    old.status_code,
+                    new.status_code,
+                    old.content_length,
+                    new.content_length
+                );
+            }
+        }
+    }
+
+    println!(
+        "\n{} {} added, {} removed, {} changed",
+        "Diff:".bright_yellow().bold(),
+        added,
+        removed,
+        changed
+    );
+    Ok(())
+}
@@ -1,30 +1,36 @@
 use crate::cli::DirArgs;
-use crate::core::{Scanner, Wordlist};
-use crate::output::tui;
-use anyhow::Result;
+use crate::core::{http_client, ScanTarget, Scanner, Wordlist};
+use crate::output::tui::{self, TuiOutput};
+use crate::utils::report;
+use crate::modes::recursion::Recursion;
+use crate::modes::ScanSummary;
+use crate::utils::session::Session;
+use anyhow::{Context, Result};
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use url::Url;
 
-pub async fn run(args: DirArgs) -> Result<()> {
+pub async fn run(args: DirArgs) -> Result<ScanSummary> {
     let base_url = Url::parse(&args.url)?;
+    http_client::preflight_proxy(&args.common, &args.url).await?;
+    http_client::preflight_target(&args.common, &args.url).await?;
     
     if !args.common.no_tui {
         return run_with_tui(args, base_url).await;
     }
     
-    if args.recursive {
+    if args.recursive || args.seed_urls.is_some() || args.recurse_on_status.is_some() {
         run_recursive(args, base_url).await
     } else {
         run_single(args, base_url).await
     }
 }
 
-async fn run_with_tui(args: DirArgs, base_url: Url) -> Result<()> {
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+async fn run_with_tui(args: DirArgs, base_url: Url) -> Result<ScanSummary> {
+    let wordlist = Wordlist::from_common(&args.common)?;
     
-    let mut extensions = args.common.get_extensions(&args.extensions);
+    let mut extensions = args.common.get_extensions(&args.extensions, &args.extensions_file)?;
     if args.backup_extensions {
         extensions.extend(vec![
             ".bak".to_string(),
@@ -38,50 +44,54 @@ async fn run_with_tui(args: DirArgs, base_url: Url) -> Result<()> {
         ]);
     }
     
-    let words = if !extensions.is_empty() {
-        wordlist.expand_with_extensions(&extensions)
-    } else {
-        wordlist.words.clone()
-    };
+    let paths = word_paths(&args, expand_words(&args, &wordlist, &extensions)?);
 
-    let urls: Vec<String> = words
+    let targets: Vec<ScanTarget> = paths
         .iter()
-        .map(|word| {
-            let path = if word.starts_with('/') {
-                word.clone()
-            } else {
-                format!("/{}", word)
-            };
-            
+        .map(|(path, source)| {
             let mut url = base_url.clone();
-            url.set_path(&path);
-            url.to_string()
+            url.set_path(path);
+            ScanTarget::new(url.to_string(), Some(Arc::clone(source)))
         })
         .collect();
 
-    let total = urls.len();
-    let scanner = Scanner::new_from_common(args.common.clone())?;
-    
-    tui::run_tui_mode(
+    let mut scanner = Scanner::new_from_common(args.common.clone())?;
+    if let Some(session) = Session::from_args(&args.common, &args.url)? {
+        scanner.set_session(Arc::new(Mutex::new(session)));
+    }
+    scanner.detect_wildcard(base_url.as_str()).await?;
+    scanner.calibrate_soft_404(base_url.as_str()).await?;
+    let targets = scanner.remaining_targets(targets);
+    let total = targets.len();
+
+    let scanner = Arc::new(scanner);
+    let scan = Arc::clone(&scanner);
+    let start = Instant::now();
+    let summary = tui::run_tui_mode(
         "dir".to_string(),
         args.url.clone(),
-        wordlist_path.clone(),
+        args.common.wordlist_label(),
         args.common.threads,
         total,
-        args.common.output.clone(),
-        args.common.output_format.clone(),
-        |tx| async move {
-            scanner.scan_urls_with_tui(urls, tx).await
+        TuiOutput::from_common(&args.common),
+        |tx, pause| async move {
+            scan.scan_urls_with_tui(targets, tx, pause).await
         },
-    ).await
+    ).await;
+    // Saved out here, as quitting the TUI cuts the scan task short
+    scanner.save_progress()?;
+    let results = scanner.tui_results();
+    report::write_report(&args.common, &args.url, &results, start.elapsed())?;
+    if args.common.summary_line {
+        println!("{}", report::summary_line(&args.common, &args.url, &results, start.elapsed()));
+    }
+    summary
 }
 
-async fn run_single(args: DirArgs, base_url: Url) -> Result<()> {
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+async fn run_single(args: DirArgs, base_url: Url) -> Result<ScanSummary> {
+    let wordlist = Wordlist::from_common(&args.common)?;
     
-    let mut extensions = args.common.get_extensions(&args.extensions);
+    let mut extensions = args.common.get_extensions(&args.extensions, &args.extensions_file)?;
     
     if args.backup_extensions {
         extensions.extend(vec![
@@ -96,44 +106,60 @@ async fn run_single(args: DirArgs, base_url: Url) -> Result<()> {
         ]);
     }
     
-    let words = if !extensions.is_empty() {
-        wordlist.expand_with_extensions(&extensions)
-    } else {
-        wordlist.words.clone()
-    };
+    let paths = word_paths(&args, expand_words(&args, &wordlist, &extensions)?);
 
-    let urls: Vec<String> = words
+    let targets: Vec<ScanTarget> = paths
         .iter()
-        .map(|word| {
-            let path = if word.starts_with('/') {
-                word.clone()
-            } else {
-                format!("/{}", word)
-            };
-            
+        .map(|(path, source)| {
             let mut url = base_url.clone();
-            url.set_path(&path);
-            url.to_string()
+            url.set_path(path);
+            ScanTarget::new(url.to_string(), Some(Arc::clone(source)))
         })
         .collect();
 
-    let mut scanner = Scanner::new_from_common(args.common)?;
+    let session = Session::from_args(&args.common, &args.url)?;
+    let mut scanner = Scanner::new_from_common(args.common.clone())?;
+    if let Some(session) = session {
+        scanner.set_session(Arc::new(Mutex::new(session)));
+    }
     scanner.detect_wildcard(base_url.as_str()).await?;
-    scanner.scan_urls(urls).await?;
+    scanner.calibrate_soft_404(base_url.as_str()).await?;
+
+    let start = Instant::now();
+    let results = scanner.scan_urls(targets).await?;
+    report::write_report(&args.common, &args.url, &results, start.elapsed())?;
+    if args.common.summary_line {
+        println!("{}", report::summary_line(&args.common, &args.url, &results, start.elapsed()));
+    }
 
-    Ok(())
+    Ok(ScanSummary { found: scanner.count_found(&results) })
 }
 
-async fn run_recursive(args: DirArgs, base_url: Url) -> Result<()> {
-    let max_depth = args.depth;
-    let mut scanned_dirs: HashSet<String> = HashSet::new();
-    let mut dirs_to_scan: Vec<(String, usize)> = vec![(base_url.to_string(), 0)];
+async fn run_recursive(args: DirArgs, base_url: Url) -> Result<ScanSummary> {
+    let recursion = Recursion {
+        common: &args.common,
+        max_depth: args.depth,
+        per_dir_budget: args.per_dir_timeout.map(Duration::from_secs),
+        stop_on_first: args.stop_on_first,
+    };
+    let mut scanner = Scanner::new_from_common(args.common.clone())?;
+    if let Some(statuses) = args.get_recurse_statuses() {
+        scanner.set_recurse_statuses(statuses);
+    }
+    if let Some(session) = Session::from_args(&args.common, &args.url)? {
+        scanner.set_session(Arc::new(Mutex::new(session)));
+    }
+    let start = Instant::now();
+
+    // Known directories replace the root as starting points
+    let start_dirs = match &args.seed_urls {
+        Some(seed_file) => load_seed_urls(seed_file, &base_url)?,
+        None => vec![base_url.to_string()],
+    };
     
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+    let wordlist = Wordlist::from_common(&args.common)?;
     
-    let mut extensions = args.common.get_extensions(&args.extensions);
+    let mut extensions = args.common.get_extensions(&args.extensions, &args.extensions_file)?;
     if args.backup_extensions {
         extensions.extend(vec![
             ".bak".to_string(),
@@ -147,56 +173,94 @@ async fn run_recursive(args: DirArgs, base_url: Url) -> Result<()> {
         ]);
     }
     
-    let words = if !extensions.is_empty() {
-        wordlist.expand_with_extensions(&extensions)
-    } else {
-        wordlist.words.clone()
-    };
+    let paths = word_paths(&args, expand_words(&args, &wordlist, &extensions)?);
 
-    while let Some((current_url, depth)) = dirs_to_scan.pop() {
-        if depth > max_depth || scanned_dirs.contains(&current_url) {
-            continue;
-        }
+    let all_results = recursion
+        .run(&scanner, start_dirs, |current_base| {
+            paths
+                .iter()
+                .map(|(path, source)| {
+                    let mut url = current_base.clone();
+                    let current_path = url.path().trim_end_matches('/');
+                    url.set_path(&format!("{}{}", current_path, path));
+                    ScanTarget::new(url.to_string(), Some(Arc::clone(source)))
+                })
+                .collect()
+        })
+        .await?;
+
+    report::write_report(&args.common, &args.url, &all_results, start.elapsed())?;
+    if args.common.summary_line {
+        println!("{}", report::summary_line(&args.common, &args.url, &all_results, start.elapsed()));
+    }
+
+    Ok(ScanSummary { found: scanner.count_found(&all_results) })
+}
+
+/// Expands the wordlist with the extensions, leaving out the bare words with `--no-bare-word`
+fn expand_words(args: &DirArgs, wordlist: &Wordlist, extensions: &[String]) -> Result<Vec<(String, Arc<str>)>> {
+    if !args.no_bare_word {
+        return Ok(wordlist.expand_with_sources(extensions));
+    }
+    if extensions.is_empty() {
+        anyhow::bail!("--no-bare-word needs extensions from -x, --extensions-file or --backup-extensions");
+    }
+    Ok(wordlist.expand_extensions_only(extensions))
+}
 
-        scanned_dirs.insert(current_url.clone());
+/// Turns expanded words into absolute paths, adding the trailing-slash variant of each with
+/// `--add-slash` or using only that variant with `--slash-only`. A word that already ends in
+/// `/` is requested once either way.
+pub fn word_paths(args: &DirArgs, words: Vec<(String, Arc<str>)>) -> Vec<(String, Arc<str>)> {
+    let mut seen = HashSet::new();
+    let mut paths = Vec::new();
 
-        if !args.common.quiet {
-            println!("\n[*] Scanning: {} (depth: {})", current_url, depth);
+    for (word, source) in words {
+        let path = if word.starts_with('/') { word } else { format!("/{}", word) };
+        let variants = if path.ends_with('/') {
+            vec![path]
+        } else if args.slash_only {
+            vec![format!("{}/", path)]
+        } else if args.add_slash {
+            vec![path.clone(), format!("{}/", path)]
+        } else {
+            vec![path]
+        };
+
+        for variant in variants {
+            if seen.insert(variant.clone()) {
+                paths.push((variant, Arc::clone(&source)));
+            }
         }
+    }
+    paths
+}
+
+/// Loads the directories listed in a seed file, resolving relative paths against the base URL
+fn load_seed_urls(path: &str, base_url: &Url) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read seed URLs file: {}", path))?;
 
-        let current_base = Url::parse(&current_url)?;
-
-        let urls: Vec<String> = words
-            .iter()
-            .map(|word| {
-                let path = if word.starts_with('/') {
-                    word.clone()
-                } else {
-                    format!("/{}", word)
-                };
-                
-                let mut url = current_base.clone();
-                let current_path = url.path().trim_end_matches('/');
-                url.set_path(&format!("{}{}", current_path, path));
-                url.to_string()
-            })
-            .collect();
-
-        let mut scanner = Scanner::new_from_common(args.common.clone())?;
-        
-        if depth == 0 {
-            scanner.detect_wildcard(current_base.as_str()).await?;
+    let mut seeds = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
-        
-        scanner.scan_urls(urls).await?;
 
-        let discovered = scanner.get_discovered_dirs();
-        for dir in discovered {
-            if !scanned_dirs.contains(&dir) {
-                dirs_to_scan.push((dir, depth + 1));
-            }
+        let mut url = base_url
+            .join(line)
+            .with_context(|| format!("Invalid seed URL: {}", line))?;
+        if !url.path().ends_with('/') {
+            let path = format!("{}/", url.path());
+            url.set_path(&path);
         }
+        seeds.push(url.to_string());
+    }
+
+    if seeds.is_empty() {
+        anyhow::bail!("Seed URLs file is empty or contains no valid entries");
     }
 
-    Ok(())
+    Ok(seeds)
 }
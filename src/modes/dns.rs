@@ -1,35 +1,78 @@
 use crate::cli::DnsArgs;
-use crate::core::Wordlist;
-use crate::output::{tui, OutputHandler};
-use crate::output::tui::{TuiMessage, TuiResult};
-use anyhow::Result;
+use crate::core::doh::DohResolver;
+use crate::core::error_budget::ErrorBudget;
+use crate::modes::ScanSummary;
+use crate::core::{HttpClient, Wordlist};
+use crate::output::{progress, tui, OutputHandler};
+use crate::output::tui::{PauseGate, TuiOutput, TuiResult, TuiSender};
+use anyhow::{anyhow, Context, Result};
 use colored::*;
 use futures::stream::{self, StreamExt};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressBar;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::Instant;
 use trust_dns_resolver::config::*;
+use trust_dns_resolver::lookup::Lookup;
+use trust_dns_resolver::proto::rr::{RData, RecordType};
+use trust_dns_resolver::error::{ResolveError, ResolveErrorKind};
 use trust_dns_resolver::TokioAsyncResolver;
-use tokio::sync::mpsc;
 
-pub async fn run(args: DnsArgs) -> Result<()> {
+pub async fn run(args: DnsArgs) -> Result<ScanSummary> {
+    let resolver = Resolver::from_args(&args)?;
+
+    if !args.force {
+        verify_base_domain(&args.domain, |domain| {
+            let resolver = &resolver;
+            async move {
+                resolver.lookup(&domain, None).await.is_ok()
+                    || resolver.lookup(&domain, Some(RecordType::NS)).await.is_ok()
+            }
+        })
+        .await?;
+    }
+
+    let record_type = parse_record_type(args.record_type.as_deref())?;
+
+    let wildcard = detect_wildcard(&args.domain, |name| {
+        let resolver = &resolver;
+        async move { resolver.lookup(&name, record_type).await.unwrap_or_default() }
+    })
+    .await;
+    if let Some(records) = &wildcard {
+        let mut records: Vec<&str> = records.iter().map(String::as_str).collect();
+        records.sort_unstable();
+        println!(
+            "[!] Warning: Wildcard DNS detected, random subdomains resolve to {}",
+            records.join(", ")
+        );
+        if args.common.wildcard {
+            println!("[!] This may produce false positives");
+        } else {
+            println!("[!] Subdomains resolving to the same records will be filtered");
+        }
+    }
+    // --wildcard keeps the lookalikes, as in the HTTP modes
+    let wildcard = wildcard.filter(|_| !args.common.wildcard);
+
     if !args.common.no_tui {
-        return run_with_tui(args).await;
+        return run_with_tui(args, resolver, record_type, wildcard).await;
     }
 
     let output = OutputHandler::new(
         args.common.output.clone(),
-        args.common.quiet,
+        args.common.quiet || args.common.url_only,
         args.common.output_format.clone(),
         args.common.verbose,
-    );
+    )
+    .with_append(args.common.output_append);
     output.print_banner_common(&args.common);
 
     // Load wordlist
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+    let wordlist = Wordlist::from_common(&args.common)?;
 
     // Generate subdomains to test
     let subdomains: Vec<String> = wordlist
@@ -41,27 +84,18 @@ pub async fn run(args: DnsArgs) -> Result<()> {
     let total = subdomains.len();
     let found = Arc::new(AtomicUsize::new(0));
     let found_clone = Arc::clone(&found);
+    let filtered = AtomicUsize::new(0);
+    let error_budget = ErrorBudget::from_common(&args.common);
 
     // Setup progress bar
-    let progress = if !args.common.no_progress && !args.common.quiet {
+    let progress = if !args.common.no_progress && !args.common.quiet && !args.common.url_only {
         let pb = ProgressBar::new(total as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
-                .unwrap()
-                .progress_chars("=>-"),
-        );
+        pb.set_style(progress::progress_style());
         Some(pb)
     } else {
         None
     };
 
-    // Create DNS resolver
-    let resolver = TokioAsyncResolver::tokio(
-        ResolverConfig::default(),
-        ResolverOpts::default(),
-    );
-
     // Process subdomains concurrently
     stream::iter(subdomains)
         .map(|subdomain| {
@@ -70,34 +104,36 @@ pub async fn run(args: DnsArgs) -> Result<()> {
             let progress = &progress;
             let expanded = args.common.expanded;
             let show_ips = args.show_ips;
-            let quiet = args.common.quiet;
+            let quiet = args.common.quiet || args.common.url_only;
+            let url_only = args.common.url_only;
+            let wildcard = wildcard.as_ref();
+            let filtered = &filtered;
+            let error_budget = &error_budget;
 
             async move {
+                if error_budget.is_exhausted() {
+                    return;
+                }
                 if let Some(pb) = progress {
                     pb.inc(1);
                 }
 
-                let start = Instant::now();
-                match resolver.lookup_ip(&subdomain).await {
-                    Ok(response) => {
-                        let _duration_ms = start.elapsed().as_millis() as u64;
-                        
-                        let ips: Vec<String> = response
-                            .iter()
-                            .map(|ip| ip.to_string())
-                            .collect();
-
-                        if !ips.is_empty() {
+                let outcome = resolver.lookup(&subdomain, record_type).await;
+                record_outcome(error_budget, &outcome);
+                match outcome {
+                    Ok(records) if matches_wildcard(&records, wildcard) => {
+                        filtered.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Ok(records) => {
+                        if !records.is_empty() {
                             found.fetch_add(1, Ordering::SeqCst);
-                            if !quiet {
-                                if show_ips {
-                                    println!(
-                                        "{} -> {}",
-                                        subdomain.bright_white(),
-                                        ips.join(", ").bright_green()
-                                    );
-                                } else {
-                                    println!("{}", subdomain.bright_white());
+                            if url_only {
+                                println!("{}", subdomain);
+                            } else if !quiet {
+                                let line = result_line(&subdomain, record_type, &records, show_ips);
+                                match progress {
+                                    Some(pb) => pb.suspend(|| println!("{}", line)),
+                                    None => println!("{}", line),
                                 }
                             }
                         }
@@ -110,7 +146,7 @@ pub async fn run(args: DnsArgs) -> Result<()> {
                 }
             }
         })
-        .buffer_unordered(args.common.threads)
+        .buffer_unordered(args.common.get_dns_threads())
         .collect::<Vec<_>>()
         .await;
 
@@ -120,14 +156,194 @@ pub async fn run(args: DnsArgs) -> Result<()> {
 
     let found_count = found.load(Ordering::SeqCst);
     output.print_summary(total, found_count);
+    output.print_wildcard_filtered(filtered.load(Ordering::SeqCst));
+
+    error_budget.check()?;
+    Ok(ScanSummary { found: found_count })
+}
+
+/// Feeds a lookup into the error budget; a name that does not exist is an answer, not a failure
+fn record_outcome(error_budget: &ErrorBudget, outcome: &Result<Vec<String>>) {
+    match outcome {
+        Err(err) if is_resolver_failure(err) => error_budget.record_error(),
+        _ => error_budget.record_success(),
+    }
+}
+
+/// Whether a failed lookup means the resolver could not answer, such as a timeout or a
+/// refused or unreachable DoH endpoint, rather than that the name has no records
+fn is_resolver_failure(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<ResolveError>() {
+        Some(err) => !matches!(err.kind(), ResolveErrorKind::NoRecordsFound { .. }),
+        None => err.chain().any(|cause| cause.is::<reqwest::Error>()),
+    }
+}
+
+/// Builds the resolver configuration, using `--resolver`/`--resolvers-file` nameservers when given
+pub fn resolver_config(args: &DnsArgs) -> Result<ResolverConfig> {
+    let mut entries: Vec<String> = args
+        .resolver
+        .as_deref()
+        .map(|list| list.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+
+    if let Some(path) = &args.resolvers_file {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read resolvers file: {}", path))?;
+        entries.extend(content.lines().map(str::to_string));
+    }
+
+    let mut name_servers = NameServerConfigGroup::new();
+    for entry in entries {
+        let entry = entry.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            continue;
+        }
+
+        let addr = entry
+            .parse::<SocketAddr>()
+            .or_else(|_| entry.parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 53)))
+            .map_err(|_| anyhow!("Invalid resolver address: {}", entry))?;
+        name_servers.push(NameServerConfig::new(addr, Protocol::Udp));
+        name_servers.push(NameServerConfig::new(addr, Protocol::Tcp));
+    }
+
+    if name_servers.is_empty() {
+        if args.resolver.is_some() || args.resolvers_file.is_some() {
+            return Err(anyhow!("No resolver addresses given"));
+        }
+        return Ok(ResolverConfig::default());
+    }
+    Ok(ResolverConfig::from_parts(None, Vec::new(), name_servers))
+}
 
-    Ok(())
+/// Maps `--record-type` to a record type; none means the default A/AAAA lookup
+fn parse_record_type(record_type: Option<&str>) -> Result<Option<RecordType>> {
+    record_type
+        .map(|name| {
+            name.to_uppercase()
+                .parse::<RecordType>()
+                .map_err(|_| anyhow!("Unsupported record type: {}", name))
+        })
+        .transpose()
+}
+
+/// Where names are resolved: the system or `--resolver` nameservers, or a DoH endpoint
+#[derive(Clone)]
+enum Resolver {
+    Dns(Box<TokioAsyncResolver>),
+    Doh(Box<DohResolver>),
 }
 
-async fn run_with_tui(args: DnsArgs) -> Result<()> {
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+impl Resolver {
+    fn from_args(args: &DnsArgs) -> Result<Self> {
+        match &args.doh {
+            Some(endpoint) => {
+                let client = HttpClient::new_from_common(&args.common)?;
+                Ok(Resolver::Doh(Box::new(DohResolver::new(client, endpoint)?)))
+            }
+            None => Ok(Resolver::Dns(Box::new(TokioAsyncResolver::tokio(
+                resolver_config(args)?,
+                ResolverOpts::default(),
+            )))),
+        }
+    }
+
+    /// Resolves one name, returning each record formatted for display
+    ///
+    /// Without a record type both A and AAAA addresses are returned.
+    async fn lookup(&self, name: &str, record_type: Option<RecordType>) -> Result<Vec<String>> {
+        match (self, record_type) {
+            (Resolver::Dns(resolver), Some(record_type)) => {
+                Ok(format_lookup(&resolver.lookup(name, record_type).await?))
+            }
+            (Resolver::Dns(resolver), None) => {
+                Ok(resolver.lookup_ip(name).await?.iter().map(|ip| ip.to_string()).collect())
+            }
+            (Resolver::Doh(doh), Some(record_type)) => doh.lookup(name, record_type).await,
+            (Resolver::Doh(doh), None) => {
+                let (v4, v6) = tokio::join!(doh.lookup(name, RecordType::A), doh.lookup(name, RecordType::AAAA));
+                match (v4, v6) {
+                    (Err(err), Err(_)) => Err(err),
+                    (v4, v6) => Ok(v4.unwrap_or_default().into_iter().chain(v6.unwrap_or_default()).collect()),
+                }
+            }
+        }
+    }
+}
+
+/// Formats the records of a lookup, quoting TXT data and showing MX as `preference exchange`
+pub fn format_lookup(lookup: &Lookup) -> Vec<String> {
+    lookup
+        .iter()
+        .map(|rdata| match rdata {
+            RData::TXT(txt) => format!("\"{}\"", txt),
+            RData::MX(mx) => format!("{} {}", mx.preference(), mx.exchange()),
+            other => other.to_string(),
+        })
+        .collect()
+}
+
+/// The line printed for a resolved subdomain
+///
+/// Records of an explicit `--record-type` are always shown, addresses only with `--show-ips`.
+pub fn result_line(subdomain: &str, record_type: Option<RecordType>, records: &[String], show_ips: bool) -> String {
+    match record_type {
+        Some(record_type) => format!(
+            "{} [{}] -> {}",
+            subdomain.bright_white(),
+            record_type,
+            records.join(", ").bright_green()
+        ),
+        None if show_ips => format!("{} -> {}", subdomain.bright_white(), records.join(", ").bright_green()),
+        None => subdomain.bright_white().to_string(),
+    }
+}
+
+/// Resolves a random subdomain and returns its records when the domain answers every name
+pub async fn detect_wildcard<F, Fut>(domain: &str, lookup: F) -> Option<HashSet<String>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Vec<String>>,
+{
+    let probe = format!("rustbuster-{}.{}", uuid::Uuid::new_v4(), domain);
+    let records: HashSet<String> = lookup(probe).await.into_iter().collect();
+    (!records.is_empty()).then_some(records)
+}
+
+/// Whether a subdomain resolved to exactly the wildcard record set
+pub fn matches_wildcard(records: &[String], wildcard: Option<&HashSet<String>>) -> bool {
+    wildcard.is_some_and(|wildcard| {
+        !records.is_empty() && records.iter().cloned().collect::<HashSet<_>>() == *wildcard
+    })
+}
+
+/// Aborts before the scan when the base domain itself does not resolve
+///
+/// A typo in the domain or a broken resolver would otherwise turn every word into an
+/// NXDOMAIN. `lookup` reports whether a name has any usable record.
+pub async fn verify_base_domain<F, Fut>(domain: &str, lookup: F) -> Result<()>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    if lookup(domain.to_string()).await {
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "Base domain {} does not resolve; check the domain and resolver, or pass --force to scan anyway",
+        domain
+    ))
+}
+
+async fn run_with_tui(
+    args: DnsArgs,
+    resolver: Resolver,
+    record_type: Option<RecordType>,
+    wildcard: Option<HashSet<String>>,
+) -> Result<ScanSummary> {
+    let wordlist = Wordlist::from_common(&args.common)?;
 
     let subdomains: Vec<String> = wordlist
         .words
@@ -136,66 +352,82 @@ async fn run_with_tui(args: DnsArgs) -> Result<()> {
         .collect();
 
     let total = subdomains.len();
-    let threads = args.common.threads;
+    let threads = args.common.get_dns_threads();
     let domain = args.domain.clone();
+    let error_budget = ErrorBudget::from_common(&args.common);
     
     tui::run_tui_mode(
         "dns".to_string(),
         domain.clone(),
-        wordlist_path.clone(),
+        args.common.wordlist_label(),
         threads,
         total,
-        args.common.output.clone(),
-        args.common.output_format.clone(),
-        move |tx| async move {
-            scan_dns_with_tui(subdomains, threads, tx).await
+        TuiOutput::from_common(&args.common),
+        move |tx, pause| async move {
+            scan_dns_with_tui(subdomains, threads, resolver, record_type, wildcard, error_budget, tx, pause).await
         },
     ).await
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn scan_dns_with_tui(
     subdomains: Vec<String>,
     threads: usize,
-    tx: mpsc::Sender<TuiMessage>,
+    resolver: Resolver,
+    record_type: Option<RecordType>,
+    wildcard: Option<HashSet<String>>,
+    error_budget: ErrorBudget,
+    tx: TuiSender,
+    pause: PauseGate,
 ) -> Result<()> {
-    let resolver = TokioAsyncResolver::tokio(
-        ResolverConfig::default(),
-        ResolverOpts::default(),
-    );
-
     stream::iter(subdomains)
         .map(|subdomain| {
             let resolver = &resolver;
             let tx = tx.clone();
+            let wildcard = wildcard.as_ref();
+            let pause = &pause;
+            let error_budget = &error_budget;
 
             async move {
-                let _ = tx.send(TuiMessage::Scanned).await;
+                pause.wait().await;
+                if error_budget.is_exhausted() {
+                    return;
+                }
+                tx.scanned();
 
                 let start = Instant::now();
-                match resolver.lookup_ip(&subdomain).await {
-                    Ok(response) => {
+                let outcome = resolver.lookup(&subdomain, record_type).await;
+                record_outcome(error_budget, &outcome);
+                match outcome {
+                    Ok(records) if matches_wildcard(&records, wildcard) => {}
+                    Ok(records) => {
                         let duration_ms = start.elapsed().as_millis() as u64;
-                        
-                        let ips: Vec<String> = response
-                            .iter()
-                            .map(|ip| ip.to_string())
-                            .collect();
 
-                        if !ips.is_empty() {
+                        if !records.is_empty() {
                             let result = TuiResult {
                                 url: subdomain,
                                 status_code: 200,
                                 content_length: 0,
-                                redirect_location: Some(ips.join(", ")),
-                                content_type: None,
+                                redirect_location: Some(records.join(", ")),
+                                content_type: record_type.map(|t| t.to_string()),
                                 server: None,
                                 duration_ms,
+                                source: None,
+                                golden_diff: None,
+                                ttfb_ms: None,
+                                allowed_methods: None,
+                                auth_scheme: None,
+                                final_url: None,
+                                redirect_count: 0,
+                                headers: Default::default(),
+                                technologies: Vec::new(),
+                                body: None,
                             };
-                            let _ = tx.send(TuiMessage::Result(result)).await;
+                            tx.result(result).await;
                         }
                     }
-                    Err(_) => {
-                        let _ = tx.send(TuiMessage::Error).await;
+                    Err(err) => {
+                        tx.error(subdomain, &err);
                     }
                 }
             }
@@ -204,6 +436,6 @@ async fn scan_dns_with_tui(
         .collect::<Vec<_>>()
         .await;
 
-    let _ = tx.send(TuiMessage::Done).await;
-    Ok(())
+    tx.done().await;
+    error_budget.check()
 }
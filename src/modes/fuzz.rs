@@ -1,49 +1,327 @@
 use crate::cli::FuzzArgs;
-use crate::core::{Scanner, Wordlist};
-use crate::output::tui;
+use crate::core::{http_client, ScanTarget, Scanner, Wordlist};
+use crate::modes::recursion::Recursion;
+use crate::modes::ScanSummary;
+use crate::output::tui::{self, TuiOutput};
+use crate::utils::report;
+use crate::utils::session::Session;
+use crate::utils::template::substitute;
 use anyhow::{Result, anyhow};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-pub async fn run(args: FuzzArgs) -> Result<()> {
-    if !args.url.contains("FUZZ") {
-        return Err(anyhow!("URL must contain the FUZZ keyword (e.g., http://example.com/FUZZ)"));
-    }
-    
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
-    
-    let extensions = args.common.get_extensions(&args.extensions);
-    let words = if !extensions.is_empty() {
-        wordlist.expand_with_extensions(&extensions)
-    } else {
-        wordlist.words.clone()
-    };
+/// Most requests several keywords may combine into; the targets are built before the scan
+/// starts, so a larger clusterbomb is refused instead of exhausting memory
+pub const MAX_COMBINED_REQUESTS: usize = 5_000_000;
+
+/// A fuzz keyword and the words bound to it
+struct KeywordList {
+    keyword: String,
+    path: String,
+    words: Vec<(String, Arc<str>)>,
+}
 
-    let urls: Vec<String> = words
+pub async fn run(args: FuzzArgs) -> Result<ScanSummary> {
+    let extensions = args.common.get_extensions(&args.extensions, &args.extensions_file)?;
+    if args.no_bare_word && extensions.is_empty() {
+        return Err(anyhow!("--no-bare-word needs extensions from -x or --extensions-file"));
+    }
+    let keywords = load_keywords(&args, &extensions)?;
+
+    let data = args.common.get_data()?;
+    let headers = args.common.get_headers();
+    let cookies = args.common.cookies.clone();
+
+    let contains_keyword = |text: &str| keywords.iter().any(|k| text.contains(&k.keyword));
+    let headers_have_keyword = headers
         .iter()
-        .map(|word| args.url.replace("FUZZ", word))
-        .collect();
+        .any(|(key, value)| contains_keyword(key) || contains_keyword(value));
+    let cookies_have_keyword = cookies.as_deref().is_some_and(contains_keyword);
+
+    for keyword in &keywords {
+        let keyword = keyword.keyword.as_str();
+        let used = args.url.contains(keyword)
+            || data.as_deref().is_some_and(|body| body.contains(keyword))
+            || headers.iter().any(|(key, value)| key.contains(keyword) || value.contains(keyword))
+            || cookies.as_deref().is_some_and(|c| c.contains(keyword));
+
+        if !used {
+            return Err(anyhow!(
+                "URL, request body, headers or cookies must contain the {} keyword (e.g., http://example.com/{})",
+                keyword,
+                keyword
+            ));
+        }
+    }
+
+    http_client::preflight_proxy(&args.common, &args.url).await?;
+    // Only the origin is fixed, and not even that when the host holds a keyword
+    if let Ok(url) = url::Url::parse(&args.url) {
+        let host = url.host_str().unwrap_or_default().to_lowercase();
+        if !keywords.iter().any(|k| host.contains(&k.keyword.to_lowercase())) {
+            let origin = format!("{}/", url.origin().ascii_serialization());
+            http_client::preflight_target(&args.common, &origin).await?;
+        }
+    }
+
+    let lengths: Vec<usize> = keywords.iter().map(|k| k.words.len()).collect();
+    let total_requests = Combinations::new(&lengths, &args.fuzz_mode).total();
+    if keywords.len() > 1 && total_requests > MAX_COMBINED_REQUESTS {
+        return Err(anyhow!(
+            "The keyword lists combine into {} requests, more than the limit of {}; use --fuzz-mode pitchfork, shorter lists or --shard",
+            total_requests,
+            MAX_COMBINED_REQUESTS
+        ));
+    }
+
+    if keywords.len() > 1 && !args.common.quiet {
+        let names: Vec<&str> = keywords.iter().map(|k| k.keyword.as_str()).collect();
+        let strategy = if args.fuzz_mode == "pitchfork" {
+            "words paired line by line"
+        } else {
+            "every combination of words"
+        };
+        println!(
+            "[*] Fuzz mode: {} ({} for {}), {} requests",
+            args.fuzz_mode,
+            strategy,
+            names.join(", "),
+            total_requests
+        );
+    }
+
+    let targets_for = |template: &str| -> Vec<ScanTarget> {
+        Combinations::new(&lengths, &args.fuzz_mode)
+            .map(|combination| {
+                let bindings: Vec<(&str, &str)> = keywords
+                    .iter()
+                    .zip(&combination)
+                    .map(|(k, &index)| (k.keyword.as_str(), k.words[index].0.as_str()))
+                    .collect();
+
+                let source = if keywords.len() == 1 {
+                    Arc::clone(&keywords[0].words[combination[0]].1)
+                } else {
+                    let sources: Vec<&str> = keywords
+                        .iter()
+                        .zip(&combination)
+                        .map(|(k, &index)| k.words[index].1.as_ref())
+                        .collect();
+                    Arc::from(sources.join(","))
+                };
+
+                let body = data.as_ref().map(|body| substitute(body, &bindings));
+                let mut target = ScanTarget::new(substitute(template, &bindings), Some(source))
+                    .with_body(body);
+
+                if headers_have_keyword {
+                    target = target.with_headers(Some(
+                        headers
+                            .iter()
+                            .map(|(key, value)| (substitute(key, &bindings), substitute(value, &bindings)))
+                            .collect(),
+                    ));
+                }
+                if cookies_have_keyword {
+                    target = target.with_cookies(cookies.as_ref().map(|c| substitute(c, &bindings)));
+                }
+
+                target
+            })
+            .collect()
+    };
 
     if !args.common.no_tui {
-        let total = urls.len();
-        let scanner = Scanner::new_from_common(args.common.clone())?;
-        
-        return tui::run_tui_mode(
+        let mut scanner = Scanner::new_from_common(args.common.clone())?;
+        if let Some(session) = Session::from_args(&args.common, &args.url)? {
+            scanner.set_session(Arc::new(Mutex::new(session)));
+        }
+        let targets = scanner.remaining_targets(targets_for(&args.url));
+        let total = targets.len();
+        let wordlist_label = keywords
+            .iter()
+            .map(|k| k.path.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let scanner = Arc::new(scanner);
+        let scan = Arc::clone(&scanner);
+        let start = Instant::now();
+        let summary = tui::run_tui_mode(
             "fuzz".to_string(),
             args.url.clone(),
-            wordlist_path.clone(),
+            wordlist_label,
             args.common.threads,
             total,
-            args.common.output.clone(),
-            args.common.output_format.clone(),
-            |tx| async move {
-                scanner.scan_urls_with_tui(urls, tx).await
+            TuiOutput::from_common(&args.common),
+            |tx, pause| async move {
+                scan.scan_urls_with_tui(targets, tx, pause).await
             },
         ).await;
+        // Saved out here, as quitting the TUI cuts the scan task short
+        scanner.save_progress()?;
+        let results = scanner.tui_results();
+        report::write_report(&args.common, &args.url, &results, start.elapsed())?;
+        if args.common.summary_line {
+            println!("{}", report::summary_line(&args.common, &args.url, &results, start.elapsed()));
+        }
+        return summary;
+    }
+
+    let session = Session::from_args(&args.common, &args.url)?;
+    let start = Instant::now();
+
+    let mut scanner = Scanner::new_from_common(args.common.clone())?;
+    if let Some(session) = session {
+        scanner.set_session(Arc::new(Mutex::new(session)));
+    }
+
+    let results = if args.recursive {
+        let (start_dir, suffix) = split_recursion_template(&args.url)?;
+        let recursion = Recursion {
+            common: &args.common,
+            max_depth: args.depth,
+            per_dir_budget: None,
+            stop_on_first: args.stop_on_first,
+        };
+        recursion
+            .run(&scanner, vec![start_dir], |dir| targets_for(&format!("{}{}", dir, suffix)))
+            .await?
+    } else {
+        scanner.scan_urls(targets_for(&args.url)).await?
+    };
+    report::write_report(&args.common, &args.url, &results, start.elapsed())?;
+    if args.common.summary_line {
+        println!("{}", report::summary_line(&args.common, &args.url, &results, start.elapsed()));
+    }
+
+    Ok(ScanSummary { found: scanner.count_found(&results) })
+}
+
+/// Splits a `/FUZZ`-style URL into its directory and the last segment re-fuzzed in each subdirectory
+fn split_recursion_template(url: &str) -> Result<(String, String)> {
+    let path_end = url.find(['?', '#']).unwrap_or(url.len());
+    let host_start = url.find("://").map_or(0, |i| i + 3);
+    let split = url[host_start..path_end].rfind('/').map(|i| host_start + i + 1);
+
+    match split {
+        Some(split) if !url[..split].contains("FUZZ") && url[split..path_end].contains("FUZZ") => {
+            Ok((url[..split].to_string(), url[split..].to_string()))
+        }
+        _ => Err(anyhow!(
+            "Recursive fuzzing needs FUZZ in the last path segment (e.g. http://example.com/FUZZ)"
+        )),
     }
+}
 
-    let mut scanner = Scanner::new_from_common(args.common)?;
-    scanner.scan_urls(urls).await?;
+/// Loads the wordlist bound to each keyword: `-w` binds FUZZ, `--fuzz-wordlist KEY=FILE` binds the rest
+fn load_keywords(args: &FuzzArgs, extensions: &[String]) -> Result<Vec<KeywordList>> {
+    let mut bindings: Vec<(String, String)> = Vec::new();
 
-    Ok(())
+    if !args.common.wordlist.is_empty() {
+        bindings.push(("FUZZ".to_string(), args.common.wordlist_label()));
+    }
+
+    for binding in &args.fuzz_wordlist {
+        let (keyword, path) = binding
+            .split_once('=')
+            .filter(|(keyword, path)| !keyword.trim().is_empty() && !path.trim().is_empty())
+            .ok_or_else(|| anyhow!("Invalid --fuzz-wordlist '{}', expected KEY=FILE", binding))?;
+        let keyword = keyword.trim().to_string();
+
+        if bindings.iter().any(|(k, _)| *k == keyword) {
+            return Err(anyhow!("Keyword {} is bound to more than one wordlist", keyword));
+        }
+        bindings.push((keyword, path.trim().to_string()));
+    }
+
+    if bindings.is_empty() {
+        return Err(anyhow!("Wordlist is required"));
+    }
+
+    let mut keywords = Vec::new();
+    for (keyword, path) in bindings {
+        // FUZZ combines every -w file, or takes its own file when there is no -w; other
+        // keywords have one file each
+        let wordlist = if keyword == "FUZZ" && !args.common.wordlist.is_empty() {
+            Wordlist::from_common(&args.common)?
+        } else if keyword == "FUZZ" {
+            Wordlist::from_file(&path)?.with_options(&args.common).with_scan_range(&args.common)?
+        } else {
+            Wordlist::from_file(&path)?.with_options(&args.common)
+        };
+        // Extensions keep applying to the primary FUZZ keyword only
+        let words = if keyword == "FUZZ" && args.no_bare_word {
+            wordlist.expand_extensions_only(extensions)
+        } else if keyword == "FUZZ" {
+            wordlist.expand_with_sources(extensions)
+        } else {
+            wordlist.expand_with_sources(&[])
+        };
+        keywords.push(KeywordList { keyword, path, words });
+    }
+
+    // Longest keywords first, the order `substitute` tries them in, so FUZZ2 is never read
+    // as FUZZ followed by a 2
+    keywords.sort_by_key(|k| std::cmp::Reverse(k.keyword.len()));
+
+    Ok(keywords)
+}
+
+/// The word index for every keyword in each request, produced one request at a time
+///
+/// `clusterbomb` walks the Cartesian product of all lists, the last keyword changing
+/// fastest; `pitchfork` pairs the lists line by line and stops at the shortest one.
+struct Combinations {
+    lengths: Vec<usize>,
+    pitchfork: bool,
+    next: Option<Vec<usize>>,
+}
+
+impl Combinations {
+    fn new(lengths: &[usize], mode: &str) -> Self {
+        let pitchfork = mode == "pitchfork";
+        let empty = lengths.contains(&0) || (pitchfork && lengths.is_empty());
+        Combinations {
+            lengths: lengths.to_vec(),
+            pitchfork,
+            next: (!empty).then(|| vec![0; lengths.len()]),
+        }
+    }
+
+    /// How many requests the combinations make
+    fn total(&self) -> usize {
+        if self.pitchfork {
+            self.lengths.iter().copied().min().unwrap_or(0)
+        } else {
+            self.lengths.iter().fold(1, |total, &length| total.saturating_mul(length))
+        }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let current = self.next.take()?;
+
+        let mut following = current.clone();
+        if self.pitchfork {
+            let index = following[0] + 1;
+            if index < self.total() {
+                self.next = Some(vec![index; following.len()]);
+            }
+        } else {
+            // Count up like an odometer; running past the first keyword ends the walk
+            for position in (0..following.len()).rev() {
+                following[position] += 1;
+                if following[position] < self.lengths[position] {
+                    self.next = Some(following);
+                    break;
+                }
+                following[position] = 0;
+            }
+        }
+        Some(current)
+    }
 }
@@ -2,3 +2,13 @@ pub mod dir;
 pub mod dns;
 pub mod vhost;
 pub mod fuzz;
+pub mod recursion;
+pub mod sessions;
+pub mod diff;
+
+/// What a finished scan reports back to `main`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanSummary {
+    /// Results matching the status filters
+    pub found: usize,
+}
@@ -0,0 +1,126 @@
+use crate::cli::CommonArgs;
+use crate::core::http_client::ScanResult;
+use crate::core::scanner::ScanPass;
+use crate::core::{ScanTarget, Scanner};
+use anyhow::Result;
+use futures::stream::{FuturesUnordered, StreamExt};
+use indicatif::ProgressBar;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::time::timeout;
+use url::Url;
+
+/// Settings shared by every directory of a recursive scan
+pub struct Recursion<'a> {
+    pub common: &'a CommonArgs,
+    pub max_depth: usize,
+    pub per_dir_budget: Option<Duration>,
+    /// Drop the rest of a directory's requests once it has a hit
+    pub stop_on_first: bool,
+}
+
+impl Recursion<'_> {
+    /// Scans directories from a shared queue, descending into the ones they turn up, down to `max_depth`
+    ///
+    /// Directories run side by side on the one `scanner`, which keeps requests at `--threads`
+    /// overall and reuses its connection pool across the whole tree. `targets_for` builds the
+    /// requests for one directory. Every directory is scanned once, and one where every request
+    /// looks like a directory is not descended into, so a server that answers every path cannot
+    /// recurse forever.
+    pub async fn run<F>(&self, scanner: &Scanner, start_dirs: Vec<String>, targets_for: F) -> Result<Vec<ScanResult>>
+    where
+        F: Fn(&Url) -> Vec<ScanTarget>,
+    {
+        let progress = scanner.progress_bar(0);
+        let progress = progress.as_ref();
+        let targets_for = &targets_for;
+
+        let scan_dir = move |dir: String, depth: usize| async move {
+            self.announce(progress, &format!("\n[*] Scanning: {} (depth: {})", dir, depth));
+
+            let base = Url::parse(&dir)?;
+            let targets = targets_for(&base);
+            let target_count = targets.len();
+
+            // Each directory may answer unknown paths differently, so calibrate per directory
+            let baseline = scanner.baseline_for(base.as_str()).await?;
+            let pass = if self.stop_on_first { ScanPass::stop_on_first() } else { ScanPass::default() };
+            let scan = scanner.scan_pass(targets, &baseline, progress, &pass);
+            let completed = match self.per_dir_budget {
+                Some(budget) => timeout(budget, scan).await.is_ok(),
+                None => {
+                    scan.await;
+                    true
+                }
+            };
+
+            Ok::<_, anyhow::Error>((dir, depth, target_count, completed, pass))
+        };
+
+        let mut queued: HashSet<String> = HashSet::new();
+        let mut pending = FuturesUnordered::new();
+        for dir in start_dirs {
+            if queued.insert(dir.clone()) {
+                pending.push(scan_dir(dir, 0));
+            }
+        }
+
+        let mut truncated_dirs: Vec<String> = Vec::new();
+        let mut all_results = Vec::new();
+        let mut responses = 0;
+
+        while let Some(outcome) = pending.next().await {
+            let (dir, depth, target_count, completed, pass) = outcome?;
+            let (results, discovered, received) = pass.into_parts();
+            all_results.extend(results);
+            responses += received;
+
+            if !completed {
+                self.announce(progress, &format!("[!] Time budget exceeded for {}, moving on", dir));
+                truncated_dirs.push(dir.clone());
+            }
+
+            if target_count > 1 && discovered.len() >= target_count {
+                self.announce(
+                    progress,
+                    &format!("[!] Every path under {} looks like a directory, not recursing into it", dir),
+                );
+                continue;
+            }
+
+            if depth < self.max_depth {
+                for child in discovered {
+                    if queued.insert(child.clone()) {
+                        pending.push(scan_dir(child, depth + 1));
+                    }
+                }
+            }
+        }
+
+        if let Some(pb) = progress {
+            pb.finish_and_clear();
+        }
+        scanner.wait_for_hooks().await;
+        scanner.finish(responses, &all_results)?;
+
+        if !truncated_dirs.is_empty() && !self.common.quiet {
+            println!("\n[!] {} directories were truncated by --per-dir-timeout:", truncated_dirs.len());
+            for dir in &truncated_dirs {
+                println!("    {}", dir);
+            }
+        }
+
+        Ok(all_results)
+    }
+
+    /// Prints a status line above the progress bar unless `--quiet` is set
+    fn announce(&self, progress: Option<&ProgressBar>, message: &str) {
+        if self.common.quiet {
+            return;
+        }
+        match progress {
+            Some(pb) => pb.suspend(|| println!("{}", message)),
+            None => println!("{}", message),
+        }
+    }
+}
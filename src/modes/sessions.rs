@@ -0,0 +1,42 @@
+use crate::cli::{SessionsAction, SessionsArgs};
+use crate::utils::session::Session;
+use anyhow::Result;
+use colored::*;
+use std::path::Path;
+
+pub fn run(args: SessionsArgs) -> Result<()> {
+    let dir = Session::dir_from(args.session_dir.as_deref())?;
+    match args.action {
+        SessionsAction::List => list(&dir),
+        SessionsAction::Delete { name } => {
+            Session::delete(&dir, &name)?;
+            println!("Deleted session {}", name.bright_white());
+            Ok(())
+        }
+    }
+}
+
+/// Prints each saved session, noting files that no longer parse instead of failing on them
+fn list(dir: &Path) -> Result<()> {
+    let names = Session::list_sessions(dir)?;
+    if names.is_empty() {
+        println!("No saved sessions");
+        return Ok(());
+    }
+
+    for name in names {
+        match Session::load(dir, &name) {
+            Ok(session) => println!(
+                "{}  {}  [{:.1}% of {} words] [Found: {}] [Updated: {}]",
+                name.bright_white().bold(),
+                session.target,
+                session.get_progress(),
+                session.total_words,
+                session.found_results.len(),
+                session.last_updated.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            Err(e) => println!("{}  {}", name.bright_white().bold(), format!("unreadable: {:#}", e).red()),
+        }
+    }
+    Ok(())
+}
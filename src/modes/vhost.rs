@@ -1,33 +1,86 @@
 use crate::cli::VhostArgs;
-use crate::core::{HttpClient, Wordlist};
-use crate::output::{tui, OutputHandler};
-use crate::output::tui::{TuiMessage, TuiResult};
+use crate::core::http_client::{self, auth_schemes};
+use crate::core::error_budget::ErrorBudget;
+use crate::modes::ScanSummary;
+use crate::core::{HttpClient, StatusFilter, Wordlist};
+use crate::output::{progress, tui, OutputHandler};
+use crate::output::tui::{PauseGate, TuiOutput, TuiResult, TuiSender};
 use anyhow::Result;
 use colored::*;
 use futures::stream::{self, StreamExt};
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressBar;
+use reqwest::Response;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
-use tokio::sync::mpsc;
 
-pub async fn run(args: VhostArgs) -> Result<()> {
+/// Status and size of the response served for an unknown Host header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VhostBaseline {
+    pub status_code: u16,
+    pub content_length: u64,
+}
+
+impl VhostBaseline {
+    /// Requests the target once with a random Host header to capture the default response
+    pub async fn fetch(
+        client: &HttpClient,
+        url: &str,
+        method: &str,
+        headers: &[(String, String)],
+        cookies: Option<&str>,
+        base_domain: &str,
+    ) -> Option<Self> {
+        let random_host = format!("rustbuster-{}.{}", uuid::Uuid::new_v4(), base_domain);
+        let mut baseline_headers = headers.to_vec();
+        baseline_headers.push(("Host".to_string(), random_host));
+
+        let response = client.request(url, method, &baseline_headers, cookies, None).await.ok()?;
+        let status_code = response.status().as_u16();
+        Some(VhostBaseline {
+            status_code,
+            content_length: response_size(client, method, response).await,
+        })
+    }
+
+    /// Returns true when a response is indistinguishable from the baseline
+    pub fn matches(&self, status_code: u16, content_length: u64) -> bool {
+        self.status_code == status_code && self.content_length == content_length
+    }
+}
+
+/// Size of a response body; decoded or chunked bodies carry no length header, so those are
+/// read to measure them, as the directory scanner does
+pub async fn response_size(client: &HttpClient, method: &str, response: Response) -> u64 {
+    let size_unknown = method != "HEAD"
+        && response.content_length().is_none()
+        && !response.headers().contains_key("content-length");
+    if size_unknown {
+        client.read_body(response).await.bytes.len() as u64
+    } else {
+        response.content_length().unwrap_or(0)
+    }
+}
+
+pub async fn run(args: VhostArgs) -> Result<ScanSummary> {
+    http_client::preflight_proxy(&args.common, &args.url).await?;
+    http_client::preflight_target(&args.common, &args.url).await?;
+
     if !args.common.no_tui {
         return run_with_tui(args).await;
     }
 
     let output = OutputHandler::new(
         args.common.output.clone(),
-        args.common.quiet,
+        args.common.quiet || args.common.url_only,
         args.common.output_format.clone(),
         args.common.verbose,
-    );
+    )
+    .with_append(args.common.output_append);
     output.print_banner_common(&args.common);
 
     // Load wordlist
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+    let wordlist = Wordlist::from_common(&args.common)?;
     let base_domain = args.url.trim_start_matches("http://").trim_start_matches("https://");
 
     // Generate vhosts to test
@@ -42,14 +95,9 @@ pub async fn run(args: VhostArgs) -> Result<()> {
     let found_clone = Arc::clone(&found);
 
     // Setup progress bar
-    let progress = if !args.common.no_progress && !args.common.quiet {
+    let progress = if !args.common.no_progress && !args.common.quiet && !args.common.url_only {
         let pb = ProgressBar::new(total as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")
-                .unwrap()
-                .progress_chars("=>-"),
-        );
+        pb.set_style(progress::progress_style());
         Some(pb)
     } else {
         None
@@ -57,31 +105,29 @@ pub async fn run(args: VhostArgs) -> Result<()> {
 
     // Create HTTP client
     let client = HttpClient::new_from_common(&args.common)?;
-    let status_codes = args.common.get_status_codes();
-    let negative_codes = args.common.get_negative_status_codes();
-    
-    let default_status_codes = if status_codes.is_empty() && negative_codes.is_empty() {
-        (200..300).collect::<Vec<u16>>()
-    } else {
-        status_codes.clone()
-    };
+    let status_filter = StatusFilter::from_common(&args.common);
 
     // Parse headers
-    let headers: Vec<(String, String)> = args
-        .common
-        .headers
-        .iter()
-        .filter_map(|h| {
-            let parts: Vec<&str> = h.splitn(2, ':').collect();
-            if parts.len() == 2 {
-                Some((parts[0].trim().to_string(), parts[1].trim().to_string()))
-            } else {
-                None
-            }
-        })
-        .collect();
+    let headers = args.common.get_headers();
 
     let cookies = args.common.cookies.as_deref();
+    let error_budget = ErrorBudget::from_common(&args.common);
+
+    // Capture the default response so vhosts that merely echo it are suppressed
+    let baseline = if args.no_baseline_filter {
+        None
+    } else {
+        VhostBaseline::fetch(&client, &args.url, &args.common.method, &headers, cookies, base_domain).await
+    };
+
+    if let Some(baseline) = &baseline {
+        if args.common.verbose {
+            eprintln!(
+                "[*] Baseline response: Status {} [Size: {}]",
+                baseline.status_code, baseline.content_length
+            );
+        }
+    }
 
     // Process vhosts concurrently
     stream::iter(vhosts)
@@ -93,11 +139,15 @@ pub async fn run(args: VhostArgs) -> Result<()> {
             let found = Arc::clone(&found_clone);
             let progress = &progress;
             let expanded = args.common.expanded;
-            let status_codes = default_status_codes.clone();
-            let negative_codes = negative_codes.clone();
-            let quiet = args.common.quiet;
+            let status_filter = &status_filter;
+            let quiet = args.common.quiet || args.common.url_only;
+            let url_only = args.common.url_only;
+            let error_budget = &error_budget;
 
             async move {
+                if error_budget.is_exhausted() {
+                    return;
+                }
                 if let Some(pb) = progress {
                     pb.inc(1);
                 }
@@ -106,26 +156,30 @@ pub async fn run(args: VhostArgs) -> Result<()> {
                 vhost_headers.push(("Host".to_string(), vhost.clone()));
 
                 let start = Instant::now();
-                match client.request(url, method, &vhost_headers, cookies).await {
+                match client.request(url, method, &vhost_headers, cookies, None).await {
                     Ok(response) => {
+                        error_budget.record_success();
                         let duration_ms = start.elapsed().as_millis() as u64;
                         
                         let status = response.status().as_u16();
-                        let content_length = response.content_length().unwrap_or(0);
-
-                        let should_display = if !negative_codes.is_empty() {
-                            !negative_codes.contains(&status)
-                        } else if !status_codes.is_empty() {
-                            status_codes.contains(&status)
-                        } else {
-                            // If no filters specified, show successful responses
-                            (200..300).contains(&status)
-                        };
-
-                        if should_display || expanded {
+                        let auth_scheme = auth_schemes(response.headers());
+                        let content_length = response_size(client, method, response).await;
+
+                        let should_display = status_filter.matches(status);
+
+                        let is_baseline = baseline
+                            .is_some_and(|b| b.matches(status, content_length));
+
+                        // -e also shows vhosts that are no findings, but only findings count
+                        let is_finding = should_display && !is_baseline;
+                        if is_finding {
                             found.fetch_add(1, Ordering::SeqCst);
-                            
-                            if !quiet {
+                        }
+
+                        if is_finding || expanded {
+                            if url_only {
+                                println!("{}", vhost);
+                            } else if !quiet {
                                 let status_color = match status {
                                     200..=299 => "green",
                                     300..=399 => "yellow",
@@ -134,17 +188,22 @@ pub async fn run(args: VhostArgs) -> Result<()> {
                                     _ => "white",
                                 };
 
+                                let auth = auth_scheme
+                                    .map(|scheme| format!(" [Auth: {}]", scheme.bright_red()))
+                                    .unwrap_or_default();
                                 println!(
-                                    "{} (Status: {}) [Size: {}] [Duration: {} ms]",
+                                    "{} (Status: {}) [Size: {}] [Duration: {} ms]{}",
                                     vhost.bright_white(),
                                     status.to_string().color(status_color).bold(),
                                     content_length,
-                                    duration_ms
+                                    duration_ms,
+                                    auth
                                 );
                             }
                         }
                     }
                     Err(_) => {
+                        error_budget.record_error();
                         if expanded {
                             eprintln!("Error testing vhost: {}", vhost);
                         }
@@ -163,13 +222,12 @@ pub async fn run(args: VhostArgs) -> Result<()> {
     let found_count = found.load(Ordering::SeqCst);
     output.print_summary(total, found_count);
 
-    Ok(())
+    error_budget.check()?;
+    Ok(ScanSummary { found: found_count })
 }
 
-async fn run_with_tui(args: VhostArgs) -> Result<()> {
-    let wordlist_path = args.common.wordlist.as_ref()
-        .ok_or_else(|| anyhow::anyhow!("Wordlist is required"))?;
-    let wordlist = Wordlist::from_file(wordlist_path)?;
+async fn run_with_tui(args: VhostArgs) -> Result<ScanSummary> {
+    let wordlist = Wordlist::from_common(&args.common)?;
     let base_domain = args.url.trim_start_matches("http://").trim_start_matches("https://");
 
     let vhosts: Vec<String> = wordlist
@@ -184,54 +242,44 @@ async fn run_with_tui(args: VhostArgs) -> Result<()> {
     let method = args.common.method.clone();
     let threads = args.common.threads;
     
-    let headers: Vec<(String, String)> = args
-        .common
-        .headers
-        .iter()
-        .filter_map(|h| {
-            let parts: Vec<&str> = h.splitn(2, ':').collect();
-            if parts.len() == 2 {
-                Some((parts[0].trim().to_string(), parts[1].trim().to_string()))
-            } else {
-                None
-            }
-        })
-        .collect();
+    let headers = args.common.get_headers();
 
-    let status_codes = args.common.get_status_codes();
-    let negative_codes = args.common.get_negative_status_codes();
+    let status_filter = StatusFilter::from_common(&args.common);
+    let error_budget = ErrorBudget::from_common(&args.common);
+
+    let baseline = if args.no_baseline_filter {
+        None
+    } else {
+        VhostBaseline::fetch(&client, &url, &method, &headers, None, base_domain).await
+    };
     
     tui::run_tui_mode(
         "vhost".to_string(),
         url.clone(),
-        wordlist_path.clone(),
+        args.common.wordlist_label(),
         threads,
         total,
-        args.common.output.clone(),
-        args.common.output_format.clone(),
-        move |tx| async move {
-            scan_vhost_with_tui(vhosts, client, url, method, headers, status_codes, negative_codes, threads, tx).await
+        TuiOutput::from_common(&args.common),
+        move |tx, pause| async move {
+            scan_vhost_with_tui(vhosts, client, url, method, headers, status_filter, baseline, error_budget, threads, tx, pause).await
         },
     ).await
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn scan_vhost_with_tui(
     vhosts: Vec<String>,
     client: HttpClient,
     url: String,
     method: String,
     headers: Vec<(String, String)>,
-    status_codes: Vec<u16>,
-    negative_codes: Vec<u16>,
+    status_filter: StatusFilter,
+    baseline: Option<VhostBaseline>,
+    error_budget: ErrorBudget,
     threads: usize,
-    tx: mpsc::Sender<TuiMessage>,
+    tx: TuiSender,
+    pause: PauseGate,
 ) -> Result<()> {
-    let default_status_codes = if status_codes.is_empty() && negative_codes.is_empty() {
-        (200..300).collect::<Vec<u16>>()
-    } else {
-        status_codes.clone()
-    };
-
     stream::iter(vhosts)
         .map(|vhost| {
             let client = &client;
@@ -239,43 +287,45 @@ async fn scan_vhost_with_tui(
             let method = &method;
             let mut vhost_headers = headers.clone();
             let tx = tx.clone();
-            let status_codes = default_status_codes.clone();
-            let negative_codes = negative_codes.clone();
+            let status_filter = &status_filter;
+            let pause = &pause;
+            let error_budget = &error_budget;
 
             async move {
-                let _ = tx.send(TuiMessage::Scanned).await;
+                pause.wait().await;
+                if error_budget.is_exhausted() {
+                    return;
+                }
+                tx.scanned();
 
                 vhost_headers.push(("Host".to_string(), vhost.clone()));
 
                 let start = Instant::now();
-                match client.request(url, method, &vhost_headers, None).await {
+                match client.request(url, method, &vhost_headers, None, None).await {
                     Ok(response) => {
+                        error_budget.record_success();
                         let duration_ms = start.elapsed().as_millis() as u64;
                         
                         let status = response.status().as_u16();
-                        let content_length = response.content_length().unwrap_or(0);
-
-                        let should_display = if !negative_codes.is_empty() {
-                            !negative_codes.contains(&status)
-                        } else if !status_codes.is_empty() {
-                            status_codes.contains(&status)
-                        } else {
-                            (200..300).contains(&status)
-                        };
-
-                        if should_display {
-                            let content_type = response
-                                .headers()
-                                .get("content-type")
-                                .and_then(|v| v.to_str().ok())
-                                .map(|s| s.split(';').next().unwrap_or(s).trim().to_string());
-                            
-                            let server = response
-                                .headers()
-                                .get("server")
-                                .and_then(|v| v.to_str().ok())
-                                .map(|s| s.to_string());
-
+                        let content_type = response
+                            .headers()
+                            .get("content-type")
+                            .and_then(|v| v.to_str().ok())
+                            .map(|s| s.split(';').next().unwrap_or(s).trim().to_string());
+                        let server = response
+                            .headers()
+                            .get("server")
+                            .and_then(|v| v.to_str().ok())
+                            .map(|s| s.to_string());
+                        let auth_scheme = auth_schemes(response.headers());
+                        let content_length = response_size(client, method, response).await;
+
+                        let should_display = status_filter.matches(status);
+
+                        let is_baseline = baseline
+                            .is_some_and(|b| b.matches(status, content_length));
+
+                        if should_display && !is_baseline {
                             let result = TuiResult {
                                 url: vhost,
                                 status_code: status,
@@ -284,12 +334,23 @@ async fn scan_vhost_with_tui(
                                 content_type,
                                 server,
                                 duration_ms,
+                                source: None,
+                                golden_diff: None,
+                                ttfb_ms: None,
+                                allowed_methods: None,
+                                auth_scheme,
+                                final_url: None,
+                                redirect_count: 0,
+                                headers: Default::default(),
+                                technologies: Vec::new(),
+                                body: None,
                             };
-                            let _ = tx.send(TuiMessage::Result(result)).await;
+                            tx.result(result).await;
                         }
                     }
-                    Err(_) => {
-                        let _ = tx.send(TuiMessage::Error).await;
+                    Err(err) => {
+                        error_budget.record_error();
+                        tx.error(vhost, &err);
                     }
                 }
             }
@@ -298,6 +359,6 @@ async fn scan_vhost_with_tui(
         .collect::<Vec<_>>()
         .await;
 
-    let _ = tx.send(TuiMessage::Done).await;
-    Ok(())
+    tx.done().await;
+    error_budget.check()
 }
@@ -1,20 +1,71 @@
 use crate::cli::CommonArgs;
 use crate::core::http_client::ScanResult;
+use crate::output::serialize::{self, ResultRecord};
+use crate::utils::report::TimingStats;
 use colored::*;
-use serde_json::json;
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
+use std::io::{IsTerminal, Write};
 use std::sync::{Arc, Mutex};
 use terminal_size::{Width, terminal_size};
 
+/// Whether output gets ANSI colors: not with `--no-color`, a non-empty `NO_COLOR`, or when
+/// stdout is not a terminal
+pub fn color_enabled(no_color_flag: bool, no_color_env: Option<OsString>, stdout_is_terminal: bool) -> bool {
+    let no_color_env = no_color_env.is_some_and(|value| !value.is_empty());
+    !no_color_flag && !no_color_env && stdout_is_terminal
+}
+
+/// Applies `color_enabled` to everything printed through `colored` from here on
+pub fn init_color(no_color_flag: bool) {
+    let enabled = color_enabled(no_color_flag, std::env::var_os("NO_COLOR"), std::io::stdout().is_terminal());
+    colored::control::set_override(enabled);
+}
+
 #[derive(Clone)]
 pub struct OutputHandler {
     output_file: Option<String>,
     output_format: String,
     quiet: bool,
     verbose: bool, // Added verbose field
-    discovered_dirs: Arc<Mutex<Vec<String>>>,
     results_buffer: Arc<Mutex<Vec<ScanResult>>>,
+    notifier: Option<FirstHitNotifier>,
+    /// Add to the json or csv file instead of replacing it (`--output-append`)
+    append: bool,
+    /// Print bare URLs instead of result lines (`--url-only`)
+    url_only: bool,
+    /// Keep response bodies in json and jsonl output (`--include-body`)
+    include_body: bool,
+}
+
+/// Tracks the `--notify-on` statuses, so each one alerts only on its first result
+#[derive(Clone)]
+pub struct FirstHitNotifier {
+    codes: Arc<Vec<u16>>,
+    seen: Arc<Mutex<HashSet<u16>>>,
+}
+
+impl FirstHitNotifier {
+    pub fn new(codes: Vec<u16>) -> Self {
+        FirstHitNotifier {
+            codes: Arc::new(codes),
+            seen: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// True the first time a watched status is seen, false for repeats and other statuses
+    pub fn first_hit(&self, status_code: u16) -> bool {
+        self.codes.contains(&status_code) && self.seen.lock().unwrap().insert(status_code)
+    }
+
+    /// Rings the terminal bell; skipped when stderr is not a terminal that could ring it
+    fn alert(&self, result: &ScanResult) {
+        let mut stderr = std::io::stderr();
+        if stderr.is_terminal() {
+            let _ = write!(stderr, "\x07");
+            let _ = writeln!(stderr, "[!] First {} found: {}", result.status_code, result.url);
+        }
+    }
 }
 
 impl OutputHandler {
@@ -24,8 +75,46 @@ impl OutputHandler {
             output_format,
             quiet,
             verbose, // Initialize verbose field
-            discovered_dirs: Arc::new(Mutex::new(Vec::new())),
             results_buffer: Arc::new(Mutex::new(Vec::new())),
+            notifier: None,
+            append: false,
+            url_only: false,
+            include_body: false,
+        }
+    }
+
+    /// Alerts on the first result with each of these statuses (`--notify-on`)
+    pub fn with_notify_on(mut self, codes: &[u16]) -> Self {
+        if !codes.is_empty() {
+            self.notifier = Some(FirstHitNotifier::new(codes.to_vec()));
+        }
+        self
+    }
+
+    /// Keeps what the json or csv output file already holds; line formats always append
+    pub fn with_append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+
+    /// Prints each result as its bare URL, even when quiet
+    pub fn with_url_only(mut self, url_only: bool) -> Self {
+        self.url_only = url_only;
+        self
+    }
+
+    /// Writes each result's body into json and jsonl files
+    pub fn with_include_body(mut self, include_body: bool) -> Self {
+        self.include_body = include_body;
+        self
+    }
+
+    /// The record written to the output file
+    fn record(&self, result: &ScanResult) -> ResultRecord {
+        if self.include_body {
+            ResultRecord::with_body(result)
+        } else {
+            ResultRecord::from(result)
         }
     }
 
@@ -55,26 +144,29 @@ impl OutputHandler {
         println!("{}", "Fast Web Directory Brute-Forcing Tool".bright_cyan());
         println!("{}", separator.bright_cyan());
         println!();
-        println!("{} {}", "Wordlist:".bright_yellow(), 
-            args.wordlist.as_deref().unwrap_or("None"));
+        let wordlists = if args.wordlist.is_empty() { "None".to_string() } else { args.wordlist_label() };
+        println!("{} {}", "Wordlist:".bright_yellow(), wordlists);
         println!("{} {}", "Threads:".bright_yellow(), args.threads);
-        println!("{} {}", "Timeout:".bright_yellow(), format!("{}s", args.timeout));
+        println!("{} {}s", "Timeout:".bright_yellow(), args.timeout);
         
         if self.verbose {
             println!("{} Enabled", "Verbose Mode:".bright_yellow());
         }
         
-        if args.delay.is_some() {
-            println!("{} {}ms", "Delay:".bright_yellow(), args.delay.unwrap());
+        if let Some(delay) = args.delay {
+            println!("{} {}ms", "Delay:".bright_yellow(), delay);
+        }
+        if let Some((min, max)) = args.random_delay {
+            println!("{} {}-{}ms", "Random Delay:".bright_yellow(), min, max);
         }
         if args.user_agents_file.is_some() {
             println!("{} Enabled", "User-Agent Rotation:".bright_yellow());
         }
-        if args.filter_regex.is_some() {
-            println!("{} {}", "Filter Regex:".bright_yellow(), args.filter_regex.as_ref().unwrap());
+        if let Some(filter_regex) = &args.filter_regex {
+            println!("{} {}", "Filter Regex:".bright_yellow(), filter_regex);
         }
-        if args.match_regex.is_some() {
-            println!("{} {}", "Match Regex:".bright_yellow(), args.match_regex.as_ref().unwrap());
+        if let Some(match_regex) = &args.match_regex {
+            println!("{} {}", "Match Regex:".bright_yellow(), match_regex);
         }
         
         println!();
@@ -84,33 +176,48 @@ impl OutputHandler {
 
     /// Prints a scan result with enhanced information
     pub fn print_result(&self, result: &ScanResult, expanded: bool) {
-        if self.quiet && !expanded {
-            return;
+        // Files are written even in quiet mode, which only silences the console
+        if self.output_format == "json" || self.output_format == "csv" {
+            if let Ok(mut buffer) = self.results_buffer.lock() {
+                buffer.push(ScanResult {
+                    body: result.body.clone().filter(|_| self.include_body),
+                    ..result.clone()
+                });
+            }
         }
 
-        if result.status_code == 301 || result.status_code == 302 || result.status_code == 200 {
-            if result.url.ends_with('/') {
-                if let Ok(mut dirs) = self.discovered_dirs.lock() {
-                    dirs.push(result.url.clone());
+        if let Some(file_path) = &self.output_file {
+            match self.output_format.as_str() {
+                "plain" => {
+                    let _ = serialize::append_plain(&result.into(), file_path);
+                }
+                "jsonl" => {
+                    let _ = serialize::append_jsonl(&self.record(result), file_path);
                 }
+                _ => {}
             }
         }
 
-        if self.output_format != "plain" {
-            if let Ok(mut buffer) = self.results_buffer.lock() {
-                buffer.push(ScanResult {
-                    url: result.url.clone(),
-                    status_code: result.status_code,
-                    content_length: result.content_length,
-                    redirect_location: result.redirect_location.clone(),
-                    body: None,
-                    content_type: result.content_type.clone(),
-                    server: result.server.clone(),
-                    duration_ms: result.duration_ms,
-                });
+        if let Some(notifier) = &self.notifier {
+            if notifier.first_hit(result.status_code) {
+                notifier.alert(result);
             }
         }
 
+        if self.url_only {
+            println!("{}", result.url);
+            return;
+        }
+
+        if self.quiet && !expanded {
+            return;
+        }
+
+        println!("{}", self.result_line(result));
+    }
+
+    /// The decorated console line for a result
+    pub fn result_line(&self, result: &ScanResult) -> String {
         let status_color = match result.status_code {
             200..=299 => "green",
             300..=399 => "yellow",
@@ -139,104 +246,52 @@ impl OutputHandler {
             output.push_str(&format!(" -> {}", location.bright_blue()));
         }
 
-        println!("{}", output);
+        if let Some(diff) = &result.golden_diff {
+            output.push_str(&format!(" [Changed: {}]", diff.bright_yellow()));
+        }
 
-        if self.output_format == "plain" {
-            if let Some(file_path) = &self.output_file {
-                let _ = self.write_plain_to_file(file_path, result);
-            }
+        if let Some(methods) = &result.allowed_methods {
+            output.push_str(&format!(" [Allow: {}]", methods.bright_green()));
         }
-    }
 
-    fn write_plain_to_file(&self, file_path: &str, result: &ScanResult) -> std::io::Result<()> {
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(file_path)?;
-
-        let line = if let Some(location) = &result.redirect_location {
-            format!(
-                "{} [{}] [{}] -> {}\n",
-                result.url, result.status_code, result.content_length, location
-            )
-        } else {
-            format!(
-                "{} [{}] [{}]\n",
-                result.url, result.status_code, result.content_length
-            )
-        };
+        if let Some(scheme) = &result.auth_scheme {
+            output.push_str(&format!(" [Auth: {}]", scheme.bright_red()));
+        }
 
-        file.write_all(line.as_bytes())?;
-        Ok(())
-    }
+        if !result.technologies.is_empty() {
+            output.push_str(&format!(" [Tech: {}]", result.technologies.join(", ").bright_green()));
+        }
 
-    #[allow(dead_code)]
-    pub fn finalize(&self) -> std::io::Result<()> {
-        if let Some(file_path) = &self.output_file {
-            if self.output_format == "json" {
-                self.write_json_to_file(file_path)?;
-            } else if self.output_format == "csv" {
-                self.write_csv_to_file(file_path)?;
-            }
+        for (name, value) in sorted_headers(result) {
+            output.push_str(&format!(" [{}: {}]", name, value.bright_cyan()));
         }
-        Ok(())
-    }
 
-    #[allow(dead_code)]
-    fn write_json_to_file(&self, file_path: &str) -> std::io::Result<()> {
-        let results = self.results_buffer.lock().unwrap();
-        let json_results: Vec<_> = results
-            .iter()
-            .map(|r| {
-                json!({
-                    "url": r.url,
-                    "status_code": r.status_code,
-                    "content_length": r.content_length,
-                    "redirect_location": r.redirect_location,
-                    "content_type": r.content_type,
-                    "server": r.server,
-                    "duration_ms": r.duration_ms,
-                })
-            })
-            .collect();
+        if let Some(final_url) = result.final_url.as_ref().filter(|_| self.verbose) {
+            output.push_str(&format!(" [Redirects: {} => {}]", result.redirect_count, final_url.bright_blue()));
+        }
 
-        let json_output = serde_json::to_string_pretty(&json_results)?;
-        std::fs::write(file_path, json_output)?;
-        Ok(())
+        output
     }
 
-    #[allow(dead_code)]
-    fn write_csv_to_file(&self, file_path: &str) -> std::io::Result<()> {
-        let results = self.results_buffer.lock().unwrap();
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(file_path)?;
-
-        // Write CSV header
-        writeln!(file, "URL,Status Code,Status Text,Content Length,Redirect Location,Content Type,Server,Duration (ms)")?;
-
-        // Write results
-        for result in results.iter() {
-            writeln!(
-                file,
-                "{},{},{},{},{},{},{},{}",
-                result.url,
-                result.status_code,
-                result.status_text(),
-                result.content_length,
-                result.redirect_location.as_deref().unwrap_or(""),
-                result.content_type.as_deref().unwrap_or(""),
-                result.server.as_deref().unwrap_or(""),
-                result.duration_ms
-            )?;
+    pub fn finalize(&self) -> std::io::Result<()> {
+        if let Some(file_path) = &self.output_file {
+            let results = self.results_buffer.lock().unwrap();
+            let records: Vec<ResultRecord> = results.iter().map(|result| self.record(result)).collect();
+            match self.output_format.as_str() {
+                "json" => serialize::write_json(&records, file_path, self.append)?,
+                "csv" => serialize::write_csv(&records, file_path, self.append)?,
+                _ => {}
+            }
         }
-
         Ok(())
     }
 
     pub fn print_summary(&self, total: usize, found: usize) {
+        self.print_summary_with_counts(total, found, &BTreeMap::new());
+    }
+
+    /// Prints the summary followed by the response count for each status group
+    pub fn print_summary_with_counts(&self, total: usize, found: usize, counts: &BTreeMap<String, usize>) {
         if self.quiet {
             return;
         }
@@ -251,11 +306,72 @@ impl OutputHandler {
             total,
             found
         );
+        if !counts.is_empty() {
+            let breakdown: Vec<String> = counts
+                .iter()
+                .map(|(status, count)| format!("{}: {}", status, count))
+                .collect();
+            println!("{} {}", "Statuses:".bright_yellow().bold(), breakdown.join(", "));
+        }
         println!("{}", separator.bright_cyan());
     }
+    /// Reports response times across the findings
+    pub fn print_timing(&self, timing: Option<TimingStats>) {
+        let Some(timing) = timing.filter(|_| !self.quiet) else {
+            return;
+        };
 
-    #[allow(dead_code)]
-    pub fn get_discovered_dirs(&self) -> Vec<String> {
-        self.discovered_dirs.lock().unwrap().clone()
+        println!("{} {}", "Timing:".bright_yellow().bold(), timing);
     }
+
+    /// Reports how many responses matched the wildcard or soft-404 baseline and were dropped
+    pub fn print_wildcard_filtered(&self, count: usize) {
+        if self.quiet || count == 0 {
+            return;
+        }
+
+        println!(
+            "{} {} responses matched the wildcard or soft-404 baseline",
+            "Filtered:".bright_yellow().bold(),
+            count
+        );
+    }
+
+    /// Reports the size `--auto-filter` settled on and how many responses it hid
+    pub fn print_auto_filtered(&self, size: u64, count: usize) {
+        if self.quiet {
+            return;
+        }
+
+        println!(
+            "{} {} responses of {} bytes, the most common size during warmup",
+            "Auto-filtered:".bright_yellow().bold(),
+            count,
+            size
+        );
+    }
+
+    /// Reports how many responses `--limit-per-status` kept off the output
+    pub fn print_suppressed(&self, suppressed: &BTreeMap<String, usize>) {
+        if self.quiet || suppressed.is_empty() {
+            return;
+        }
+
+        let breakdown: Vec<String> = suppressed
+            .iter()
+            .map(|(status, count)| format!("{}: {}", status, count))
+            .collect();
+        println!(
+            "{} {} (over --limit-per-status)",
+            "Suppressed:".bright_yellow().bold(),
+            breakdown.join(", ")
+        );
+    }
+}
+
+/// The `--show-headers` captures of a result, ordered by name
+fn sorted_headers(result: &ScanResult) -> Vec<(&String, &String)> {
+    let mut headers: Vec<_> = result.headers.iter().collect();
+    headers.sort();
+    headers
 }
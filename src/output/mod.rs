@@ -1,4 +1,6 @@
 pub mod handler;
+pub mod progress;
+pub mod serialize;
 pub mod tui;
 
 pub use handler::OutputHandler;
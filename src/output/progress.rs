@@ -0,0 +1,23 @@
+//! The progress bar shown by console scans in every mode.
+
+use indicatif::{ProgressState, ProgressStyle};
+use std::fmt::Write;
+
+/// Layout of the bar; `{rate}` is filled in by `progress_style`
+pub const PROGRESS_TEMPLATE: &str =
+    "[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {rate} ETA {eta} {msg}";
+
+/// Requests per second so far, from the bar's position and elapsed time
+fn write_rate(state: &ProgressState, out: &mut dyn Write) {
+    let secs = state.elapsed().as_secs_f64();
+    let rate = if secs > 0.0 { state.pos() as f64 / secs } else { 0.0 };
+    let _ = write!(out, "{:.0} req/s", rate);
+}
+
+pub fn progress_style() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template(PROGRESS_TEMPLATE)
+        .unwrap()
+        .with_key("rate", write_rate)
+        .progress_chars("=>-")
+}
@@ -0,0 +1,255 @@
+//! Output file formats shared by the console and TUI paths.
+//!
+//! Both kinds of result are turned into a `ResultRecord` first, so every format has one
+//! schema whichever path wrote it. A new format only needs a writer here.
+
+use crate::core::http_client::{status_text, ScanResult};
+use crate::output::tui::TuiResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Column names for CSV output, in record order
+const CSV_COLUMNS: [&str; 12] = [
+    "URL", "Status Code", "Status Text", "Content Length", "Redirect Location", "Content Type",
+    "Server", "Duration (ms)", "Source", "Allowed Methods", "Headers", "Technologies",
+];
+
+/// One result as it is written to an output file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ResultRecord {
+    pub url: String,
+    pub status_code: u16,
+    pub content_length: u64,
+    pub redirect_location: Option<String>,
+    pub content_type: Option<String>,
+    pub server: Option<String>,
+    pub duration_ms: u64,
+    pub source: Option<String>,
+    pub golden_diff: Option<String>,
+    pub allowed_methods: Option<String>,
+    pub auth_scheme: Option<String>,
+    pub final_url: Option<String>,
+    pub redirect_count: usize,
+    /// Sorted by name so files are stable between runs
+    pub headers: BTreeMap<String, String>,
+    pub technologies: Vec<String>,
+    /// Response body, only with `--include-body`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+impl From<&ScanResult> for ResultRecord {
+    fn from(r: &ScanResult) -> Self {
+        ResultRecord {
+            url: r.url.clone(),
+            status_code: r.status_code,
+            content_length: r.content_length,
+            redirect_location: r.redirect_location.clone(),
+            content_type: r.content_type.clone(),
+            server: r.server.clone(),
+            duration_ms: r.duration_ms,
+            source: r.source.clone(),
+            golden_diff: r.golden_diff.clone(),
+            allowed_methods: r.allowed_methods.clone(),
+            auth_scheme: r.auth_scheme.clone(),
+            final_url: r.final_url.clone(),
+            redirect_count: r.redirect_count,
+            headers: r.headers.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            technologies: r.technologies.clone(),
+            body: None,
+        }
+    }
+}
+
+impl From<&TuiResult> for ResultRecord {
+    fn from(r: &TuiResult) -> Self {
+        ResultRecord {
+            url: r.url.clone(),
+            status_code: r.status_code,
+            content_length: r.content_length,
+            redirect_location: r.redirect_location.clone(),
+            content_type: r.content_type.clone(),
+            server: r.server.clone(),
+            duration_ms: r.duration_ms,
+            source: r.source.clone(),
+            golden_diff: r.golden_diff.clone(),
+            allowed_methods: r.allowed_methods.clone(),
+            auth_scheme: r.auth_scheme.clone(),
+            final_url: r.final_url.clone(),
+            redirect_count: r.redirect_count,
+            headers: r.headers.clone(),
+            technologies: r.technologies.clone(),
+            body: r.body.clone(),
+        }
+    }
+}
+
+impl ResultRecord {
+    /// The record for a result, keeping its body
+    pub fn with_body(result: &ScanResult) -> Self {
+        ResultRecord { body: result.body.clone(), ..result.into() }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("result records always serialize")
+    }
+
+    /// The line used by the plain format, newline included
+    pub fn plain_line(&self) -> String {
+        match &self.redirect_location {
+            Some(location) => format!(
+                "{} [{}] [{}B] [{}ms] -> {}\n",
+                self.url, self.status_code, self.content_length, self.duration_ms, location
+            ),
+            None => format!(
+                "{} [{}] [{}B] [{}ms]\n",
+                self.url, self.status_code, self.content_length, self.duration_ms
+            ),
+        }
+    }
+
+    /// Captured headers as `Name: value` pairs separated by semicolons
+    fn header_list(&self) -> String {
+        self.headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    fn csv_row(&self) -> [String; 12] {
+        [
+            self.url.clone(),
+            self.status_code.to_string(),
+            status_text(self.status_code).to_string(),
+            self.content_length.to_string(),
+            self.redirect_location.clone().unwrap_or_default(),
+            self.content_type.clone().unwrap_or_default(),
+            self.server.clone().unwrap_or_default(),
+            self.duration_ms.to_string(),
+            self.source.clone().unwrap_or_default(),
+            self.allowed_methods.clone().unwrap_or_default(),
+            self.header_list(),
+            self.technologies.join(", "),
+        ]
+    }
+}
+
+/// Opens the file for writing, keeping what is already there when `append` is set
+fn create(file_path: &str, append: bool) -> io::Result<std::fs::File> {
+    if append {
+        return self::append(file_path);
+    }
+    OpenOptions::new().create(true).write(true).truncate(true).open(file_path)
+}
+
+/// Whether an append should start the file from scratch: it is missing or empty
+fn starts_empty(file_path: &str) -> bool {
+    std::fs::metadata(file_path).map_or(true, |meta| meta.len() == 0)
+}
+
+fn append(file_path: &str) -> io::Result<std::fs::File> {
+    OpenOptions::new().create(true).append(true).open(file_path)
+}
+
+/// The hidden file next to `file_path` that an atomic write goes through
+fn temp_path(file_path: &str) -> PathBuf {
+    let path = Path::new(file_path);
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
+
+/// Writes through a temporary file in the same directory that is renamed over `file_path`
+/// once complete, so an interrupted write leaves the old file (or none), never a partial one
+///
+/// `write` gets the temporary file; with `keep` it starts as a copy of the existing file.
+pub fn write_atomically(
+    file_path: &str,
+    keep: bool,
+    write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> io::Result<()> {
+    let temp = temp_path(file_path);
+    let result = (|| {
+        if keep && Path::new(file_path).exists() {
+            std::fs::copy(file_path, &temp)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(&temp)?;
+        write(&mut file)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, file_path)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Writes the records as a pretty-printed JSON array
+///
+/// With `append`, the records are added to the array already in the file.
+pub fn write_json(records: &[ResultRecord], file_path: &str, append: bool) -> io::Result<()> {
+    if !append || starts_empty(file_path) {
+        let json = serde_json::to_string_pretty(records)?;
+        return write_atomically(file_path, false, |file| file.write_all(json.as_bytes()));
+    }
+
+    let existing = std::fs::read_to_string(file_path)?;
+    let serde_json::Value::Array(mut merged) = serde_json::from_str(&existing)? else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} does not hold a JSON array to append to", file_path),
+        ));
+    };
+    merged.extend(records.iter().map(ResultRecord::to_json));
+    let json = serde_json::to_string_pretty(&merged)?;
+    write_atomically(file_path, false, |file| file.write_all(json.as_bytes()))
+}
+
+/// Writes one compact JSON object per line
+pub fn write_jsonl(records: &[ResultRecord], file_path: &str, append: bool) -> io::Result<()> {
+    let mut file = create(file_path, append)?;
+    for record in records {
+        writeln!(file, "{}", record.to_json())?;
+    }
+    Ok(())
+}
+
+/// Appends a single jsonl line, so the file is usable mid-scan
+pub fn append_jsonl(record: &ResultRecord, file_path: &str) -> io::Result<()> {
+    writeln!(append(file_path)?, "{}", record.to_json())
+}
+
+pub fn write_plain(records: &[ResultRecord], file_path: &str, append: bool) -> io::Result<()> {
+    let mut file = create(file_path, append)?;
+    for record in records {
+        file.write_all(record.plain_line().as_bytes())?;
+    }
+    Ok(())
+}
+
+pub fn append_plain(record: &ResultRecord, file_path: &str) -> io::Result<()> {
+    append(file_path)?.write_all(record.plain_line().as_bytes())
+}
+
+/// Writes a header row and one row per record; the csv writer quotes any field holding a
+/// comma, quote or line break (RFC 4180)
+///
+/// With `append`, rows go after those already in the file and the header is only written
+/// to an empty one.
+pub fn write_csv(records: &[ResultRecord], file_path: &str, append: bool) -> io::Result<()> {
+    let header = !append || starts_empty(file_path);
+    write_atomically(file_path, append, |file| {
+        let mut writer = csv::Writer::from_writer(file);
+        if header {
+            writer.write_record(CSV_COLUMNS)?;
+        }
+        for record in records {
+            writer.write_record(record.csv_row())?;
+        }
+        writer.flush()
+    })
+}
@@ -1,4 +1,9 @@
+use crate::cli::CommonArgs;
+use crate::core::http_client::ScanResult;
+use crate::modes::ScanSummary;
+use crate::output::serialize::{self, ResultRecord};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
     execute,
@@ -12,11 +17,14 @@ use ratatui::{
     widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
     Frame, Terminal,
 };
-use std::fs::OpenOptions;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 use serde_json::json;
 
 /// A result to display in the TUI
@@ -29,21 +37,283 @@ pub struct TuiResult {
     pub content_type: Option<String>,
     pub server: Option<String>,
     pub duration_ms: u64,
+    pub source: Option<String>,
+    pub golden_diff: Option<String>,
+    /// Time to first byte; shown next to the total duration when set
+    pub ttfb_ms: Option<u64>,
+    pub allowed_methods: Option<String>,
+    /// Schemes offered in `WWW-Authenticate`
+    pub auth_scheme: Option<String>,
+    pub final_url: Option<String>,
+    pub redirect_count: usize,
+    /// Headers picked with `--show-headers`, sorted by name
+    pub headers: BTreeMap<String, String>,
+    pub technologies: Vec<String>,
+    /// Response body, only kept with `--include-body`
+    pub body: Option<String>,
 }
 
+impl From<ScanResult> for TuiResult {
+    fn from(r: ScanResult) -> Self {
+        TuiResult {
+            url: r.url,
+            status_code: r.status_code,
+            content_length: r.content_length,
+            redirect_location: r.redirect_location,
+            content_type: r.content_type,
+            server: r.server,
+            duration_ms: r.duration_ms,
+            source: r.source,
+            golden_diff: r.golden_diff,
+            ttfb_ms: r.ttfb_ms,
+            allowed_methods: r.allowed_methods,
+            auth_scheme: r.auth_scheme,
+            final_url: r.final_url,
+            redirect_count: r.redirect_count,
+            headers: r.headers.into_iter().collect(),
+            technologies: r.technologies,
+            body: r.body,
+        }
+    }
+}
+
+/// Where and how the results are written
+///
+/// Line-based formats (plain and jsonl) are appended as results arrive, so an interrupted
+/// scan keeps what it found; json and csv are written once the TUI closes.
+#[derive(Clone, Debug)]
+pub struct TuiOutput {
+    pub file: Option<String>,
+    pub format: String,
+    /// Wrap JSON results in an object carrying scan metadata
+    pub json_metadata: bool,
+    /// Keep what the file already holds (`--output-append`)
+    pub append: bool,
+}
+
+impl TuiOutput {
+    pub fn from_common(common: &CommonArgs) -> Self {
+        TuiOutput {
+            file: common.output.clone(),
+            format: common.output_format.clone(),
+            json_metadata: common.json_metadata,
+            append: common.output_append,
+        }
+    }
+
+    /// Whether results go to the file one line at a time as they arrive
+    pub fn is_live(&self) -> bool {
+        self.file.is_some() && !matches!(self.format.as_str(), "json" | "csv")
+    }
+
+    /// Empties the output file so this run's live results start from scratch
+    pub fn start_live(&self) -> Result<()> {
+        if let Some(file_path) = self.file.as_deref().filter(|_| self.is_live() && !self.append) {
+            std::fs::write(file_path, "")?;
+        }
+        Ok(())
+    }
+
+    /// Appends one result to the output file in its line-based format
+    pub fn append_live(&self, result: &TuiResult) -> Result<()> {
+        let Some(file_path) = self.file.as_deref().filter(|_| self.is_live()) else {
+            return Ok(());
+        };
+
+        let record = ResultRecord::from(result);
+        match self.format.as_str() {
+            "jsonl" => serialize::append_jsonl(&record, file_path)?,
+            _ => serialize::append_plain(&record, file_path)?,
+        }
+        Ok(())
+    }
+}
+
+/// Column the results pane is ordered by, cycled with `s`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    Status,
+    Size,
+    Duration,
+    Url,
+}
+
+impl SortKey {
+    fn next(self) -> Self {
+        match self {
+            SortKey::Status => SortKey::Size,
+            SortKey::Size => SortKey::Duration,
+            SortKey::Duration => SortKey::Url,
+            SortKey::Url => SortKey::Status,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SortKey::Status => "status",
+            SortKey::Size => "size",
+            SortKey::Duration => "time",
+            SortKey::Url => "url",
+        }
+    }
+
+    fn compare(self, a: &TuiResult, b: &TuiResult) -> Ordering {
+        match self {
+            SortKey::Status => a.status_code.cmp(&b.status_code),
+            SortKey::Size => a.content_length.cmp(&b.content_length),
+            SortKey::Duration => a.duration_ms.cmp(&b.duration_ms),
+            SortKey::Url => a.url.cmp(&b.url),
+        }
+    }
+}
+
+/// Switch shared between the TUI and the scan workers, flipped with `p`
+///
+/// Workers wait on it before each request, so a pause stops new requests while the
+/// ones already in flight finish.
+#[derive(Clone)]
+pub struct PauseGate {
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl PauseGate {
+    pub fn new() -> Self {
+        PauseGate {
+            paused: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Pauses a running scan or resumes a paused one, returning whether it is now paused
+    pub fn toggle(&self) -> bool {
+        self.paused.send_modify(|paused| *paused = !*paused);
+        self.is_paused()
+    }
+
+    /// Returns once the scan is not paused
+    pub async fn wait(&self) {
+        let mut paused = self.paused.subscribe();
+        let _ = paused.wait_for(|paused| !*paused).await;
+    }
+
+    /// Opens the gate whatever its state, so no worker is left waiting
+    pub fn release(&self) {
+        self.paused.send_replace(false);
+    }
+}
+
+impl Default for PauseGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Progress counted by the scan workers themselves, read by the TUI on each redraw
+///
+/// Counting here rather than with one message per request keeps the totals exact however
+/// far the TUI falls behind, and leaves the channel to results and error details.
+#[derive(Default)]
+pub struct ScanCounters {
+    scanned: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+impl ScanCounters {
+    pub fn scanned(&self) -> usize {
+        self.scanned.load(AtomicOrdering::Relaxed)
+    }
+
+    pub fn errors(&self) -> usize {
+        self.errors.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// Capacity of the result channel; workers wait for room rather than drop results
+const CHANNEL_CAPACITY: usize = 100;
+
+/// The scan side of the TUI: shared counters plus a channel for results and errors
+#[derive(Clone)]
+pub struct TuiSender {
+    tx: mpsc::Sender<TuiMessage>,
+    counters: Arc<ScanCounters>,
+}
+
+impl TuiSender {
+    /// Creates a sender and the receiver the TUI drains
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<TuiMessage>) {
+        let (tx, rx) = mpsc::channel(capacity);
+        let sender = TuiSender {
+            tx,
+            counters: Arc::new(ScanCounters::default()),
+        };
+        (sender, rx)
+    }
+
+    pub fn counters(&self) -> Arc<ScanCounters> {
+        Arc::clone(&self.counters)
+    }
+
+    /// Counts one request as done
+    pub fn scanned(&self) {
+        self.counters.scanned.fetch_add(1, AtomicOrdering::Relaxed);
+    }
+
+    /// Queues a result, waiting for room in the channel so none are lost
+    pub async fn result(&self, result: TuiResult) {
+        let _ = self.tx.send(TuiMessage::Result(Box::new(result))).await;
+    }
+
+    /// Counts a failure and passes its details on when the channel has room
+    ///
+    /// Details are only shown for the latest few errors, so dropping some under load is
+    /// fine; the count itself never is.
+    pub fn error(&self, url: impl Into<String>, err: &anyhow::Error) {
+        self.counters.errors.fetch_add(1, AtomicOrdering::Relaxed);
+        let _ = self.tx.try_send(TuiMessage::error(url, err));
+    }
+
+    /// Tells the TUI the scan has finished
+    pub async fn done(&self) {
+        let _ = self.tx.send(TuiMessage::Done).await;
+    }
+}
+
+/// How many recent errors the TUI keeps for display
+pub const RECENT_ERRORS: usize = 5;
+
 pub struct TuiState {
     pub results: Vec<TuiResult>,
     pub total: usize,
     pub scanned: usize,
     pub found: usize,
     pub errors: usize,
+    /// The latest failures as (URL, reason), oldest first, capped at `RECENT_ERRORS`
+    pub recent_errors: VecDeque<(String, String)>,
     pub start_time: Instant,
+    pub started_at: DateTime<Utc>,
     pub mode: String,
     pub target: String,
     pub wordlist: String,
     pub threads: usize,
     pub scan_complete: bool,
+    /// Mirrors the pause gate so the footer can show it
+    pub paused: bool,
     pub scroll_offset: usize,
+    /// Highlighted row, as a position among the visible results
+    pub cursor: usize,
+    /// URLs marked with space for copying or exporting
+    pub selected: HashSet<String>,
+    /// Outcome of the last copy or export, shown above the footer
+    pub notice: Option<String>,
+    /// Substring the results pane is narrowed to, set with `/`
+    pub filter: Option<String>,
+    /// Keys go to the search prompt instead of the usual bindings
+    pub editing_filter: bool,
+    /// Current order of `results`; discovery order until `s` is pressed
+    pub sort: Option<SortKey>,
 }
 
 impl TuiState {
@@ -54,21 +324,55 @@ impl TuiState {
             scanned: 0,
             found: 0,
             errors: 0,
+            recent_errors: VecDeque::with_capacity(RECENT_ERRORS),
             start_time: Instant::now(),
+            started_at: Utc::now(),
             mode,
             target,
             wordlist,
             threads,
             scan_complete: false,
+            paused: false,
             scroll_offset: 0,
+            cursor: 0,
+            selected: HashSet::new(),
+            notice: None,
+            filter: None,
+            editing_filter: false,
+            sort: None,
         }
     }
 
+    /// Adds a result, keeping the current sort order
     pub fn add_result(&mut self, result: TuiResult) {
         self.found += 1;
-        self.results.push(result);
+        match self.sort {
+            Some(key) => {
+                let position = self
+                    .results
+                    .partition_point(|existing| key.compare(existing, &result) != Ordering::Greater);
+                self.results.insert(position, result);
+            }
+            None => self.results.push(result),
+        }
+    }
+
+    /// Orders the results by `key`, keeping discovery order among equal entries
+    pub fn sort_by(&mut self, key: SortKey) {
+        self.sort = Some(key);
+        self.results.sort_by(|a, b| key.compare(a, b));
+        let last = self.visible_indices().len().saturating_sub(1);
+        self.scroll_offset = self.scroll_offset.min(last);
+        self.cursor = self.cursor.min(last);
     }
 
+    /// Moves to the next sort key: status, size, time, url and back to status
+    pub fn cycle_sort(&mut self) {
+        let key = self.sort.map_or(SortKey::Status, SortKey::next);
+        self.sort_by(key);
+    }
+
+    #[allow(dead_code)]
     pub fn increment_scanned(&mut self) {
         self.scanned += 1;
     }
@@ -77,6 +381,33 @@ impl TuiState {
         self.errors += 1;
     }
 
+    /// Counts a failure and remembers it, dropping the oldest once `RECENT_ERRORS` are kept
+    pub fn push_error(&mut self, url: String, reason: String) {
+        self.increment_errors();
+        if self.recent_errors.len() == RECENT_ERRORS {
+            self.recent_errors.pop_front();
+        }
+        self.recent_errors.push_back((url, reason));
+    }
+
+    /// Takes the scanned and error totals from the workers' counters, which are authoritative
+    pub fn sync_counters(&mut self, counters: &ScanCounters) {
+        self.scanned = counters.scanned();
+        self.errors = counters.errors();
+    }
+
+    /// Applies a message from the scan, appending new results to a live output file
+    pub fn apply_message(&mut self, msg: TuiMessage, output: &TuiOutput) {
+        match msg {
+            TuiMessage::Result(result) => {
+                let _ = output.append_live(&result);
+                self.add_result(*result);
+            }
+            TuiMessage::Error { url, reason } => self.push_error(url, reason),
+            TuiMessage::Done => self.scan_complete = true,
+        }
+    }
+
     pub fn elapsed(&self) -> Duration {
         self.start_time.elapsed()
     }
@@ -90,27 +421,119 @@ impl TuiState {
         }
     }
 
-    pub fn scroll_up(&mut self) {
-        if self.scroll_offset > 0 {
-            self.scroll_offset -= 1;
+    pub fn scroll_to_top(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self, max_visible: usize) {
+        let visible = self.visible_indices().len();
+        if visible > max_visible {
+            self.scroll_offset = visible - max_visible;
+        } else {
+            self.scroll_offset = 0;
+        }
+    }
+
+    /// Indices of the results whose URL or status code contains `query`, ignoring case
+    pub fn filtered_indices(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        self.results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| {
+                result.url.to_lowercase().contains(&query) || result.status_code.to_string().contains(&query)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Indices of the results the pane shows: all of them, or those matching the filter
+    pub fn visible_indices(&self) -> Vec<usize> {
+        match &self.filter {
+            Some(query) => self.filtered_indices(query),
+            None => (0..self.results.len()).collect(),
         }
     }
 
-    pub fn scroll_down(&mut self, max_visible: usize) {
-        if self.scroll_offset + max_visible < self.results.len() {
-            self.scroll_offset += 1;
+    /// Moves the highlighted row by `delta`, scrolling to keep it among `max_visible` rows
+    pub fn move_cursor(&mut self, delta: isize, max_visible: usize) {
+        let count = self.visible_indices().len();
+        if count == 0 {
+            self.cursor = 0;
+            return;
+        }
+
+        self.cursor = self.cursor.saturating_add_signed(delta).min(count - 1);
+        if self.cursor < self.scroll_offset {
+            self.scroll_offset = self.cursor;
+        } else if self.cursor >= self.scroll_offset + max_visible {
+            self.scroll_offset = self.cursor + 1 - max_visible;
         }
     }
 
-    pub fn scroll_to_top(&mut self) {
+    /// The result on the highlighted row
+    pub fn cursor_result(&self) -> Option<&TuiResult> {
+        self.visible_indices().get(self.cursor).map(|&index| &self.results[index])
+    }
+
+    /// Marks the highlighted result, or unmarks it if it already was
+    pub fn toggle_selected(&mut self) {
+        let Some(url) = self.cursor_result().map(|result| result.url.clone()) else {
+            return;
+        };
+        if !self.selected.remove(&url) {
+            self.selected.insert(url);
+        }
+    }
+
+    /// Marked results in display order, or the highlighted one when nothing is marked
+    pub fn selected_results(&self) -> Vec<&TuiResult> {
+        if self.selected.is_empty() {
+            return self.cursor_result().into_iter().collect();
+        }
+        self.results
+            .iter()
+            .filter(|result| self.selected.contains(&result.url))
+            .collect()
+    }
+
+    /// Opens the search prompt, keeping any filter already typed
+    pub fn start_filter(&mut self) {
+        self.editing_filter = true;
+        self.filter.get_or_insert_with(String::new);
+    }
+
+    /// Drops the filter and closes the prompt
+    pub fn clear_filter(&mut self) {
+        self.filter = None;
+        self.editing_filter = false;
         self.scroll_offset = 0;
+        self.cursor = 0;
     }
 
-    pub fn scroll_to_bottom(&mut self, max_visible: usize) {
-        if self.results.len() > max_visible {
-            self.scroll_offset = self.results.len() - max_visible;
-        } else {
-            self.scroll_offset = 0;
+    /// Handles a key while the search prompt is open
+    pub fn edit_filter(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.clear_filter(),
+            KeyCode::Enter => {
+                self.editing_filter = false;
+                if self.filter.as_deref() == Some("") {
+                    self.filter = None;
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(filter) = &mut self.filter {
+                    filter.pop();
+                }
+                self.scroll_offset = 0;
+                self.cursor = 0;
+            }
+            KeyCode::Char(c) => {
+                self.filter.get_or_insert_with(String::new).push(c);
+                self.scroll_offset = 0;
+                self.cursor = 0;
+            }
+            _ => {}
         }
     }
 }
@@ -118,17 +541,19 @@ impl TuiState {
 pub struct Tui {
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
     state: Arc<Mutex<TuiState>>,
+    output: TuiOutput,
+    pause: PauseGate,
 }
 
 impl Tui {
-    pub fn new(state: Arc<Mutex<TuiState>>) -> Result<Self> {
+    pub fn new(state: Arc<Mutex<TuiState>>, output: TuiOutput, pause: PauseGate) -> Result<Self> {
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
-        Ok(Self { terminal, state })
+        Ok(Self { terminal, state, output, pause })
     }
 
     pub fn draw(&mut self) -> Result<()> {
@@ -139,7 +564,7 @@ impl Tui {
         Ok(())
     }
 
-    pub async fn run(&mut self, mut rx: mpsc::Receiver<TuiMessage>) -> Result<()> {
+    pub async fn run(&mut self, mut rx: mpsc::Receiver<TuiMessage>, counters: Arc<ScanCounters>) -> Result<()> {
         let mut scan_finished = false;
         let mut last_draw = Instant::now();
         
@@ -153,38 +578,85 @@ impl Tui {
                 Ok(true) => {
                     match event::read() {
                         Ok(Event::Key(key)) => {
+                            let (editing, filtering) = {
+                                let state = self.state.lock().unwrap();
+                                (state.editing_filter, state.filter.is_some())
+                            };
+                            if editing {
+                                self.state.lock().unwrap().edit_filter(key.code);
+                                continue;
+                            }
+
                             match key.code {
+                                KeyCode::Esc if filtering => {
+                                    self.state.lock().unwrap().clear_filter();
+                                }
                                 KeyCode::Char('q') | KeyCode::Esc => break,
+                                KeyCode::Char('/') => {
+                                    self.state.lock().unwrap().start_filter();
+                                }
+                                KeyCode::Char('s') => {
+                                    self.state.lock().unwrap().cycle_sort();
+                                }
+                                KeyCode::Char('p') => {
+                                    let paused = self.pause.toggle();
+                                    self.state.lock().unwrap().paused = paused;
+                                }
                                 KeyCode::Up | KeyCode::Char('k') => {
                                     let mut state = self.state.lock().unwrap();
-                                    state.scroll_up();
+                                    let max_visible = 20; // Approximate visible items
+                                    state.move_cursor(-1, max_visible);
                                 }
                                 KeyCode::Down | KeyCode::Char('j') => {
                                     let mut state = self.state.lock().unwrap();
-                                    let max_visible = 20; // Approximate visible items
-                                    state.scroll_down(max_visible);
+                                    let max_visible = 20;
+                                    state.move_cursor(1, max_visible);
                                 }
                                 KeyCode::Home | KeyCode::Char('g') => {
                                     let mut state = self.state.lock().unwrap();
                                     state.scroll_to_top();
+                                    state.cursor = 0;
                                 }
                                 KeyCode::End | KeyCode::Char('G') => {
                                     let mut state = self.state.lock().unwrap();
                                     let max_visible = 20;
                                     state.scroll_to_bottom(max_visible);
+                                    state.cursor = state.visible_indices().len().saturating_sub(1);
                                 }
                                 KeyCode::PageUp => {
                                     let mut state = self.state.lock().unwrap();
-                                    for _ in 0..10 {
-                                        state.scroll_up();
-                                    }
+                                    let max_visible = 20;
+                                    state.move_cursor(-10, max_visible);
                                 }
                                 KeyCode::PageDown => {
                                     let mut state = self.state.lock().unwrap();
                                     let max_visible = 20;
-                                    for _ in 0..10 {
-                                        state.scroll_down(max_visible);
-                                    }
+                                    state.move_cursor(10, max_visible);
+                                }
+                                KeyCode::Char(' ') => {
+                                    self.state.lock().unwrap().toggle_selected();
+                                }
+                                KeyCode::Char('y') => {
+                                    let mut state = self.state.lock().unwrap();
+                                    let urls: Vec<&str> =
+                                        state.selected_results().iter().map(|r| r.url.as_str()).collect();
+                                    let count = urls.len();
+                                    let notice = match copy_to_clipboard(urls.join("\n")) {
+                                        Ok(()) => format!("Copied {} URL(s) to the clipboard", count),
+                                        Err(e) => format!("Clipboard unavailable: {}", e),
+                                    };
+                                    state.notice = Some(notice);
+                                }
+                                KeyCode::Char('e') => {
+                                    let mut state = self.state.lock().unwrap();
+                                    let path = format!("rustbuster-selected-{}.txt", Utc::now().format("%Y%m%d-%H%M%S"));
+                                    let selected = state.selected_results();
+                                    let count = selected.len();
+                                    let notice = match export_results(&selected, &path) {
+                                        Ok(()) => format!("Exported {} result(s) to {}", count, path),
+                                        Err(e) => format!("Export failed: {}", e),
+                                    };
+                                    state.notice = Some(notice);
                                 }
                                 _ => {}
                             }
@@ -202,15 +674,8 @@ impl Tui {
                 match rx.try_recv() {
                     Ok(msg) => {
                         let mut state = self.state.lock().unwrap();
-                        match msg {
-                            TuiMessage::Result(result) => state.add_result(result),
-                            TuiMessage::Scanned => state.increment_scanned(),
-                            TuiMessage::Error => state.increment_errors(),
-                            TuiMessage::Done => {
-                                state.scan_complete = true;
-                                scan_finished = true;
-                            }
-                        }
+                        state.apply_message(msg, &self.output);
+                        scan_finished = state.scan_complete;
                         drop(state);
                         messages_processed += 1;
                     }
@@ -227,6 +692,7 @@ impl Tui {
                     }
                 }
             }
+            self.state.lock().unwrap().sync_counters(&counters);
 
             if messages_processed > 0 || last_draw.elapsed() > Duration::from_millis(100) {
                 let _ = self.draw();
@@ -251,25 +717,55 @@ impl Drop for Tui {
 }
 
 pub enum TuiMessage {
-    Result(TuiResult),
-    Scanned,
-    Error,
+    Result(Box<TuiResult>),
+    Error { url: String, reason: String },
     Done,
 }
 
+impl TuiMessage {
+    /// An error naming the failed URL and the innermost cause, which is the most readable part
+    pub fn error(url: impl Into<String>, err: &anyhow::Error) -> Self {
+        TuiMessage::Error {
+            url: url.into(),
+            reason: err.root_cause().to_string(),
+        }
+    }
+}
+
 fn render_ui(f: &mut Frame, state: &TuiState) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(5),  // Header
             Constraint::Min(10),    // Results
+            Constraint::Length(if state.notice.is_none() && state.recent_errors.is_empty() { 0 } else { 1 }), // Notice or last error
             Constraint::Length(5),  // Progress & Stats
         ])
         .split(f.area());
 
     render_header(f, chunks[0], state);
     render_results(f, chunks[1], state);
-    render_footer(f, chunks[2], state);
+    render_status_line(f, chunks[2], state);
+    render_footer(f, chunks[3], state);
+}
+
+fn render_status_line(f: &mut Frame, area: Rect, state: &TuiState) {
+    if let Some(notice) = &state.notice {
+        let line = Span::styled(format!(" {}", notice), Style::default().fg(Color::Yellow));
+        f.render_widget(Paragraph::new(Line::from(line)), area);
+        return;
+    }
+
+    let Some((url, reason)) = state.recent_errors.back() else {
+        return;
+    };
+
+    let line = Line::from(vec![
+        Span::styled(" Last error: ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        Span::raw(format!("{} ", url)),
+        Span::styled(format!("- {}", reason), Style::default().fg(Color::Gray)),
+    ]);
+    f.render_widget(Paragraph::new(line), area);
 }
 
 fn render_header(f: &mut Frame, area: Rect, state: &TuiState) {
@@ -300,70 +796,109 @@ fn render_header(f: &mut Frame, area: Rect, state: &TuiState) {
     f.render_widget(header, area);
 }
 
+/// Builds the result line shown in the results pane
+pub fn result_line(result: &TuiResult) -> Line<'static> {
+    let status_color = match result.status_code {
+        200..=299 => Color::Green,
+        300..=399 => Color::Yellow,
+        400..=499 => Color::Red,
+        500..=599 => Color::Magenta,
+        _ => Color::White,
+    };
+
+    let status_text = match result.status_code {
+        200 => "OK", 201 => "Created", 204 => "No Content",
+        301 => "Moved", 302 => "Found", 307 => "Redirect",
+        401 => "Unauthorized", 403 => "Forbidden", 404 => "Not Found",
+        500 => "Error", 502 => "Bad Gateway", 503 => "Unavailable",
+        _ => "",
+    };
+
+    let mut line_spans = vec![
+        Span::styled(
+            format!("[{} {}] ", result.status_code, status_text),
+            Style::default().fg(status_color).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("{} ", result.url)),
+        Span::styled(
+            format!("[{}B]", result.content_length),
+            Style::default().fg(Color::Gray),
+        ),
+    ];
+
+    let timing = match result.ttfb_ms {
+        Some(ttfb) => format!(" [{}ms/{}ms]", ttfb, result.duration_ms),
+        None => format!(" [{}ms]", result.duration_ms),
+    };
+    line_spans.push(Span::styled(timing, Style::default().fg(Color::Magenta)));
+
+    if let Some(content_type) = &result.content_type {
+        line_spans.push(Span::styled(
+            format!(" [{}]", content_type),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+
+    if let Some(location) = &result.redirect_location {
+        line_spans.push(Span::styled(
+            format!(" -> {}", location),
+            Style::default().fg(Color::Blue),
+        ));
+    }
+
+    if let Some(diff) = &result.golden_diff {
+        line_spans.push(Span::styled(
+            format!(" [changed: {}]", diff),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    if let Some(methods) = &result.allowed_methods {
+        line_spans.push(Span::styled(
+            format!(" [allow: {}]", methods),
+            Style::default().fg(Color::Green),
+        ));
+    }
+
+    if let Some(scheme) = &result.auth_scheme {
+        line_spans.push(Span::styled(
+            format!(" [auth: {}]", scheme),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
+    Line::from(line_spans)
+}
+
 fn render_results(f: &mut Frame, area: Rect, state: &TuiState) {
     let max_visible = area.height.saturating_sub(2) as usize;
-    let total_results = state.results.len();
+    let visible = state.visible_indices();
+    let total_results = visible.len();
     
     let start_idx = state.scroll_offset;
     let end_idx = (start_idx + max_visible).min(total_results);
     
-    let results: Vec<ListItem> = state
-        .results
+    let results: Vec<ListItem> = visible
         .iter()
+        .enumerate()
         .skip(start_idx)
         .take(max_visible)
-        .map(|result| {
-            let status_color = match result.status_code {
-                200..=299 => Color::Green,
-                300..=399 => Color::Yellow,
-                400..=499 => Color::Red,
-                500..=599 => Color::Magenta,
-                _ => Color::White,
-            };
-            
-            let status_text = match result.status_code {
-                200 => "OK", 201 => "Created", 204 => "No Content",
-                301 => "Moved", 302 => "Found", 307 => "Redirect",
-                401 => "Unauthorized", 403 => "Forbidden", 404 => "Not Found",
-                500 => "Error", 502 => "Bad Gateway", 503 => "Unavailable",
-                _ => "",
-            };
-
-            let mut line_spans = vec![
-                Span::styled(
-                    format!("[{} {}] ", result.status_code, status_text),
-                    Style::default().fg(status_color).add_modifier(Modifier::BOLD),
-                ),
-                Span::raw(format!("{} ", result.url)),
-                Span::styled(
-                    format!("[{}B]", result.content_length),
-                    Style::default().fg(Color::Gray),
-                ),
-                Span::styled(
-                    format!(" [{}ms]", result.duration_ms),
-                    Style::default().fg(Color::Magenta),
-                ),
-            ];
-            
-            if let Some(content_type) = &result.content_type {
-                line_spans.push(Span::styled(
-                    format!(" [{}]", content_type),
-                    Style::default().fg(Color::Cyan),
-                ));
-            }
+        .map(|(row, &index)| {
+            let result = &state.results[index];
+            let marker = if state.selected.contains(&result.url) { "● " } else { "  " };
+            let mut line = result_line(result);
+            line.spans.insert(0, Span::styled(marker, Style::default().fg(Color::Yellow)));
 
-            if let Some(location) = &result.redirect_location {
-                line_spans.push(Span::styled(
-                    format!(" -> {}", location),
-                    Style::default().fg(Color::Blue),
-                ));
+            let item = ListItem::new(line);
+            if row == state.cursor {
+                item.style(Style::default().bg(Color::DarkGray))
+            } else {
+                item
             }
-
-            ListItem::new(Line::from(line_spans))
         })
         .collect();
 
-    let title = if total_results > max_visible {
+    let mut title = if total_results > max_visible {
         format!(
             "Results (Found: {}) - Showing {}-{} of {} [↑↓ to scroll, g/G for top/bottom]",
             state.found,
@@ -375,6 +910,19 @@ fn render_results(f: &mut Frame, area: Rect, state: &TuiState) {
         format!("Results (Found: {})", state.found)
     };
 
+    match (&state.filter, state.editing_filter) {
+        (Some(query), true) => title.push_str(&format!(" - Search: {}_ [Enter to keep, Esc to clear]", query)),
+        (Some(query), false) => title.push_str(&format!(" - Filter: \"{}\" ({} matches, Esc to clear)", query, total_results)),
+        (None, _) => title.push_str(" [/ to search]"),
+    }
+    match state.sort {
+        Some(key) => title.push_str(&format!(" [sort: {}, s to change]", key.label())),
+        None => title.push_str(" [s to sort]"),
+    }
+    if !state.selected.is_empty() {
+        title.push_str(&format!(" [{} selected, y to copy, e to export]", state.selected.len()));
+    }
+
     let results_list = List::new(results)
         .block(
             Block::default()
@@ -400,8 +948,10 @@ fn render_footer(f: &mut Frame, area: Rect, state: &TuiState) {
 
     let progress_title = if state.scan_complete {
         "Progress - COMPLETE ✓"
+    } else if state.paused {
+        "Progress - PAUSED (p to resume)"
     } else {
-        "Progress - Scanning..."
+        "Progress - Scanning... (p to pause)"
     };
 
     let progress_label = if state.total > 0 {
@@ -476,21 +1026,22 @@ fn render_footer(f: &mut Frame, area: Rect, state: &TuiState) {
     f.render_widget(stats, footer_chunks[1]);
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_tui_mode<F, Fut>(
     mode: String,
     target: String,
     wordlist: String,
     threads: usize,
     total: usize,
-    output_file: Option<String>,
-    output_format: String,
+    output: TuiOutput,
     scan_fn: F,
-) -> Result<()>
+) -> Result<ScanSummary>
 where
-    F: FnOnce(mpsc::Sender<TuiMessage>) -> Fut + Send + 'static,
+    F: FnOnce(TuiSender, PauseGate) -> Fut + Send + 'static,
     Fut: std::future::Future<Output = Result<()>> + Send + 'static,
 {
-    let (tx, rx) = mpsc::channel(100);
+    let (tx, rx) = TuiSender::channel(CHANNEL_CAPACITY);
+    let counters = tx.counters();
     
     let state = Arc::new(Mutex::new(TuiState::new(
         mode,
@@ -500,103 +1051,97 @@ where
         total,
     )));
     
-    let mut tui = Tui::new(Arc::clone(&state))?;
+    output.start_live()?;
+    let pause = PauseGate::new();
+    let mut tui = Tui::new(Arc::clone(&state), output.clone(), pause.clone())?;
     
+    let gate = pause.clone();
     let scan_handle = tokio::spawn(async move {
-        scan_fn(tx).await
+        scan_fn(tx, pause).await
     });
     
-    let tui_result = tui.run(rx).await;
+    let tui_result = tui.run(rx, Arc::clone(&counters)).await;
     
-    let _ = scan_handle.await;
+    let scan_result = stop_scan(scan_handle, &gate).await;
+    state.lock().unwrap().sync_counters(&counters);
     
-    if let Some(output_path) = output_file {
-        let state = state.lock().unwrap();
-        write_results_to_file(&state.results, &output_path, &output_format)?;
-        drop(state);
+    if let Some(output_path) = &output.file {
+        // Line-based formats are already on disk
+        if !output.is_live() {
+            let state = state.lock().unwrap();
+            write_results_to_file(&state, &output)?;
+            drop(state);
+        }
         
         println!("\nResults saved to: {}", output_path);
     }
     
-    tui_result
+    tui_result?;
+    scan_result?;
+    let found = state.lock().unwrap().found;
+    Ok(ScanSummary { found })
 }
 
-fn write_results_to_file(results: &[TuiResult], file_path: &str, format: &str) -> Result<()> {
-    match format {
-        "json" => write_json_results(results, file_path),
-        "csv" => write_csv_results(results, file_path),
-        _ => write_plain_results(results, file_path),
+/// Ends the scan task once the TUI has closed
+///
+/// Quitting while paused would leave the workers waiting at the gate for good, so the gate
+/// is opened and a scan still running is aborted. An aborted scan is not an error.
+pub async fn stop_scan(handle: JoinHandle<Result<()>>, pause: &PauseGate) -> Result<()> {
+    pause.release();
+    if !handle.is_finished() {
+        handle.abort();
     }
-}
-
-fn write_plain_results(results: &[TuiResult], file_path: &str) -> Result<()> {
-    let mut file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(file_path)?;
-
-    for result in results {
-        let line = if let Some(location) = &result.redirect_location {
-            format!(
-                "{} [{}] [{}B] [{}ms] -> {}\n",
-                result.url, result.status_code, result.content_length, result.duration_ms, location
-            )
-        } else {
-            format!(
-                "{} [{}] [{}B] [{}ms]\n",
-                result.url, result.status_code, result.content_length, result.duration_ms
-            )
-        };
-        file.write_all(line.as_bytes())?;
+    match handle.await {
+        Ok(result) => result,
+        Err(e) if e.is_cancelled() => Ok(()),
+        Err(e) => Err(e.into()),
     }
+}
 
+/// Puts text on the system clipboard, which fails on headless machines without one
+fn copy_to_clipboard(text: String) -> Result<()> {
+    arboard::Clipboard::new()?.set_text(text)?;
     Ok(())
 }
 
-fn write_json_results(results: &[TuiResult], file_path: &str) -> Result<()> {
-    let json_results: Vec<_> = results
-        .iter()
-        .map(|r| {
-            json!({
-                "url": r.url,
-                "status_code": r.status_code,
-                "content_length": r.content_length,
-                "duration_ms": r.duration_ms,
-                "redirect_location": r.redirect_location,
-                "content_type": r.content_type,
-                "server": r.server,
-            })
-        })
-        .collect();
-
-    let json_output = serde_json::to_string_pretty(&json_results)?;
-    std::fs::write(file_path, json_output)?;
+/// Writes the given results to a new file, one plain-format line each
+pub fn export_results(results: &[&TuiResult], file_path: &str) -> Result<()> {
+    let records: Vec<ResultRecord> = results.iter().map(|r| ResultRecord::from(*r)).collect();
+    serialize::write_plain(&records, file_path, false)?;
     Ok(())
 }
 
-fn write_csv_results(results: &[TuiResult], file_path: &str) -> Result<()> {
-    let mut file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(file_path)?;
-
-    writeln!(file, "URL,Status Code,Content Length,Duration (ms),Redirect Location,Content Type,Server")?;
+/// Writes the final results to the configured output file
+pub fn write_results_to_file(state: &TuiState, output: &TuiOutput) -> Result<()> {
+    let Some(file_path) = output.file.as_deref() else {
+        return Ok(());
+    };
+    let records: Vec<ResultRecord> = state.results.iter().map(ResultRecord::from).collect();
 
-    for result in results {
-        writeln!(
-            file,
-            "{},{},{},{},{},{},{}",
-            result.url,
-            result.status_code,
-            result.content_length,
-            result.duration_ms,
-            result.redirect_location.as_deref().unwrap_or(""),
-            result.content_type.as_deref().unwrap_or(""),
-            result.server.as_deref().unwrap_or(""),
-        )?;
+    match output.format.as_str() {
+        "json" if output.json_metadata => write_json_with_metadata(state, &records, file_path)?,
+        "json" => serialize::write_json(&records, file_path, output.append)?,
+        "csv" => serialize::write_csv(&records, file_path, output.append)?,
+        "jsonl" => serialize::write_jsonl(&records, file_path, output.append)?,
+        _ => serialize::write_plain(&records, file_path, output.append)?,
     }
+    Ok(())
+}
+
+fn write_json_with_metadata(state: &TuiState, records: &[ResultRecord], file_path: &str) -> Result<()> {
+    let output = json!({
+        "target": state.target,
+        "mode": state.mode,
+        "total_scanned": state.scanned,
+        "found": state.found,
+        "errors": state.errors,
+        "duration_ms": state.elapsed().as_millis() as u64,
+        "started_at": state.started_at.to_rfc3339(),
+        "rustbuster_version": env!("CARGO_PKG_VERSION"),
+        "results": records,
+    });
 
+    let json = serde_json::to_string_pretty(&output)?;
+    serialize::write_atomically(file_path, false, |file| file.write_all(json.as_bytes()))?;
     Ok(())
 }
@@ -0,0 +1,51 @@
+//! Saves the response bodies of findings for offline analysis.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+/// Longest sanitized URL prefix kept in a file name, leaving room for the hash suffix
+const MAX_NAME_LEN: usize = 100;
+
+/// A directory holding one body file per finding
+pub struct BodyStore {
+    dir: PathBuf,
+}
+
+impl BodyStore {
+    /// Opens (and creates if needed) the body directory
+    pub fn new(dir: &str) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create bodies directory: {}", dir))?;
+        Ok(BodyStore { dir: PathBuf::from(dir) })
+    }
+
+    /// Writes a body to the file for its URL and returns the path
+    pub fn save(&self, url: &str, body: &[u8]) -> Result<PathBuf> {
+        let path = self.dir.join(file_name(url));
+        fs::write(&path, body)
+            .with_context(|| format!("Failed to write body file: {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Builds a readable, bounded file name for a URL
+///
+/// The sanitized URL is cut to `MAX_NAME_LEN` characters and suffixed with a short hash
+/// of the full URL, so long or similar URLs never collide.
+pub fn file_name(url: &str) -> String {
+    let readable = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .take(MAX_NAME_LEN)
+        .collect::<String>();
+
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    let hash = format!("{:x}", hasher.finalize());
+
+    format!("{}_{}.body", readable.trim_matches('_'), &hash[..12])
+}
@@ -0,0 +1,130 @@
+//! Scan-state checkpoints for `--resume-state`.
+//!
+//! Unlike sessions, a state file lives wherever the user points it, is rewritten every few
+//! seconds while the scan runs, and keeps a versioned JSON layout meant for other tools:
+//!
+//! ```json
+//! {"version": 1, "updated_at": "...", "completed": ["http://..."], "results": [{...}]}
+//! ```
+//!
+//! Results use the same fields as the json output format.
+
+use crate::core::http_client::ScanResult;
+use crate::output::serialize::{self, ResultRecord};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Layout version written into every state file
+pub const STATE_VERSION: u32 = 1;
+
+/// How often a running scan rewrites its state file
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What a state file holds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScanState {
+    pub version: u32,
+    pub updated_at: DateTime<Utc>,
+    /// Every request that got a response, sorted; see `ScanTarget::completion_key`
+    pub completed: BTreeSet<String>,
+    /// Results that were shown, in the order they arrived
+    pub results: Vec<ResultRecord>,
+}
+
+impl ScanState {
+    fn new() -> Self {
+        ScanState {
+            version: STATE_VERSION,
+            updated_at: Utc::now(),
+            completed: BTreeSet::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Reads a state file, which must have a version this build understands
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state file: {}", path))?;
+        let state: ScanState = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse state file: {}", path))?;
+        if state.version != STATE_VERSION {
+            bail!(
+                "State file {} has version {}, but this build reads version {}",
+                path,
+                state.version,
+                STATE_VERSION
+            );
+        }
+        Ok(state)
+    }
+}
+
+/// A state file being kept up to date by a scan
+pub struct Checkpoint {
+    path: String,
+    state: Mutex<ScanState>,
+    last_saved: Mutex<Instant>,
+}
+
+impl Checkpoint {
+    /// Picks up the state in `path` when the file exists, otherwise starts an empty one
+    pub fn open(path: &str) -> Result<Self> {
+        let state = if Path::new(path).exists() {
+            ScanState::load(path)?
+        } else {
+            ScanState::new()
+        };
+
+        Ok(Checkpoint {
+            path: path.to_string(),
+            state: Mutex::new(state),
+            last_saved: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Whether an earlier run already got a response for this request key
+    pub fn is_completed(&self, key: &str) -> bool {
+        self.state.lock().unwrap().completed.contains(key)
+    }
+
+    /// Marks a request key as done, keeping its result when it was shown, and saves when one is due
+    pub fn record(&self, key: &str, shown: Option<&ScanResult>) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.completed.insert(key.to_string());
+            if let Some(result) = shown {
+                state.results.push(ResultRecord::from(result));
+            }
+        }
+
+        let due = {
+            let mut last_saved = self.last_saved.lock().unwrap();
+            let due = last_saved.elapsed() >= CHECKPOINT_INTERVAL;
+            if due {
+                *last_saved = Instant::now();
+            }
+            due
+        };
+        if due {
+            // A missed checkpoint is retried at the next interval and at the end of the scan
+            let _ = self.save();
+        }
+    }
+
+    /// Writes the state file, replacing the old one only once the new one is complete
+    pub fn save(&self) -> Result<()> {
+        let json = {
+            let mut state = self.state.lock().unwrap();
+            state.updated_at = Utc::now();
+            serde_json::to_string(&*state)?
+        };
+        serialize::write_atomically(&self.path, false, |file| file.write_all(json.as_bytes()))
+            .with_context(|| format!("Failed to write state file: {}", self.path))
+    }
+}
@@ -1,14 +1,40 @@
+use crate::cli::CommonArgs;
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::ArgMatches;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+/// Environment variable naming a config file, used when --config is not given
+pub const CONFIG_ENV: &str = "RUSTBUSTER_CONFIG";
+
+/// Values accepted by `--output-format`
+const OUTPUT_FORMATS: [&str; 4] = ["plain", "json", "jsonl", "csv"];
+
+/// Defaults from `~/.rustbuster.toml`, used for options not given on the command line
+///
+/// Top-level keys apply to every mode; a `[dir]`, `[dns]`, `[vhost]` or `[fuzz]` table
+/// holds the same keys for one mode and wins over the top level.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Config {
     pub default_threads: Option<usize>,
     pub default_timeout: Option<u64>,
     pub default_user_agent: Option<String>,
     pub default_wordlist: Option<String>,
     pub proxy: Option<String>,
+    /// Statuses to report, as for -s
+    pub default_status_codes: Option<Vec<u16>>,
+    /// Headers in `Name: value` form, as for -H
+    pub default_headers: Option<Vec<String>>,
+    pub default_output_format: Option<String>,
+    /// Extensions for dir and fuzz modes, as for -x
+    pub default_extensions: Option<Vec<String>>,
+    pub dir: Option<Box<Config>>,
+    pub dns: Option<Box<Config>>,
+    pub vhost: Option<Box<Config>>,
+    pub fuzz: Option<Box<Config>>,
 }
 
 impl Config {
@@ -22,17 +48,113 @@ impl Config {
         toml::from_str(&content).ok()
     }
 
+    /// Reads a config the user asked for by name; unlike the default file, it must exist and parse
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))
+    }
+
     fn get_config_path() -> Option<PathBuf> {
         let home = dirs::home_dir()?;
         Some(home.join(".rustbuster.toml"))
     }
-}
 
-pub fn load_config() {
-    if let Some(config) = Config::load() {
-        println!("[*] Loaded configuration from ~/.rustbuster.toml");
-        if config.proxy.is_some() {
-            println!("[*] Default proxy configured");
+    /// The defaults for one mode: its own table merged over the top-level keys
+    pub fn for_mode(&self, mode: &str) -> Config {
+        let section = match mode {
+            "dir" => self.dir.as_deref(),
+            "dns" => self.dns.as_deref(),
+            "vhost" => self.vhost.as_deref(),
+            "fuzz" => self.fuzz.as_deref(),
+            _ => None,
+        };
+        let Some(section) = section else {
+            return Config { dir: None, dns: None, vhost: None, fuzz: None, ..self.clone() };
+        };
+
+        Config {
+            default_threads: section.default_threads.or(self.default_threads),
+            default_timeout: section.default_timeout.or(self.default_timeout),
+            default_user_agent: section.default_user_agent.clone().or_else(|| self.default_user_agent.clone()),
+            default_wordlist: section.default_wordlist.clone().or_else(|| self.default_wordlist.clone()),
+            proxy: section.proxy.clone().or_else(|| self.proxy.clone()),
+            default_status_codes: section.default_status_codes.clone().or_else(|| self.default_status_codes.clone()),
+            default_headers: section.default_headers.clone().or_else(|| self.default_headers.clone()),
+            default_output_format: section.default_output_format.clone().or_else(|| self.default_output_format.clone()),
+            default_extensions: section.default_extensions.clone().or_else(|| self.default_extensions.clone()),
+            ..Config::default()
+        }
+    }
+
+    /// Fills in the options that `matches` did not get from the command line
+    pub fn apply(&self, common: &mut CommonArgs, matches: &ArgMatches) -> Result<()> {
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+
+        if let Some(threads) = self.default_threads.filter(|_| unset("threads")) {
+            common.threads = threads;
+        }
+        if let Some(timeout) = self.default_timeout.filter(|_| unset("timeout")) {
+            common.timeout = timeout;
+        }
+        if let Some(user_agent) = self.default_user_agent.as_ref().filter(|_| unset("user_agent")) {
+            common.user_agent = user_agent.clone();
+        }
+        if let Some(wordlist) = self.default_wordlist.as_ref().filter(|_| unset("wordlist")) {
+            common.wordlist = vec![wordlist.clone()];
+        }
+        if let Some(proxy) = self.proxy.as_ref().filter(|_| unset("proxy")) {
+            common.proxy = Some(proxy.clone());
+        }
+        if let Some(codes) = self.default_status_codes.as_ref().filter(|_| unset("status_codes")) {
+            common.status_codes = codes.iter().map(u16::to_string).collect::<Vec<_>>().join(",");
+        }
+        if let Some(headers) = self.default_headers.as_ref().filter(|_| unset("headers")) {
+            common.headers = headers.clone();
+        }
+        if let Some(format) = self.default_output_format.as_ref().filter(|_| unset("output_format")) {
+            if !OUTPUT_FORMATS.contains(&format.as_str()) {
+                bail!(
+                    "default_output_format in the config must be one of {}, not '{}'",
+                    OUTPUT_FORMATS.join(", "),
+                    format
+                );
+            }
+            common.output_format = format.clone();
+        }
+        Ok(())
+    }
+
+    /// Fills in -x for the modes that take extensions
+    pub fn apply_extensions(&self, extensions: &mut Option<String>, matches: &ArgMatches) {
+        if matches.value_source("extensions") == Some(ValueSource::CommandLine) {
+            return;
         }
+        if let Some(defaults) = &self.default_extensions {
+            *extensions = Some(defaults.join(","));
+        }
+    }
+}
+
+/// The explicitly chosen config file: --config first, then `RUSTBUSTER_CONFIG`
+pub fn explicit_config_path(flag: Option<&Path>, env: Option<OsString>) -> Option<PathBuf> {
+    flag.map(Path::to_path_buf)
+        .or_else(|| env.filter(|value| !value.is_empty()).map(PathBuf::from))
+}
+
+/// Loads the --config or `RUSTBUSTER_CONFIG` file if one is set, otherwise `~/.rustbuster.toml`
+pub fn load_config(flag: Option<&Path>) -> Result<Option<Config>> {
+    let (config, source) = match explicit_config_path(flag, std::env::var_os(CONFIG_ENV)) {
+        Some(path) => (Config::load_from(&path)?, path.display().to_string()),
+        None => match Config::load() {
+            Some(config) => (config, "~/.rustbuster.toml".to_string()),
+            None => return Ok(None),
+        },
+    };
+    eprintln!("[*] Loaded configuration from {}", source);
+    if config.proxy.is_some() {
+        eprintln!("[*] Default proxy configured");
     }
+    Ok(Some(config))
 }
@@ -0,0 +1,196 @@
+//! Technology detection for `--fingerprint`.
+//!
+//! Each rule names a technology and a regex that is matched against one part of the
+//! response: a header value, the names of the cookies it sets, or the body. A response is
+//! tagged with every technology that has at least one matching rule.
+//!
+//! `--fingerprint-rules` adds rules from a TOML or JSON file after the built-in ones:
+//!
+//! ```toml
+//! [[rules]]
+//! technology = "Acme CMS"
+//! pattern = "acme-cms-[0-9]+"      # matched against the body
+//!
+//! [[rules]]
+//! technology = "Acme Gateway"
+//! header = "X-Acme-Node"          # matched against this header's value
+//! pattern = "."
+//! ```
+
+use crate::cli::CommonArgs;
+use anyhow::{bail, Context, Result};
+use regex::{Regex, RegexBuilder};
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+use std::path::Path;
+
+/// The part of a response a rule looks at
+pub enum RuleTarget {
+    /// The value of the named header, once per occurrence
+    Header(String),
+    /// The name of each cookie in `Set-Cookie`
+    Cookie,
+    Body,
+}
+
+pub struct Rule {
+    pub technology: String,
+    pub target: RuleTarget,
+    pub pattern: Regex,
+}
+
+impl Rule {
+    /// Compiles `pattern` case-insensitively, as every rule is matched
+    fn new(technology: &str, target: RuleTarget, pattern: &str) -> Result<Self> {
+        let pattern = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .with_context(|| format!("Invalid fingerprint pattern for {}: {}", technology, pattern))?;
+        Ok(Rule { technology: technology.to_string(), target, pattern })
+    }
+}
+
+/// A user-defined rule as written in a `--fingerprint-rules` file
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FingerprintRule {
+    pub technology: String,
+    /// Regex for the body, or for the header value or cookie names when those are set
+    pub pattern: String,
+    /// Header to match instead of the body
+    #[serde(default)]
+    pub header: Option<String>,
+    /// Match the names of cookies the response sets instead of the body
+    #[serde(default)]
+    pub cookie: bool,
+}
+
+impl FingerprintRule {
+    fn compile(&self) -> Result<Rule> {
+        let target = match (&self.header, self.cookie) {
+            (Some(_), true) => bail!("Fingerprint rule for {} sets both header and cookie", self.technology),
+            (Some(header), false) => RuleTarget::Header(header.clone()),
+            (None, true) => RuleTarget::Cookie,
+            (None, false) => RuleTarget::Body,
+        };
+        Rule::new(&self.technology, target, &self.pattern)
+    }
+}
+
+/// The layout of a rules file: a `rules` list in TOML (`[[rules]]`) or JSON
+#[derive(Deserialize)]
+struct RuleFile {
+    rules: Vec<FingerprintRule>,
+}
+
+/// Reads the rules in a TOML file, or a JSON one when the name ends in `.json`
+pub fn load_rules(path: &str) -> Result<Vec<FingerprintRule>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read fingerprint rules: {}", path))?;
+    let is_json = Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    let file: RuleFile = if is_json {
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse fingerprint rules: {}", path))?
+    } else {
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse fingerprint rules: {}", path))?
+    };
+    Ok(file.rules)
+}
+
+/// Built-in rules as (technology, header name or `cookie`/`body`, case-insensitive regex)
+const BUILTIN_RULES: [(&str, &str, &str); 20] = [
+    ("nginx", "server", "nginx"),
+    ("Apache", "server", "apache"),
+    ("Microsoft-IIS", "server", "microsoft-iis"),
+    ("LiteSpeed", "server", "litespeed"),
+    ("Cloudflare", "server", "cloudflare"),
+    ("Cloudflare", "cf-ray", "."),
+    ("PHP", "x-powered-by", "php"),
+    ("PHP", "cookie", "^phpsessid$"),
+    ("ASP.NET", "x-powered-by", r"asp\.net"),
+    ("ASP.NET", "x-aspnet-version", "."),
+    ("ASP.NET", "cookie", r"^asp\.net_sessionid$"),
+    ("Express", "x-powered-by", "express"),
+    ("Java", "cookie", "^jsessionid$"),
+    ("Laravel", "cookie", "^laravel_session$"),
+    ("Django", "cookie", "^(csrftoken|django_language)$"),
+    ("WordPress", "body", "/wp-(content|includes)/"),
+    ("WordPress", "link", "api\\.w\\.org"),
+    ("Drupal", "x-generator", "drupal"),
+    ("Drupal", "body", "drupal-settings-json|/sites/default/files/"),
+    ("Joomla", "body", r#"content="joomla"#),
+];
+
+/// Tags responses with the technologies their rules match
+pub struct Fingerprinter {
+    rules: Vec<Rule>,
+}
+
+impl Fingerprinter {
+    /// The built-in ruleset
+    pub fn builtin() -> Self {
+        let rules = BUILTIN_RULES
+            .iter()
+            .map(|(technology, target, pattern)| {
+                let target = match *target {
+                    "cookie" => RuleTarget::Cookie,
+                    "body" => RuleTarget::Body,
+                    header => RuleTarget::Header(header.to_string()),
+                };
+                Rule::new(technology, target, pattern).expect("built-in fingerprint patterns are valid")
+            })
+            .collect();
+
+        Fingerprinter { rules }
+    }
+
+    /// The ruleset for `--fingerprint`, extended by `--fingerprint-rules`, which implies it
+    pub fn from_common(common: &CommonArgs) -> Result<Option<Self>> {
+        let Some(path) = &common.fingerprint_rules else {
+            return Ok(common.fingerprint.then(Self::builtin));
+        };
+
+        let mut fingerprinter = Self::builtin();
+        for rule in load_rules(path)? {
+            let rule = rule.compile().with_context(|| format!("In fingerprint rules: {}", path))?;
+            fingerprinter.rules.push(rule);
+        }
+        Ok(Some(fingerprinter))
+    }
+
+    /// Technologies with a matching rule, each once, in ruleset order
+    pub fn detect(&self, headers: &HeaderMap, body: &str) -> Vec<String> {
+        let cookie_names: Vec<&str> = headers
+            .get_all("set-cookie")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .filter_map(|cookie| cookie.split_once('=').map(|(name, _)| name.trim()))
+            .collect();
+
+        let mut technologies: Vec<String> = Vec::new();
+        for rule in &self.rules {
+            if technologies.contains(&rule.technology) {
+                continue;
+            }
+
+            let matched = match &rule.target {
+                RuleTarget::Header(name) => headers
+                    .get_all(name.as_str())
+                    .iter()
+                    .filter_map(|v| v.to_str().ok())
+                    .any(|value| rule.pattern.is_match(value)),
+                RuleTarget::Cookie => cookie_names.iter().any(|name| rule.pattern.is_match(name)),
+                RuleTarget::Body => rule.pattern.is_match(body),
+            };
+            if matched {
+                technologies.push(rule.technology.clone());
+            }
+        }
+
+        technologies
+    }
+}
@@ -0,0 +1,78 @@
+//! Golden copies of response bodies used to detect content changes between runs.
+//!
+//! The first time a URL is seen its body is stored in the golden directory; later
+//! runs compare the live body against that copy and summarize what changed.
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Outcome of comparing a response body against its golden copy
+#[derive(Debug, PartialEq, Eq)]
+pub enum GoldenStatus {
+    /// No golden copy existed yet, so the body was stored
+    New,
+    /// The body matches the golden copy
+    Unchanged,
+    /// The body differs, with a short summary of the difference
+    Changed(String),
+}
+
+/// A directory of golden bodies, one file per URL
+pub struct GoldenStore {
+    dir: PathBuf,
+}
+
+impl GoldenStore {
+    /// Opens (and creates if needed) a golden directory
+    pub fn new(dir: &str) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create golden directory: {}", dir))?;
+        Ok(GoldenStore { dir: PathBuf::from(dir) })
+    }
+
+    /// Compares a body against the stored copy, storing it when none exists
+    pub fn compare(&self, url: &str, body: &str) -> Result<GoldenStatus> {
+        let path = self.path_for(url);
+        if !path.exists() {
+            fs::write(&path, body)
+                .with_context(|| format!("Failed to write golden file: {}", path.display()))?;
+            return Ok(GoldenStatus::New);
+        }
+
+        let golden = fs::read(&path)
+            .with_context(|| format!("Failed to read golden file: {}", path.display()))?;
+        let golden = String::from_utf8_lossy(&golden);
+
+        if golden == body {
+            Ok(GoldenStatus::Unchanged)
+        } else {
+            Ok(GoldenStatus::Changed(diff_summary(&golden, body)))
+        }
+    }
+
+    /// Golden files are keyed by a hash of the URL so any URL maps to a valid file name
+    fn path_for(&self, url: &str) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(url.as_bytes());
+        self.dir.join(format!("{:x}.golden", hasher.finalize()))
+    }
+}
+
+/// Summarizes a line-based difference, e.g. "+3 -1 lines, 120 -> 140 bytes"
+fn diff_summary(old: &str, new: &str) -> String {
+    let mut counts: HashMap<&str, isize> = HashMap::new();
+    for line in old.lines() {
+        *counts.entry(line).or_insert(0) -= 1;
+    }
+    for line in new.lines() {
+        *counts.entry(line).or_insert(0) += 1;
+    }
+
+    let added: isize = counts.values().filter(|c| **c > 0).sum();
+    let removed: isize = counts.values().filter(|c| **c < 0).map(|c| -c).sum();
+
+    format!("+{} -{} lines, {} -> {} bytes", added, removed, old.len(), new.len())
+}
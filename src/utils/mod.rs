@@ -1,8 +1,11 @@
+pub mod body_store;
+pub mod checkpoint;
 pub mod config;
+pub mod fingerprint;
+pub mod golden;
+pub mod report;
 
 #[allow(dead_code)]
 pub mod session;
-#[allow(dead_code)]
 pub mod smart_404;
-#[allow(dead_code)]
-pub mod report;
+pub mod template;
@@ -1,10 +1,58 @@
+use crate::cli::CommonArgs;
 use crate::core::http_client::ScanResult;
-use anyhow::Result;
+use crate::core::StatusFilter;
+use anyhow::{Context, Result};
 use chrono::Utc;
+use std::collections::BTreeMap;
 use std::fs;
+use std::time::Duration;
 
-/// Generates HTML reports from scan results
-#[allow(dead_code)]
+/// Response time statistics over a set of results, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingStats {
+    pub min_ms: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+    /// Nearest-rank 95th percentile
+    pub p95_ms: u64,
+}
+
+impl TimingStats {
+    /// Statistics for the given durations, or `None` when there are none
+    pub fn from_durations(durations: impl IntoIterator<Item = u64>) -> Option<Self> {
+        let mut durations: Vec<u64> = durations.into_iter().collect();
+        if durations.is_empty() {
+            return None;
+        }
+        durations.sort_unstable();
+
+        let count = durations.len() as u64;
+        let sum: u64 = durations.iter().sum();
+        let p95_rank = (durations.len() * 95).div_ceil(100);
+        Some(TimingStats {
+            min_ms: durations[0],
+            avg_ms: (sum + count / 2) / count,
+            max_ms: durations[durations.len() - 1],
+            p95_ms: durations[p95_rank - 1],
+        })
+    }
+
+    pub fn from_results(results: &[ScanResult]) -> Option<Self> {
+        Self::from_durations(results.iter().map(|result| result.duration_ms))
+    }
+}
+
+impl std::fmt::Display for TimingStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "min {}ms, avg {}ms, max {}ms, p95 {}ms",
+            self.min_ms, self.avg_ms, self.max_ms, self.p95_ms
+        )
+    }
+}
+
+/// Generates HTML and Markdown reports from scan results
 pub struct ReportGenerator {
     results: Vec<ScanResult>,
     target: String,
@@ -13,7 +61,6 @@ pub struct ReportGenerator {
 
 impl ReportGenerator {
     /// Creates a new report generator for a target
-
     pub fn new(target: String) -> Self {
         ReportGenerator {
             results: Vec::new(),
@@ -23,19 +70,16 @@ impl ReportGenerator {
     }
 
     /// Adds a scan result to the report
-
     pub fn add_result(&mut self, result: ScanResult) {
         self.results.push(result);
     }
 
     /// Sets the total scan duration in seconds
-
     pub fn set_duration(&mut self, duration: u64) {
         self.scan_duration = duration;
     }
 
     /// Generates and saves the HTML report to a file
-
     pub fn generate_html(&self, output_path: &str) -> Result<()> {
         let html = self.build_html();
         fs::write(output_path, html)?;
@@ -43,41 +87,173 @@ impl ReportGenerator {
         Ok(())
     }
 
-    /// Builds the HTML content for the report
+    /// Generates and saves the Markdown report to a file
+    pub fn generate_markdown(&self, output_path: &str) -> Result<()> {
+        let markdown = self.build_markdown();
+        fs::write(output_path, markdown)?;
+        println!("[+] Markdown report generated: {}", output_path);
+        Ok(())
+    }
 
-    fn build_html(&self) -> String {
-        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
-        
-        let mut status_counts = std::collections::HashMap::new();
+    /// Writes the report as Markdown when asked for `md` or when the path ends in `.md`
+    pub fn generate(&self, output_path: &str, format: Option<&str>) -> Result<()> {
+        let markdown = match format {
+            Some(format) => format == "md",
+            None => output_path.to_lowercase().ends_with(".md"),
+        };
+
+        if markdown {
+            self.generate_markdown(output_path)
+        } else {
+            self.generate_html(output_path)
+        }
+    }
+
+    /// Number of results per status code
+    fn status_counts(&self) -> BTreeMap<u16, usize> {
+        let mut status_counts = BTreeMap::new();
         for result in &self.results {
             *status_counts.entry(result.status_code).or_insert(0) += 1;
         }
+        status_counts
+    }
+
+    /// Number of results per media type, without parameters such as the charset
+    fn content_type_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for result in &self.results {
+            let content_type = result
+                .content_type
+                .as_deref()
+                .and_then(|value| value.split(';').next())
+                .map(|value| value.trim().to_lowercase())
+                .filter(|value| !value.is_empty())
+                .unwrap_or_else(|| "unknown".to_string());
+            *counts.entry(content_type).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Builds the Markdown content for the report
+    fn build_markdown(&self) -> String {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+        let mut md = String::new();
+
+        md.push_str("# Rustbuster Scan Report\n\n");
+        md.push_str("## Summary\n\n");
+        md.push_str(&format!("- **Target:** {}\n", markdown_escape(&self.target)));
+        md.push_str(&format!("- **Total Findings:** {}\n", self.results.len()));
+        md.push_str(&format!("- **Scan Duration:** {}s\n", self.scan_duration));
+        md.push_str(&format!("- **Scan Time:** {}\n", timestamp));
+        if let Some(timing) = TimingStats::from_results(&self.results) {
+            md.push_str(&format!("- **Response Times:** {}\n", timing));
+        }
+        md.push('\n');
+
+        md.push_str("| Status | Count |\n");
+        md.push_str("|--------|-------|\n");
+        for (status, count) in self.status_counts() {
+            md.push_str(&format!("| {} | {} |\n", status, count));
+        }
+
+        let technologies = self.has_technologies();
+        md.push_str("\n## Discovered Resources\n\n");
+        if technologies {
+            md.push_str("| URL | Status | Size | Redirect | Technologies |\n");
+            md.push_str("|-----|--------|------|----------|--------------|\n");
+        } else {
+            md.push_str("| URL | Status | Size | Redirect |\n");
+            md.push_str("|-----|--------|------|----------|\n");
+        }
+        for result in &self.results {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |",
+                markdown_escape(&result.url),
+                result.status_code,
+                result.content_length,
+                markdown_escape(result.redirect_location.as_deref().unwrap_or("-"))
+            ));
+            if technologies {
+                md.push_str(&format!(" {} |", markdown_escape(&technology_list(result))));
+            }
+            md.push('\n');
+        }
+
+        md
+    }
+
+    /// Whether any result was tagged by `--fingerprint`, which adds a Technologies column
+    fn has_technologies(&self) -> bool {
+        self.results.iter().any(|result| !result.technologies.is_empty())
+    }
+
+    /// Builds the HTML content for the report
+    fn build_html(&self) -> String {
+        let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC");
+        let technologies = self.has_technologies();
 
         let mut results_html = String::new();
         for result in &self.results {
-            let status_class = match result.status_code {
-                200..=299 => "success",
-                300..=399 => "redirect",
-                400..=499 => "client-error",
-                500..=599 => "server-error",
-                _ => "other",
-            };
+            let status_class = status_class(result.status_code);
 
+            let technology_cell = if technologies {
+                format!("\n                    <td>{}</td>", html_escape(&technology_list(result)))
+            } else {
+                String::new()
+            };
             results_html.push_str(&format!(
                 r#"<tr class="{}">
                     <td>{}</td>
                     <td>{}</td>
                     <td>{}</td>
-                    <td>{}</td>
+                    <td>{}</td>{}
                 </tr>"#,
                 status_class,
                 html_escape(&result.url),
                 result.status_code,
                 result.content_length,
-                result.redirect_location.as_deref().unwrap_or("-")
+                result.redirect_location.as_deref().unwrap_or("-"),
+                technology_cell
             ));
         }
 
+        let status_rows: Vec<(String, &str, usize)> = self
+            .status_counts()
+            .into_iter()
+            .map(|(status, count)| (status.to_string(), status_class(status), count))
+            .collect();
+        let content_type_rows: Vec<(String, &str, usize)> = self
+            .content_type_counts()
+            .into_iter()
+            .map(|(content_type, count)| (content_type, "other", count))
+            .collect();
+        let timing_html = match TimingStats::from_results(&self.results) {
+            Some(timing) => [
+                ("Min Response", timing.min_ms),
+                ("Avg Response", timing.avg_ms),
+                ("Max Response", timing.max_ms),
+                ("P95 Response", timing.p95_ms),
+            ]
+                .iter()
+                .map(|(label, ms)| {
+                    format!(
+                        r#"<div class="stat-card">
+                <div class="stat-label">{}</div>
+                <div class="stat-value">{}ms</div>
+            </div>"#,
+                        label, ms
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n            "),
+            None => String::new(),
+        };
+        let breakdown_html = format!(
+            "{}\n            {}",
+            breakdown_table("Status Codes", &status_rows, self.results.len()),
+            breakdown_table("Content Types", &content_type_rows, self.results.len())
+        );
+
         format!(
             r#"<!DOCTYPE html>
 <html lang="en">
@@ -105,6 +281,15 @@ impl ReportGenerator {
         .redirect {{ background: rgba(255, 193, 7, 0.1); }}
         .client-error {{ background: rgba(244, 67, 54, 0.1); }}
         .server-error {{ background: rgba(156, 39, 176, 0.1); }}
+        .breakdown {{ display: grid; grid-template-columns: repeat(auto-fit, minmax(400px, 1fr)); gap: 20px; margin-bottom: 30px; }}
+        .breakdown-card {{ background: #1a1f3a; padding: 20px; border-radius: 8px; }}
+        .breakdown-card td {{ padding: 6px 10px; }}
+        .bar-cell {{ width: 60%; }}
+        .bar {{ height: 14px; border-radius: 3px; background: #667eea; }}
+        .bar.success {{ background: #4caf50; }}
+        .bar.redirect {{ background: #ffc107; }}
+        .bar.client-error {{ background: #f44336; }}
+        .bar.server-error {{ background: #9c27b0; }}
         .footer {{ text-align: center; margin-top: 30px; color: #666; }}
     </style>
 </head>
@@ -132,6 +317,11 @@ impl ReportGenerator {
                 <div class="stat-label">Scan Time</div>
                 <div class="stat-value" style="font-size: 1em;">{}</div>
             </div>
+            {}
+        </div>
+
+        <div class="breakdown">
+            {}
         </div>
 
         <div class="results-section">
@@ -142,7 +332,7 @@ impl ReportGenerator {
                         <th>URL</th>
                         <th>Status</th>
                         <th>Size</th>
-                        <th>Redirect</th>
+                        <th>Redirect</th>{}
                     </tr>
                 </thead>
                 <tbody>
@@ -161,14 +351,65 @@ impl ReportGenerator {
             self.results.len(),
             self.scan_duration,
             timestamp,
+            timing_html,
+            breakdown_html,
+            if technologies { "\n                        <th>Technologies</th>" } else { "" },
             results_html,
             timestamp
         )
     }
 }
 
+/// Row class for a status, which also colours its bar in the breakdown
+fn status_class(status_code: u16) -> &'static str {
+    match status_code {
+        200..=299 => "success",
+        300..=399 => "redirect",
+        400..=499 => "client-error",
+        500..=599 => "server-error",
+        _ => "other",
+    }
+}
+
+/// A summary card with one row per (label, bar class, count) and a bar sized by its share
+fn breakdown_table(title: &str, rows: &[(String, &str, usize)], total: usize) -> String {
+    let mut html = format!(
+        r#"<div class="breakdown-card">
+                <h2 style="margin-bottom: 15px; color: #667eea;">{}</h2>
+                <table>"#,
+        title
+    );
+    for (label, class, count) in rows {
+        let percent = *count as f64 * 100.0 / total.max(1) as f64;
+        html.push_str(&format!(
+            r#"
+                    <tr>
+                        <td>{}</td>
+                        <td>{}</td>
+                        <td class="bar-cell"><div class="bar {}" style="width: {:.1}%"></div></td>
+                        <td>{:.1}%</td>
+                    </tr>"#,
+            html_escape(label),
+            count,
+            class,
+            percent,
+            percent
+        ));
+    }
+    html.push_str("\n                </table>\n            </div>");
+    html
+}
+
+/// Technologies of a result for a report cell, or `-` when none were detected
+fn technology_list(result: &ScanResult) -> String {
+    if result.technologies.is_empty() {
+        "-".to_string()
+    } else {
+        result.technologies.join(", ")
+    }
+}
+
 /// Escapes HTML special characters
-#[allow(dead_code)]
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -176,3 +417,69 @@ fn html_escape(s: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&#39;")
 }
+
+/// Escapes characters that would break a Markdown table cell
+fn markdown_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+/// Writes the `--report` file for a finished plain scan, keeping only the findings
+pub fn write_report(
+    common: &CommonArgs,
+    target: &str,
+    results: &[ScanResult],
+    duration: Duration,
+) -> Result<()> {
+    let Some(path) = &common.report else {
+        return Ok(());
+    };
+
+    let status_filter = StatusFilter::from_common(common);
+    let mut report = ReportGenerator::new(target.to_string());
+    for result in results.iter().filter(|r| status_filter.matches_url(&r.url, r.status_code)) {
+        report.add_result(result.clone());
+    }
+    report.set_duration(duration.as_secs());
+
+    report
+        .generate(path, common.report_format.as_deref())
+        .with_context(|| format!("Failed to write report: {}", path))
+}
+
+/// Builds the one-line summary printed by `--summary-line`
+///
+/// For example `example.com: 1234 scanned, 7 found [200:3 301:2 403:2] in 00:42`.
+pub fn summary_line(common: &CommonArgs, target: &str, results: &[ScanResult], elapsed: Duration) -> String {
+    let status_filter = StatusFilter::from_common(common);
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for result in results.iter().filter(|r| status_filter.matches_url(&r.url, r.status_code)) {
+        *counts.entry(status_filter.group(result.status_code)).or_insert(0) += 1;
+    }
+
+    let host = url::Url::parse(target)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| target.to_string());
+    let breakdown: Vec<String> = counts
+        .iter()
+        .map(|(status, count)| format!("{}:{}", status, count))
+        .collect();
+
+    let secs = elapsed.as_secs();
+    let duration = if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    };
+
+    format!(
+        "{}: {} scanned, {} found [{}] in {}",
+        host,
+        results.len(),
+        counts.values().sum::<usize>(),
+        breakdown.join(" "),
+        duration
+    )
+}
@@ -1,7 +1,8 @@
+use crate::cli::CommonArgs;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 
 /// Represents a scan session that can be saved and resumed
@@ -16,6 +17,9 @@ pub struct Session {
     pub completed_words: Vec<String>,
     pub total_words: usize,
     pub found_results: Vec<SessionResult>,
+    /// Directory the session is saved in; the default one when not set
+    #[serde(skip)]
+    dir: Option<PathBuf>,
 }
 
 /// A result found during a scan session
@@ -41,13 +45,46 @@ impl Session {
             completed_words: Vec::new(),
             total_words,
             found_results: Vec::new(),
+            dir: None,
         }
     }
 
+    /// Saves the session in `dir` instead of the default session directory
+    pub fn with_dir(mut self, dir: PathBuf) -> Self {
+        self.dir = Some(dir);
+        self
+    }
+
+    /// Opens the session for a scan: `--resume-session` loads a saved one, `--save-session` starts
+    /// a new one. Resumed progress is saved under the `--save-session` name when both are given.
+    pub fn from_args(common: &CommonArgs, target: &str) -> Result<Option<Self>> {
+        let dir = Self::dir_from(common.session_dir.as_deref())?;
+        if let Some(name) = &common.resume_session {
+            let mut session = Self::load(&dir, name)?;
+            if let Some(save_name) = &common.save_session {
+                session.name = save_name.clone();
+            }
+            return Ok(Some(session));
+        }
+
+        Ok(common.save_session.as_ref().map(|name| {
+            Session::new(
+                name.clone(),
+                target.to_string(),
+                common.wordlist_label(),
+                0,
+            )
+            .with_dir(dir)
+        }))
+    }
+
     /// Saves the session to disk
     pub fn save(&mut self) -> Result<()> {
         self.last_updated = Utc::now();
-        let session_dir = Self::get_session_dir()?;
+        let session_dir = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => Self::default_dir()?,
+        };
         fs::create_dir_all(&session_dir)?;
         
         let session_file = session_dir.join(format!("{}.json", self.name));
@@ -57,16 +94,15 @@ impl Session {
         Ok(())
     }
 
-    /// Loads a session from disk by name
-    pub fn load(name: &str) -> Result<Self> {
-        let session_dir = Self::get_session_dir()?;
-        let session_file = session_dir.join(format!("{}.json", name));
+    /// Loads a session by name from a session directory
+    pub fn load(dir: &Path, name: &str) -> Result<Self> {
+        let session_file = dir.join(format!("{}.json", name));
         
         let json = fs::read_to_string(&session_file)
             .context(format!("Failed to load session: {}", name))?;
         let session: Session = serde_json::from_str(&json)?;
         
-        Ok(session)
+        Ok(session.with_dir(dir.to_path_buf()))
     }
 
     /// Marks a word as completed in the session
@@ -79,6 +115,11 @@ impl Session {
         self.found_results.push(result);
     }
 
+    /// Checks if a result for this URL was already recorded
+    pub fn has_result(&self, url: &str) -> bool {
+        self.found_results.iter().any(|result| result.url == url)
+    }
+
     /// Checks if a word has already been scanned
     pub fn is_word_completed(&self, word: &str) -> bool {
         self.completed_words.contains(&word.to_string())
@@ -92,15 +133,28 @@ impl Session {
         (self.completed_words.len() as f32 / self.total_words as f32) * 100.0
     }
 
-    /// Gets the directory where sessions are stored
-    fn get_session_dir() -> Result<PathBuf> {
+    /// The directory sessions are stored in by default, under the home directory
+    pub fn default_dir() -> Result<PathBuf> {
         let home = dirs::home_dir().context("Could not find home directory")?;
         Ok(home.join(".rustbuster").join("sessions"))
     }
 
-    /// Lists all saved sessions
-    pub fn list_sessions() -> Result<Vec<String>> {
-        let session_dir = Self::get_session_dir()?;
+    /// The `--session-dir` directory when one is given, otherwise the default one
+    pub fn dir_from(session_dir: Option<&str>) -> Result<PathBuf> {
+        match session_dir {
+            Some(dir) => Ok(PathBuf::from(dir)),
+            None => Self::default_dir(),
+        }
+    }
+
+    /// Removes a saved session from a session directory
+    pub fn delete(dir: &Path, name: &str) -> Result<()> {
+        let session_file = dir.join(format!("{}.json", name));
+        fs::remove_file(&session_file).context(format!("Failed to delete session: {}", name))
+    }
+
+    /// Lists the sessions saved in a session directory by name, in alphabetical order
+    pub fn list_sessions(session_dir: &Path) -> Result<Vec<String>> {
         if !session_dir.exists() {
             return Ok(Vec::new());
         }
@@ -114,6 +168,7 @@ impl Session {
                 }
             }
         }
+        sessions.sort();
         Ok(sessions)
     }
 }
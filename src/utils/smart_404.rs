@@ -8,6 +8,8 @@ use std::collections::HashSet;
 pub struct Smart404Detector {
     baseline_hashes: HashSet<String>,
     baseline_sizes: HashSet<u64>,
+    /// Word sets of the baseline bodies, for `similarity`
+    baseline_tokens: Vec<HashSet<String>>,
     enabled: bool,
 }
 
@@ -18,6 +20,7 @@ impl Smart404Detector {
         Smart404Detector {
             baseline_hashes: HashSet::new(),
             baseline_sizes: HashSet::new(),
+            baseline_tokens: Vec::new(),
             enabled,
         }
     }
@@ -39,12 +42,12 @@ impl Smart404Detector {
         ];
 
         for path in test_paths {
-            if let Ok(response) = client.request(&path, "GET", &[], None).await {
-                if let Ok(body) = response.text().await {
-                    let hash = self.hash_content(&body);
-                    self.baseline_hashes.insert(hash);
-                    self.baseline_sizes.insert(body.len() as u64);
-                }
+            if let Ok(response) = client.request(&path, "GET", &[], None, None).await {
+                let body = client.read_body(response).await.text();
+                let hash = self.hash_content(&body);
+                self.baseline_hashes.insert(hash);
+                self.baseline_sizes.insert(body.len() as u64);
+                self.baseline_tokens.push(tokenize(&body));
             }
         }
 
@@ -65,6 +68,20 @@ impl Smart404Detector {
         self.baseline_hashes.contains(&hash) || self.baseline_sizes.contains(&size)
     }
 
+    /// Highest similarity between a body and any baseline body, from 0.0 to 1.0
+    pub fn similarity(&self, body: &str) -> f32 {
+        let tokens = tokenize(body);
+        self.baseline_tokens
+            .iter()
+            .map(|baseline| jaccard(&tokens, baseline))
+            .fold(0.0, f32::max)
+    }
+
+    /// Whether calibration captured any baseline body
+    pub fn is_calibrated(&self) -> bool {
+        !self.baseline_tokens.is_empty()
+    }
+
     /// Hashes response content for comparison
     fn hash_content(&self, content: &str) -> String {
         let mut hasher = Sha256::new();
@@ -72,3 +89,23 @@ impl Smart404Detector {
         format!("{:x}", hasher.finalize())
     }
 }
+
+/// Token Jaccard similarity of two bodies, from 0.0 (disjoint) to 1.0 (same words)
+///
+/// Linear in the body size, so it stays cheap across large wordlists.
+#[allow(dead_code)]
+pub fn token_similarity(a: &str, b: &str) -> f32 {
+    jaccard(&tokenize(a), &tokenize(b))
+}
+
+fn tokenize(body: &str) -> HashSet<String> {
+    body.split_whitespace().map(str::to_string).collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
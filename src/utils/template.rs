@@ -0,0 +1,23 @@
+//! Placeholder substitution shared by fuzz keywords and `--on-hit` commands.
+
+/// Replaces every bound keyword in a template in one pass from left to right
+///
+/// A substituted word is never scanned again, so a FUZZ2 word holding the text FUZZ stays
+/// as it is. Where several keywords match, the first binding wins, so longer keywords go first.
+pub fn substitute(template: &str, bindings: &[(&str, &str)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        match bindings.iter().find(|(keyword, _)| rest.starts_with(keyword)) {
+            Some((keyword, word)) => {
+                text.push_str(word);
+                rest = &rest[keyword.len()..];
+            }
+            None => {
+                text.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    text
+}
@@ -0,0 +1,218 @@
+//! Minimal HTTP/1.1 mock server shared by the integration tests
+
+#![allow(dead_code)]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// A request captured by the mock server
+#[derive(Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    /// Returns the first header value matching `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// The response the mock server should send back
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: body.as_bytes().to_vec(),
+            delay: None,
+        }
+    }
+
+    pub fn bytes(status: u16, body: Vec<u8>) -> Self {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body,
+            delay: None,
+        }
+    }
+
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync;
+
+/// Serves every connection on its own thread and records each request
+pub struct MockServer {
+    pub addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let recorded = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let recorded = Arc::clone(&recorded);
+                let handler = Arc::clone(&handler);
+                thread::spawn(move || {
+                    let _ = handle_connection(stream, &recorded, handler.as_ref());
+                });
+            }
+        });
+
+        MockServer { addr, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn paths(&self) -> Vec<String> {
+        self.requests().into_iter().map(|r| r.path).collect()
+    }
+}
+
+fn handle_connection(
+    stream: TcpStream,
+    recorded: &Mutex<Vec<RecordedRequest>>,
+    handler: &Handler,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("").to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let content_length = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+
+    let request = RecordedRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    };
+    recorded.lock().unwrap().push(request.clone());
+
+    let response = handler(&request);
+    if let Some(delay) = response.delay {
+        thread::sleep(delay);
+    }
+
+    let mut stream = stream;
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    let has_length = response
+        .headers
+        .iter()
+        .any(|(key, _)| key.eq_ignore_ascii_case("content-length"));
+    if !has_length {
+        head.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+    }
+    for (key, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    head.push_str("Connection: close\r\n\r\n");
+
+    stream.write_all(head.as_bytes())?;
+    if request.method != "HEAD" {
+        stream.write_all(&response.body)?;
+    }
+    stream.flush()
+}
+
+/// Writes one word per line to a temporary wordlist
+pub fn wordlist_file(words: &[&str]) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    for word in words {
+        writeln!(file, "{}", word).unwrap();
+    }
+    file
+}
+
+/// Parses quiet, non-TUI common flags plus `extra`
+pub fn common_args(extra: &[&str]) -> rustbuster::cli::CommonArgs {
+    use clap::Parser;
+    let mut args = vec!["rustbuster", "-q", "--no-tui"];
+    args.extend_from_slice(extra);
+    rustbuster::cli::CommonArgs::try_parse_from(args).unwrap()
+}
+
+/// Parses a quiet, non-TUI `dir` scan of `url` with `wordlist`, plus `extra`
+pub fn dir_args(
+    url: &str,
+    wordlist: &tempfile::NamedTempFile,
+    extra: &[&str],
+) -> rustbuster::cli::DirArgs {
+    use clap::Parser;
+    let mut args = vec![
+        "dir",
+        "-u",
+        url,
+        "-w",
+        wordlist.path().to_str().unwrap(),
+        "--no-tui",
+        "-q",
+    ];
+    args.extend_from_slice(extra);
+    rustbuster::cli::DirArgs::try_parse_from(args).unwrap()
+}
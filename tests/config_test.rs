@@ -0,0 +1,150 @@
+//! Tests for applying config file defaults
+
+use clap::{CommandFactory, FromArgMatches, Parser};
+use rustbuster::cli::{Cli, DirArgs};
+use rustbuster::utils::config::{explicit_config_path, load_config, Config};
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Parses dir arguments and applies `config` the way `main` does
+fn dir_args_with_config(config: &Config, args: &[&str]) -> DirArgs {
+    let mut argv = vec!["dir", "-u", "http://example.com"];
+    argv.extend_from_slice(args);
+    let matches = DirArgs::command().try_get_matches_from(argv).unwrap();
+    let mut args = DirArgs::from_arg_matches(&matches).unwrap();
+    let config = config.for_mode("dir");
+    config.apply(&mut args.common, &matches).unwrap();
+    config.apply_extensions(&mut args.extensions, &matches);
+    args
+}
+
+// the config fills in -t when it is left out
+#[test]
+fn test_config_threads_used_when_flag_omitted() {
+    let config: Config = toml::from_str("default_threads = 42").unwrap();
+    let args = dir_args_with_config(&config, &[]);
+    assert_eq!(args.common.threads, 42);
+}
+
+// an explicit -t wins, even when it matches the built-in default
+#[test]
+fn test_config_threads_ignored_when_flag_given() {
+    let config: Config = toml::from_str("default_threads = 42").unwrap();
+    assert_eq!(dir_args_with_config(&config, &["-t", "5"]).common.threads, 5);
+    assert_eq!(dir_args_with_config(&config, &["-t", "10"]).common.threads, 10);
+}
+
+// a mode table overrides the top-level keys for that mode only
+#[test]
+fn test_config_mode_section_overrides_top_level() {
+    let config: Config = toml::from_str(
+        r#"
+        default_threads = 20
+        default_timeout = 30
+        proxy = "http://127.0.0.1:8080"
+
+        [dir]
+        default_threads = 50
+        default_wordlist = "/usr/share/wordlists/dirb/common.txt"
+        "#,
+    )
+    .unwrap();
+
+    let args = dir_args_with_config(&config, &[]);
+    assert_eq!(args.common.threads, 50);
+    assert_eq!(args.common.timeout, 30);
+    assert_eq!(args.common.wordlist, vec!["/usr/share/wordlists/dirb/common.txt"]);
+    assert_eq!(args.common.proxy.as_deref(), Some("http://127.0.0.1:8080"));
+
+    let dns = config.for_mode("dns");
+    assert_eq!(dns.default_threads, Some(20));
+    assert_eq!(dns.default_wordlist, None);
+}
+
+const TEAM_CONFIG: &str = r#"
+default_status_codes = [200, 301, 403]
+default_headers = ["X-Bug-Bounty: team-red", "Accept: application/json"]
+default_output_format = "jsonl"
+default_extensions = ["php", "bak"]
+"#;
+
+// statuses, headers, output format and extensions come from the config when left out
+#[test]
+fn test_config_scan_defaults_merged() {
+    let config: Config = toml::from_str(TEAM_CONFIG).unwrap();
+    let args = dir_args_with_config(&config, &[]);
+
+    assert_eq!(args.common.status_codes, "200,301,403");
+    assert_eq!(args.common.get_status_codes(), vec![200, 301, 403]);
+    assert_eq!(args.common.headers, vec!["X-Bug-Bounty: team-red", "Accept: application/json"]);
+    assert_eq!(args.common.output_format, "jsonl");
+    assert_eq!(args.extensions.as_deref(), Some("php,bak"));
+}
+
+// each of those flags still wins over the config
+#[test]
+fn test_config_scan_defaults_overridden_by_flags() {
+    let config: Config = toml::from_str(TEAM_CONFIG).unwrap();
+    let args = dir_args_with_config(
+        &config,
+        &["-s", "200", "-H", "X-Test: 1", "--output-format", "csv", "-x", "txt"],
+    );
+
+    assert_eq!(args.common.status_codes, "200");
+    assert_eq!(args.common.headers, vec!["X-Test: 1"]);
+    assert_eq!(args.common.output_format, "csv");
+    assert_eq!(args.extensions.as_deref(), Some("txt"));
+}
+
+// an output format clap would reject is rejected from the config too
+#[test]
+fn test_config_rejects_unknown_output_format() {
+    let config: Config = toml::from_str(r#"default_output_format = "xml""#).unwrap();
+    let matches = DirArgs::command()
+        .try_get_matches_from(["dir", "-u", "http://example.com"])
+        .unwrap();
+    let mut args = DirArgs::from_arg_matches(&matches).unwrap();
+    assert!(config.apply(&mut args.common, &matches).is_err());
+}
+
+// --config loads the named file, with mode tables intact
+#[test]
+fn test_config_loaded_from_custom_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("project.toml");
+    std::fs::write(&path, "default_threads = 7\n[dir]\ndefault_extensions = [\"asp\"]\n").unwrap();
+
+    let config = load_config(Some(&path)).unwrap().expect("config should load");
+    let args = dir_args_with_config(&config, &[]);
+    assert_eq!(args.common.threads, 7);
+    assert_eq!(args.extensions.as_deref(), Some("asp"));
+}
+
+// a config file that was asked for by name has to exist and parse
+#[test]
+fn test_config_custom_path_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    assert!(load_config(Some(&dir.path().join("missing.toml"))).is_err());
+
+    let broken = dir.path().join("broken.toml");
+    std::fs::write(&broken, "default_threads = \"many\"").unwrap();
+    assert!(load_config(Some(&broken)).is_err());
+}
+
+// --config beats RUSTBUSTER_CONFIG, which is only used when set to something
+#[test]
+fn test_config_path_precedence() {
+    let flag = Path::new("flag.toml");
+    let env = || Some(OsString::from("env.toml"));
+    assert_eq!(explicit_config_path(Some(flag), env()).unwrap(), flag);
+    assert_eq!(explicit_config_path(None, env()).unwrap(), Path::new("env.toml"));
+    assert_eq!(explicit_config_path(None, Some(OsString::new())), None);
+    assert_eq!(explicit_config_path(None, None), None);
+}
+
+// --config is global, so it may follow the mode name
+#[test]
+fn test_config_flag_after_mode() {
+    let cli = Cli::try_parse_from(["rustbuster", "dir", "-u", "http://example.com", "--config", "p.toml"]).unwrap();
+    assert_eq!(cli.config.as_deref(), Some(Path::new("p.toml")));
+}
@@ -0,0 +1,117 @@
+//! Tests for comparing two scans with `rustbuster diff`
+
+use rustbuster::modes::diff::{self, Change};
+use serde_json::json;
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+fn record(url: &str, status: u16, size: u64, duration_ms: u64) -> serde_json::Value {
+    json!({
+        "url": url,
+        "status_code": status,
+        "content_length": size,
+        "redirect_location": null,
+        "content_type": "text/html",
+        "server": null,
+        "duration_ms": duration_ms,
+        "source": null,
+        "golden_diff": null,
+        "allowed_methods": null,
+        "auth_scheme": null,
+        "final_url": null,
+        "redirect_count": 0,
+        "headers": {},
+        "technologies": []
+    })
+}
+
+fn results_file(content: &str) -> NamedTempFile {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "{}", content).unwrap();
+    file
+}
+
+fn classify(changes: &[Change]) -> Vec<(&'static str, &str)> {
+    changes
+        .iter()
+        .map(|change| match change {
+            Change::Added(_) => ("added", change.url()),
+            Change::Removed(_) => ("removed", change.url()),
+            Change::Changed { .. } => ("changed", change.url()),
+        })
+        .collect()
+}
+
+// URLs are sorted into added, removed and changed; a new timing alone is not a change
+#[test]
+fn test_diff_classifies_urls() {
+    let old = results_file(
+        &json!([
+            record("http://t/admin", 403, 10, 5),
+            record("http://t/index", 200, 100, 5),
+            record("http://t/login", 200, 50, 5),
+            record("http://t/old", 200, 20, 5),
+        ])
+        .to_string(),
+    );
+    let new = results_file(
+        &json!([
+            record("http://t/admin", 200, 10, 5),
+            record("http://t/backup", 200, 70, 5),
+            record("http://t/index", 200, 100, 90),
+            record("http://t/login", 200, 64, 5),
+        ])
+        .to_string(),
+    );
+
+    let old = diff::load_results(old.path().to_str().unwrap()).unwrap();
+    let new = diff::load_results(new.path().to_str().unwrap()).unwrap();
+    let changes = diff::diff(&old, &new);
+
+    assert_eq!(
+        classify(&changes),
+        vec![
+            ("changed", "http://t/admin"),
+            ("added", "http://t/backup"),
+            ("changed", "http://t/login"),
+            ("removed", "http://t/old"),
+        ]
+    );
+    match &changes[0] {
+        Change::Changed { old, new } => assert_eq!((old.status_code, new.status_code), (403, 200)),
+        other => panic!("expected a change, got {:?}", other),
+    }
+}
+
+// Files written with --json-metadata and as jsonl load the same results as a plain array
+#[test]
+fn test_diff_loads_metadata_and_jsonl() {
+    let with_metadata = results_file(
+        &json!({
+            "metadata": {"target": "http://t"},
+            "results": [record("http://t/a", 200, 1, 1), record("http://t/b", 301, 0, 1)]
+        })
+        .to_string(),
+    );
+    let jsonl = results_file(&format!(
+        "{}\n{}\n",
+        record("http://t/a", 200, 1, 1),
+        record("http://t/c", 200, 2, 1)
+    ));
+
+    let old = diff::load_results(with_metadata.path().to_str().unwrap()).unwrap();
+    let new = diff::load_results(jsonl.path().to_str().unwrap()).unwrap();
+    assert_eq!(old.len(), 2);
+    assert_eq!(new.len(), 2);
+
+    let changes = diff::diff(&old, &new);
+    assert_eq!(classify(&changes), vec![("removed", "http://t/b"), ("added", "http://t/c")]);
+}
+
+// A file that is not json output is reported rather than read as an empty scan
+#[test]
+fn test_diff_rejects_other_files() {
+    let file = results_file("not a result\n");
+    let err = diff::load_results(file.path().to_str().unwrap()).unwrap_err();
+    assert!(format!("{:#}", err).contains("Failed to parse results"));
+}
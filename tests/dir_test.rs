@@ -0,0 +1,238 @@
+//! Tests for dir mode URL generation and recursion
+
+mod common;
+
+use clap::Parser;
+use common::{dir_args, wordlist_file, MockResponse, MockServer};
+use rustbuster::cli::DirArgs;
+use rustbuster::modes::dir;
+
+// seeded directories are scanned instead of the root
+#[tokio::test]
+async fn test_seed_urls_scanned_without_root() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = wordlist_file(&["secret"]);
+    let other = format!("{}/other", server.url());
+    let seeds = wordlist_file(&["/known/", &other]);
+
+    let args = dir_args(&server.url(), &wordlist, &["--seed-urls", seeds.path().to_str().unwrap()]);
+    dir::run(args).await.unwrap();
+
+    let paths = server.paths();
+    assert!(paths.contains(&"/known/secret".to_string()));
+    assert!(paths.contains(&"/other/secret".to_string()));
+    assert!(!paths.contains(&"/secret".to_string()));
+}
+
+// a slow directory is abandoned once its budget is spent and the queue moves on
+#[tokio::test]
+async fn test_per_dir_timeout_moves_to_next_directory() {
+    let server = MockServer::start(|_| {
+        MockResponse::new(404, "slow").delay(std::time::Duration::from_millis(400))
+    });
+    let wordlist = wordlist_file(&["a", "b", "c", "d", "e", "f"]);
+    let seeds = wordlist_file(&["/one/", "/two/"]);
+
+    let args = dir_args(
+        &server.url(),
+        &wordlist,
+        &["--seed-urls", seeds.path().to_str().unwrap(), "--per-dir-timeout", "1", "-t", "1"],
+    );
+
+    let start = std::time::Instant::now();
+    dir::run(args).await.unwrap();
+    let elapsed = start.elapsed();
+
+    let paths = server.paths();
+    assert!(paths.iter().any(|p| p.starts_with("/one/")));
+    assert!(paths.iter().any(|p| p.starts_with("/two/")));
+    assert!(elapsed < std::time::Duration::from_millis(4500), "took {:?}", elapsed);
+}
+
+fn index_server() -> MockServer {
+    MockServer::start(|req| {
+        if req.path == "/files" {
+            MockResponse::new(200, "<html><head><title>Index of /files</title></head></html>")
+        } else {
+            MockResponse::new(404, "not found")
+        }
+    })
+}
+
+// a 200 autoindex page without a trailing slash is recursed into when configured
+#[tokio::test]
+async fn test_recurse_on_status_follows_index_page() {
+    let server = index_server();
+    let wordlist = wordlist_file(&["files", "secret"]);
+
+    let args = dir_args(&server.url(), &wordlist, &["-R", "--recurse-on-status", "200"]);
+    dir::run(args).await.unwrap();
+
+    assert!(server.paths().contains(&"/files/secret".to_string()));
+}
+
+// without the flag the same page is treated as a plain file
+#[tokio::test]
+async fn test_index_page_not_recursed_by_default() {
+    let server = index_server();
+    let wordlist = wordlist_file(&["files", "secret"]);
+
+    let args = dir_args(&server.url(), &wordlist, &["-R"]);
+    dir::run(args).await.unwrap();
+
+    assert!(!server.paths().contains(&"/files/secret".to_string()));
+}
+
+// recursion descends into directories announced by a redirect
+#[tokio::test]
+async fn test_recursion_follows_redirected_directory() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(301, "").header("Location", "/admin/"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let wordlist = wordlist_file(&["admin", "config"]);
+
+    let args = dir_args(&server.url(), &wordlist, &["-R"]);
+    dir::run(args).await.unwrap();
+
+    assert!(server.paths().contains(&"/admin/config".to_string()));
+}
+
+// extensions from a file are normalized like -x values and merged with them
+#[tokio::test]
+async fn test_extensions_file_merged_with_inline_extensions() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = wordlist_file(&["index"]);
+    let extensions = wordlist_file(&["php", ".bak", "", "# comment", "html"]);
+
+    let args = dir_args(
+        &server.url(),
+        &wordlist,
+        &["-x", "html,.txt", "--extensions-file", extensions.path().to_str().unwrap()],
+    );
+    assert_eq!(
+        args.common.get_extensions(&args.extensions, &args.extensions_file).unwrap(),
+        vec![".html", ".txt", ".php", ".bak"]
+    );
+
+    dir::run(args).await.unwrap();
+
+    let paths = server.paths();
+    for path in ["/index", "/index.html", "/index.txt", "/index.php", "/index.bak"] {
+        assert!(paths.contains(&path.to_string()), "missing {}", path);
+    }
+}
+
+fn dead_proxy() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    format!("http://127.0.0.1:{}", port)
+}
+
+// a proxy that refuses connections aborts the scan before any word is tried
+#[tokio::test]
+async fn test_unreachable_proxy_aborts_before_scan() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = wordlist_file(&["admin", "secret"]);
+    let proxy = dead_proxy();
+
+    let args = dir_args(&server.url(), &wordlist, &["-p", &proxy]);
+    let start = std::time::Instant::now();
+    let err = dir::run(args).await.unwrap_err().to_string();
+
+    assert!(err.contains("unreachable"), "{}", err);
+    assert!(err.contains(&proxy), "{}", err);
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    assert!(server.paths().is_empty());
+}
+
+// --skip-preflight goes straight to scanning
+#[tokio::test]
+async fn test_skip_preflight_scans_despite_dead_proxy() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = wordlist_file(&["admin"]);
+    let proxy = dead_proxy();
+
+    let args = dir_args(&server.url(), &wordlist, &["-p", &proxy, "--skip-preflight"]);
+    dir::run(args).await.unwrap();
+}
+
+fn parse_url(url: &str) -> Result<String, String> {
+    DirArgs::try_parse_from(["dir", "-u", url])
+        .map(|args| args.url)
+        .map_err(|e| e.to_string())
+}
+
+// a bare host gets http:// in front of it
+#[test]
+fn test_target_without_scheme_assumes_http() {
+    assert_eq!(parse_url("example.com").unwrap(), "http://example.com");
+    assert_eq!(parse_url("localhost:8080/app").unwrap(), "http://localhost:8080/app");
+}
+
+// schemes other than http and https are refused with a pointer to the fix
+#[test]
+fn test_target_with_unsupported_scheme_rejected() {
+    let err = parse_url("ftp://x").unwrap_err();
+    assert!(err.contains("unsupported scheme 'ftp://'"), "{}", err);
+    assert!(parse_url("http://").is_err());
+}
+
+// valid targets are kept exactly as typed
+#[test]
+fn test_valid_target_kept() {
+    assert_eq!(parse_url("https://x").unwrap(), "https://x");
+    assert!(rustbuster::cli::FuzzArgs::try_parse_from(["fuzz", "-u", "https://x/FUZZ"]).is_ok());
+    assert!(rustbuster::cli::VhostArgs::try_parse_from(["vhost", "-u", "ftp://x"]).is_err());
+}
+
+fn requested_paths(extra: &[&str]) -> Vec<String> {
+    let wordlist = wordlist_file(&["admin", "static/"]);
+    let args = dir_args("http://example.com", &wordlist, extra);
+    let words = rustbuster::core::Wordlist::from_file(wordlist.path().to_str().unwrap())
+        .unwrap()
+        .expand_with_sources(&[]);
+    dir::word_paths(&args, words).into_iter().map(|(path, _)| path).collect()
+}
+
+// --add-slash requests both forms of a word, without doubling a slash that is already there
+#[test]
+fn test_add_slash_generates_both_variants() {
+    assert_eq!(requested_paths(&["--add-slash"]), vec!["/admin", "/admin/", "/static/"]);
+    assert_eq!(requested_paths(&[]), vec!["/admin", "/static/"]);
+}
+
+// --slash-only swaps in the trailing-slash form and reaches the server that way
+#[tokio::test]
+async fn test_slash_only_requests_trailing_slash_paths() {
+    assert_eq!(requested_paths(&["--slash-only"]), vec!["/admin/", "/static/"]);
+
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = wordlist_file(&["admin"]);
+    dir::run(dir_args(&server.url(), &wordlist, &["--slash-only"])).await.unwrap();
+    assert!(server.paths().contains(&"/admin/".to_string()));
+    assert!(!server.paths().contains(&"/admin".to_string()));
+}
+
+// a target that refuses connections stops the scan with the reason, unless --skip-check
+#[tokio::test]
+async fn test_unreachable_target_aborts_before_scan() {
+    let wordlist = wordlist_file(&["admin", "secret"]);
+    let target = dead_proxy();
+    let args = |extra: &[&str]| {
+        let mut args = vec!["dir", "-u", &target, "-w", wordlist.path().to_str().unwrap(), "--no-tui", "--no-progress"];
+        args.extend_from_slice(extra);
+        DirArgs::try_parse_from(args).unwrap()
+    };
+
+    let start = std::time::Instant::now();
+    let err = dir::run(args(&[])).await.unwrap_err().to_string();
+    assert!(err.contains("Cannot reach"), "{}", err);
+    assert!(err.contains("connection refused"), "{}", err);
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+    // Skipping the check falls through to the scan, whose requests all fail
+    let err = dir::run(args(&["--skip-check", "--max-errors", "1"])).await.unwrap_err().to_string();
+    assert!(!err.contains("Cannot reach"), "{}", err);
+}
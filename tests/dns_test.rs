@@ -0,0 +1,205 @@
+//! Tests for dns mode
+
+mod common;
+
+use clap::Parser;
+use common::{MockResponse, MockServer};
+use rustbuster::cli::{CommonArgs, DnsArgs};
+use rustbuster::core::doh::DohResolver;
+use rustbuster::core::HttpClient;
+use rustbuster::modes::dns;
+use trust_dns_resolver::lookup::Lookup;
+use trust_dns_resolver::proto::op::Query;
+use trust_dns_resolver::proto::rr::rdata::{MX, TXT};
+use trust_dns_resolver::proto::rr::{Name, RData, Record, RecordType};
+
+// a base domain that does not resolve aborts the scan before any word is tried
+#[tokio::test]
+async fn test_unresolvable_base_domain_aborts_early() {
+    let err = dns::verify_base_domain("exmaple.com", |_| async { false })
+        .await
+        .unwrap_err();
+
+    let message = err.to_string();
+    assert!(message.contains("exmaple.com"), "{}", message);
+    assert!(message.contains("--force"), "{}", message);
+}
+
+// a resolvable base domain lets the scan continue
+#[tokio::test]
+async fn test_resolvable_base_domain_passes() {
+    let checked = std::sync::Mutex::new(Vec::new());
+    dns::verify_base_domain("example.com", |domain| {
+        checked.lock().unwrap().push(domain);
+        async { true }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(*checked.lock().unwrap(), vec!["example.com".to_string()]);
+}
+
+fn lookup_of(name: &str, record_type: RecordType, records: Vec<RData>) -> Lookup {
+    let name = Name::from_ascii(name).unwrap();
+    let records: Vec<Record> = records
+        .into_iter()
+        .map(|rdata| Record::from_rdata(name.clone(), 300, rdata))
+        .collect();
+    Lookup::new_with_max_ttl(Query::query(name, record_type), records.into())
+}
+
+// TXT answers are quoted and shown with their record type
+#[test]
+fn test_txt_records_are_printed() {
+    let lookup = lookup_of(
+        "mail.example.com.",
+        RecordType::TXT,
+        vec![
+            RData::TXT(TXT::new(vec!["v=spf1 include:_spf.example.com ~all".to_string()])),
+            RData::TXT(TXT::new(vec!["verification=abc123".to_string()])),
+        ],
+    );
+
+    let records = dns::format_lookup(&lookup);
+    assert_eq!(records, vec!["\"v=spf1 include:_spf.example.com ~all\"", "\"verification=abc123\""]);
+
+    let line = dns::result_line("mail.example.com", Some(RecordType::TXT), &records, false);
+    assert!(line.contains("mail.example.com"), "{}", line);
+    assert!(line.contains("[TXT]"), "{}", line);
+    assert!(line.contains("verification=abc123"), "{}", line);
+}
+
+// MX answers read as preference and exchange
+#[test]
+fn test_mx_records_are_formatted() {
+    let exchange = Name::from_ascii("mx1.example.com.").unwrap();
+    let lookup = lookup_of("example.com.", RecordType::MX, vec![RData::MX(MX::new(10, exchange))]);
+
+    assert_eq!(dns::format_lookup(&lookup), vec!["10 mx1.example.com."]);
+}
+
+// the record type is validated by the argument parser
+#[test]
+fn test_record_type_argument() {
+    let parse = |value: &str| DnsArgs::try_parse_from(["dns", "-d", "example.com", "--record-type", value]);
+    assert_eq!(parse("txt").unwrap().record_type.as_deref(), Some("txt"));
+    assert_eq!(parse("MX").unwrap().record_type.as_deref(), Some("MX"));
+    assert!(parse("SRV").is_err());
+}
+
+// a domain answering random names is flagged and names with the same answer are suppressed
+#[tokio::test]
+async fn test_wildcard_dns_suppresses_matching_names() {
+    let probes = std::sync::Mutex::new(Vec::new());
+    let wildcard = dns::detect_wildcard("example.com", |name| {
+        probes.lock().unwrap().push(name);
+        async { vec!["203.0.113.7".to_string()] }
+    })
+    .await;
+
+    let probe = probes.lock().unwrap()[0].clone();
+    assert!(probe.starts_with("rustbuster-") && probe.ends_with(".example.com"), "{}", probe);
+
+    let wildcard = wildcard.expect("wildcard should be detected");
+    assert!(dns::matches_wildcard(&["203.0.113.7".to_string()], Some(&wildcard)));
+    assert!(!dns::matches_wildcard(&["198.51.100.1".to_string()], Some(&wildcard)));
+    assert!(!dns::matches_wildcard(
+        &["203.0.113.7".to_string(), "198.51.100.1".to_string()],
+        Some(&wildcard)
+    ));
+    assert!(!dns::matches_wildcard(&["203.0.113.7".to_string()], None));
+}
+
+// a random name that does not resolve means there is no wildcard
+#[tokio::test]
+async fn test_no_wildcard_when_random_name_fails() {
+    assert!(dns::detect_wildcard("example.com", |_| async { Vec::new() }).await.is_none());
+}
+
+fn dns_args(extra: &[&str]) -> DnsArgs {
+    let mut args = vec!["dns", "-d", "example.com"];
+    args.extend_from_slice(extra);
+    DnsArgs::try_parse_from(args).unwrap()
+}
+
+// --resolver and --resolvers-file nameservers make up the resolver config
+#[test]
+fn test_resolver_config_uses_given_nameservers() {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "# backup\n9.9.9.9:5353").unwrap();
+    let args = dns_args(&["--resolver", "1.1.1.1, 8.8.8.8", "--resolvers-file", file.path().to_str().unwrap()]);
+
+    let config = dns::resolver_config(&args).unwrap();
+    let mut addrs: Vec<String> = config
+        .name_servers()
+        .iter()
+        .map(|ns| ns.socket_addr.to_string())
+        .collect();
+    addrs.dedup();
+    assert_eq!(addrs, vec!["1.1.1.1:53", "8.8.8.8:53", "9.9.9.9:5353"]);
+}
+
+// malformed resolver addresses are rejected before scanning
+#[test]
+fn test_resolver_config_rejects_invalid_address() {
+    let err = dns::resolver_config(&dns_args(&["--resolver", "1.1.1.1,not-an-ip"])).unwrap_err();
+    assert!(err.to_string().contains("not-an-ip"), "{}", err);
+}
+
+fn doh_resolver(endpoint: &str) -> DohResolver {
+    let common = CommonArgs::try_parse_from(["rustbuster", "-q"]).unwrap();
+    DohResolver::new(HttpClient::new_from_common(&common).unwrap(), endpoint).unwrap()
+}
+
+// DoH answers of the requested type are returned and the query uses the JSON API
+#[tokio::test]
+async fn test_doh_lookup_reads_json_answers() {
+    let server = MockServer::start(|req| {
+        if req.path.contains("type=TXT") {
+            MockResponse::new(
+                200,
+                r#"{"Status":0,"Answer":[{"name":"example.com","type":5,"data":"alias.example.com."},{"name":"example.com","type":16,"data":"v=spf1 -all"}]}"#,
+            )
+        } else {
+            MockResponse::new(200, r#"{"Status":3}"#)
+        }
+    });
+    let resolver = doh_resolver(&format!("{}/dns-query", server.url()));
+
+    let records = resolver.lookup("example.com", RecordType::TXT).await.unwrap();
+    assert_eq!(records, vec!["\"v=spf1 -all\""]);
+
+    let err = resolver.lookup("missing.example.com", RecordType::A).await.unwrap_err();
+    assert!(err.to_string().contains("rcode 3"), "{}", err);
+
+    let request = &server.requests()[0];
+    assert!(request.path.starts_with("/dns-query?name=example.com&type=TXT"), "{}", request.path);
+    assert_eq!(request.header("accept"), Some("application/dns-json"));
+}
+
+// --doh cannot be combined with plain nameservers
+#[test]
+fn test_doh_conflicts_with_resolver() {
+    assert!(DnsArgs::try_parse_from(["dns", "-d", "example.com", "--doh", "https://dns.google/resolve"]).is_ok());
+    assert!(DnsArgs::try_parse_from([
+        "dns",
+        "-d",
+        "example.com",
+        "--doh",
+        "https://dns.google/resolve",
+        "--resolver",
+        "1.1.1.1"
+    ])
+    .is_err());
+}
+
+// resolves a real name through Cloudflare's DoH endpoint; needs network access
+#[tokio::test]
+#[ignore]
+async fn test_doh_resolves_known_name() {
+    let resolver = doh_resolver("https://cloudflare-dns.com/dns-query");
+    let records = resolver.lookup("one.one.one.one", RecordType::A).await.unwrap();
+    assert!(records.iter().any(|ip| ip == "1.1.1.1"), "{:?}", records);
+}
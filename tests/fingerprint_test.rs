@@ -0,0 +1,127 @@
+//! Tests for technology fingerprinting
+
+mod common;
+
+use clap::Parser;
+use common::{MockResponse, MockServer};
+use reqwest::header::{HeaderMap, HeaderValue};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::{ScanTarget, Scanner};
+use rustbuster::utils::fingerprint::Fingerprinter;
+
+fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in pairs {
+        map.append(*name, HeaderValue::from_static(value));
+    }
+    map
+}
+
+// the Server header names the web server, whatever its case and version
+#[test]
+fn test_fingerprint_server_header() {
+    let fingerprinter = Fingerprinter::builtin();
+    assert_eq!(fingerprinter.detect(&headers(&[("Server", "nginx/1.25.3")]), ""), vec!["nginx"]);
+    assert_eq!(fingerprinter.detect(&headers(&[("Server", "Apache/2.4")]), ""), vec!["Apache"]);
+    assert!(fingerprinter.detect(&headers(&[("Server", "gws")]), "").is_empty());
+}
+
+// body markers, X-Powered-By and cookie names combine, each technology once
+#[test]
+fn test_fingerprint_body_header_and_cookie() {
+    let fingerprinter = Fingerprinter::builtin();
+    let response = headers(&[
+        ("X-Powered-By", "PHP/8.2"),
+        ("Set-Cookie", "PHPSESSID=abc; path=/"),
+        ("Set-Cookie", "theme=dark"),
+    ]);
+    let body = r#"<link rel="stylesheet" href="/wp-content/themes/x/style.css">"#;
+    assert_eq!(fingerprinter.detect(&response, body), vec!["PHP", "WordPress"]);
+
+    // A cookie named after a technology only counts by its name, not its value
+    let response = headers(&[("Set-Cookie", "session=JSESSIONID")]);
+    assert!(fingerprinter.detect(&response, "").is_empty());
+}
+
+// --fingerprint tags hits from the scanner; without it nothing is tagged
+#[tokio::test]
+async fn test_scanner_fingerprints_hits() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/blog" => MockResponse::new(200, "<script src=\"/wp-includes/js/jquery.js\"></script>")
+            .header("Server", "nginx"),
+        _ => MockResponse::new(404, "not found").header("Server", "nginx"),
+    });
+    let scan = |flags: &'static [&'static str]| {
+        let url = format!("{}/blog", server.url());
+        async move {
+            let mut args = vec!["rustbuster", "-q"];
+            args.extend_from_slice(flags);
+            let scanner = Scanner::new_from_common(CommonArgs::try_parse_from(args).unwrap()).unwrap();
+            scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap()
+        }
+    };
+
+    let results = scan(&["--fingerprint"]).await;
+    assert_eq!(results[0].technologies, vec!["nginx", "WordPress"]);
+
+    let results = scan(&[]).await;
+    assert!(results[0].technologies.is_empty());
+}
+
+fn rules_file(suffix: &str, content: &str) -> tempfile::NamedTempFile {
+    use std::io::Write;
+
+    let mut file = tempfile::Builder::new().suffix(suffix).tempfile().unwrap();
+    write!(file, "{}", content).unwrap();
+    file
+}
+
+fn fingerprinter_with(rules: &tempfile::NamedTempFile) -> anyhow::Result<Option<Fingerprinter>> {
+    let path = rules.path().to_str().unwrap();
+    Fingerprinter::from_common(&CommonArgs::try_parse_from(["rustbuster", "--fingerprint-rules", path]).unwrap())
+}
+
+// custom rules from TOML are added to the built-ins and implied --fingerprint
+#[test]
+fn test_custom_rules_from_toml() {
+    let rules = rules_file(".toml", r#"
+[[rules]]
+technology = "Acme CMS"
+pattern = "acme-cms-[0-9]+"
+
+[[rules]]
+technology = "Acme Gateway"
+header = "X-Acme-Node"
+pattern = "^gw-"
+"#);
+    let fingerprinter = fingerprinter_with(&rules).unwrap().expect("rules imply --fingerprint");
+
+    let response = headers(&[("Server", "nginx"), ("X-Acme-Node", "gw-eu-1")]);
+    assert_eq!(
+        fingerprinter.detect(&response, "<meta name=\"generator\" content=\"ACME-CMS-4\">"),
+        vec!["nginx", "Acme CMS", "Acme Gateway"]
+    );
+    assert!(fingerprinter.detect(&headers(&[]), "acme-cms").is_empty());
+}
+
+// JSON works too, with cookie rules
+#[test]
+fn test_custom_rules_from_json() {
+    let rules = rules_file(".json", r#"{"rules": [{"technology": "Acme SSO", "pattern": "^acme_sid$", "cookie": true}]}"#);
+    let fingerprinter = fingerprinter_with(&rules).unwrap().unwrap();
+    assert_eq!(fingerprinter.detect(&headers(&[("Set-Cookie", "acme_sid=1")]), ""), vec!["Acme SSO"]);
+}
+
+// bad regexes, contradictory targets and unknown keys fail when the rules are loaded
+#[test]
+fn test_custom_rules_validated_at_load() {
+    let bad_regex = rules_file(".toml", "[[rules]]\ntechnology = \"Broken\"\npattern = \"(unclosed\"\n");
+    let err = fingerprinter_with(&bad_regex).err().unwrap();
+    assert!(format!("{:#}", err).contains("Broken"), "{:#}", err);
+
+    let both = rules_file(".toml", "[[rules]]\ntechnology = \"X\"\npattern = \".\"\nheader = \"Via\"\ncookie = true\n");
+    assert!(fingerprinter_with(&both).is_err());
+
+    let typo = rules_file(".toml", "[[rules]]\ntechnology = \"X\"\npatern = \".\"\n");
+    assert!(fingerprinter_with(&typo).is_err());
+}
@@ -0,0 +1,264 @@
+//! Tests for fuzz mode keyword substitution
+
+mod common;
+
+use clap::Parser;
+use common::{wordlist_file, MockResponse, MockServer};
+use rustbuster::cli::FuzzArgs;
+use rustbuster::modes::fuzz;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+
+fn fuzz_args(url: &str, wordlist: &NamedTempFile, extra: &[&str]) -> FuzzArgs {
+    let mut args = vec![
+        "fuzz",
+        "-u",
+        url,
+        "-w",
+        wordlist.path().to_str().unwrap(),
+        "--no-tui",
+        "-q",
+    ];
+    args.extend_from_slice(extra);
+    FuzzArgs::try_parse_from(args).unwrap()
+}
+
+// FUZZ inside --data is substituted per word and sent as the POST body
+#[tokio::test]
+async fn test_fuzz_post_body_substitution() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let wordlist = wordlist_file(&["admin", "login"]);
+    let url = format!("{}/api/search", server.url());
+
+    let args = fuzz_args(&url, &wordlist, &["--method", "POST", "--data", r#"{"q":"FUZZ"}"#]);
+    fuzz::run(args).await.unwrap();
+
+    let mut bodies: Vec<String> = server.requests().into_iter().map(|r| r.body).collect();
+    bodies.sort();
+    assert_eq!(bodies, vec![r#"{"q":"admin"}"#, r#"{"q":"login"}"#]);
+    assert!(server.requests().iter().all(|r| r.method == "POST"));
+}
+
+// without FUZZ in the URL or body the run is rejected
+#[tokio::test]
+async fn test_fuzz_requires_keyword() {
+    let wordlist = wordlist_file(&["admin"]);
+    let args = fuzz_args("http://127.0.0.1:1/api", &wordlist, &["--data", "q=static"]);
+    assert!(fuzz::run(args).await.is_err());
+}
+
+// FUZZ inside -H values is substituted per request
+#[tokio::test]
+async fn test_fuzz_header_substitution() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let wordlist = wordlist_file(&["key-one", "key-two", "key-three"]);
+    let url = format!("{}/api", server.url());
+
+    let args = fuzz_args(&url, &wordlist, &["-H", "X-Api-Key: FUZZ", "-c", "session=FUZZ"]);
+    fuzz::run(args).await.unwrap();
+
+    let requests = server.requests();
+    let mut keys: Vec<&str> = requests.iter().filter_map(|r| r.header("x-api-key")).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["key-one", "key-three", "key-two"]);
+
+    for request in &requests {
+        let key = request.header("x-api-key").unwrap();
+        assert_eq!(request.header("cookie"), Some(format!("session={}", key).as_str()));
+    }
+}
+
+// with --cookie-jar and many threads each request, redirect hops included, still carries its
+// own FUZZ cookie next to the cookie the server set
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_fuzz_cookie_with_cookie_jar_concurrent() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/api" => MockResponse::new(302, "")
+            .header("Location", "/final")
+            .header("Set-Cookie", "tracker=1; Path=/")
+            .delay(Duration::from_millis(20)),
+        _ => MockResponse::new(200, "ok"),
+    });
+    let words: Vec<String> = (0..30).map(|i| format!("key-{}", i)).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let wordlist = wordlist_file(&words);
+    let url = format!("{}/api", server.url());
+
+    let args = fuzz_args(
+        &url,
+        &wordlist,
+        &["-H", "X-Api-Key: FUZZ", "-c", "session=FUZZ", "--cookie-jar", "-r", "-t", "10"],
+    );
+    fuzz::run(args).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), words.len() * 2);
+    for request in &requests {
+        let key = request.header("x-api-key").unwrap();
+        let cookie = request.header("cookie").unwrap();
+        let sessions: Vec<&str> = cookie.split("; ").filter(|pair| pair.starts_with("session=")).collect();
+        assert_eq!(sessions, vec![format!("session={}", key)], "{}", cookie);
+    }
+    assert!(requests
+        .iter()
+        .filter(|r| r.path == "/final")
+        .all(|r| r.header("cookie").unwrap().contains("tracker=1")));
+}
+
+fn sorted_paths(server: &MockServer) -> Vec<String> {
+    let mut paths = server.paths();
+    paths.sort();
+    paths
+}
+
+// clusterbomb requests every combination of the two keyword lists
+#[tokio::test]
+async fn test_fuzz_clusterbomb_combinations() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let paths = wordlist_file(&["a", "b"]);
+    let ids = wordlist_file(&["1", "2", "3"]);
+    let url = format!("{}/FUZZ/FUZZ2", server.url());
+    let binding = format!("FUZZ2={}", ids.path().to_str().unwrap());
+
+    let args = fuzz_args(&url, &paths, &["--fuzz-wordlist", &binding]);
+    fuzz::run(args).await.unwrap();
+
+    assert_eq!(
+        sorted_paths(&server),
+        vec!["/a/1", "/a/2", "/a/3", "/b/1", "/b/2", "/b/3"]
+    );
+}
+
+// a clusterbomb past the request limit is refused before anything is sent
+#[tokio::test]
+async fn test_fuzz_clusterbomb_over_limit_refused() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let side = (fuzz::MAX_COMBINED_REQUESTS as f64).sqrt() as usize + 1;
+    let words: Vec<String> = (0..side).map(|i| i.to_string()).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let paths = wordlist_file(&words);
+    let ids = wordlist_file(&words);
+    let url = format!("{}/FUZZ/FUZZ2", server.url());
+    let binding = format!("FUZZ2={}", ids.path().to_str().unwrap());
+
+    let args = fuzz_args(&url, &paths, &["--fuzz-wordlist", &binding]);
+    let err = fuzz::run(args).await.unwrap_err();
+    assert!(err.to_string().contains("more than the limit"), "{}", err);
+    assert!(server.requests().is_empty());
+}
+
+// pitchfork pairs the lists line by line and stops at the shortest
+#[tokio::test]
+async fn test_fuzz_pitchfork_pairs() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let paths = wordlist_file(&["a", "b"]);
+    let ids = wordlist_file(&["1", "2", "3"]);
+    let url = format!("{}/FUZZ/FUZZ2", server.url());
+    let binding = format!("FUZZ2={}", ids.path().to_str().unwrap());
+
+    let args = fuzz_args(&url, &paths, &["--fuzz-wordlist", &binding, "--fuzz-mode", "pitchfork"]);
+    fuzz::run(args).await.unwrap();
+
+    assert_eq!(sorted_paths(&server), vec!["/a/1", "/b/2"]);
+}
+
+// FUZZ bound with --fuzz-wordlist works without -w
+#[tokio::test]
+async fn test_fuzz_keyword_bound_without_wordlist() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let words = wordlist_file(&["a", "b"]);
+    let url = format!("{}/FUZZ", server.url());
+    let binding = format!("FUZZ={}", words.path().to_str().unwrap());
+
+    let args = FuzzArgs::try_parse_from(["fuzz", "-u", &url, "--fuzz-wordlist", &binding, "--no-tui", "-q"])
+        .unwrap();
+    fuzz::run(args).await.unwrap();
+
+    assert_eq!(sorted_paths(&server), vec!["/a", "/b"]);
+}
+
+// a word that contains another keyword is put in as it is, not substituted again
+#[tokio::test]
+async fn test_fuzz_words_are_not_substituted_twice() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let paths = wordlist_file(&["a"]);
+    let ids = wordlist_file(&["xFUZZy"]);
+    let url = format!("{}/FUZZ/FUZZ2", server.url());
+    let binding = format!("FUZZ2={}", ids.path().to_str().unwrap());
+
+    let args = fuzz_args(&url, &paths, &["--fuzz-wordlist", &binding]);
+    fuzz::run(args).await.unwrap();
+
+    assert_eq!(sorted_paths(&server), vec!["/a/xFUZZy"]);
+}
+
+// -R re-fuzzes the last path segment inside each discovered directory
+#[tokio::test]
+async fn test_recursive_fuzz_descends_two_levels() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(301, "").header("Location", "/admin/"),
+        "/admin/users" => MockResponse::new(301, "").header("Location", "/admin/users/"),
+        "/admin/users/list" => MockResponse::new(200, "users"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let wordlist = wordlist_file(&["admin", "users", "list"]);
+
+    let url = format!("{}/FUZZ", server.url());
+    fuzz::run(fuzz_args(&url, &wordlist, &["-R"])).await.unwrap();
+
+    let paths = server.paths();
+    assert!(paths.contains(&"/admin/users".to_string()));
+    assert!(paths.contains(&"/admin/users/list".to_string()));
+    assert!(!paths.contains(&"/users/list".to_string()));
+}
+
+// a server that turns every path into a directory is not recursed into
+#[tokio::test]
+async fn test_recursive_fuzz_stops_when_every_path_is_a_directory() {
+    let server = MockServer::start(|req| {
+        let location = format!("{}/", req.path);
+        MockResponse::new(301, "").header("Location", &location)
+    });
+    let wordlist = wordlist_file(&["a", "b"]);
+
+    let url = format!("{}/FUZZ", server.url());
+    fuzz::run(fuzz_args(&url, &wordlist, &["-R", "--wildcard"])).await.unwrap();
+
+    let paths = server.paths();
+    assert!(paths.contains(&"/a".to_string()));
+    assert!(!paths.iter().any(|p| p.starts_with("/a/") && !p.contains("rustbuster")), "{:?}", paths);
+}
+
+// recursion needs FUZZ in the last path segment
+#[tokio::test]
+async fn test_recursive_fuzz_rejects_query_keyword() {
+    let wordlist = wordlist_file(&["a"]);
+    let args = fuzz_args("http://127.0.0.1:9/search?q=FUZZ", &wordlist, &["-R"]);
+    let err = fuzz::run(args).await.unwrap_err().to_string();
+    assert!(err.contains("last path segment"), "{}", err);
+}
+
+// --extensions-file entries are normalized and merged with -x for the FUZZ keyword
+#[tokio::test]
+async fn test_fuzz_extensions_file_merged_with_inline_extensions() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = wordlist_file(&["index"]);
+    let extensions = wordlist_file(&["bak", ".txt", "php", "# comment"]);
+    let url = format!("{}/FUZZ", server.url());
+
+    let args = fuzz_args(
+        &url,
+        &wordlist,
+        &["-x", "php,html", "--extensions-file", extensions.path().to_str().unwrap()],
+    );
+    assert_eq!(
+        args.common.get_extensions(&args.extensions, &args.extensions_file).unwrap(),
+        vec![".php", ".html", ".bak", ".txt"]
+    );
+
+    fuzz::run(args).await.unwrap();
+    assert_eq!(
+        sorted_paths(&server),
+        vec!["/index", "/index.bak", "/index.html", "/index.php", "/index.txt"]
+    );
+}
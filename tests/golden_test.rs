@@ -0,0 +1,30 @@
+//! Tests for golden body storage and change detection
+
+use rustbuster::utils::golden::{GoldenStatus, GoldenStore};
+
+// first sight stores the body, identical bodies report no diff, changes are flagged
+#[test]
+fn test_golden_store_detects_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = GoldenStore::new(dir.path().to_str().unwrap()).unwrap();
+    let url = "http://example.com/status";
+
+    assert_eq!(store.compare(url, "line one\nline two\n").unwrap(), GoldenStatus::New);
+    assert_eq!(store.compare(url, "line one\nline two\n").unwrap(), GoldenStatus::Unchanged);
+
+    match store.compare(url, "line one\nline three\nline four\n").unwrap() {
+        GoldenStatus::Changed(summary) => assert!(summary.starts_with("+2 -1 lines")),
+        other => panic!("expected a change, got {:?}", other),
+    }
+}
+
+// golden copies are kept per URL
+#[test]
+fn test_golden_store_is_keyed_by_url() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = GoldenStore::new(dir.path().to_str().unwrap()).unwrap();
+
+    assert_eq!(store.compare("http://example.com/a", "a").unwrap(), GoldenStatus::New);
+    assert_eq!(store.compare("http://example.com/b", "b").unwrap(), GoldenStatus::New);
+    assert_eq!(store.compare("http://example.com/a", "a").unwrap(), GoldenStatus::Unchanged);
+}
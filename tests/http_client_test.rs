@@ -0,0 +1,500 @@
+//! Tests for the shared HTTP client
+
+mod common;
+
+use clap::Parser;
+use common::{MockResponse, MockServer};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::{ScanTarget, Scanner};
+use std::time::{Duration, Instant};
+
+// --rate-limit caps the combined rate of all worker threads
+#[tokio::test]
+async fn test_rate_limit_caps_total_throughput() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let args = CommonArgs::try_parse_from(["rustbuster", "-q", "-t", "20", "--rate-limit", "10"]).unwrap();
+
+    let targets = (0..50)
+        .map(|i| ScanTarget::new(format!("{}/{}", server.url(), i), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(args).unwrap();
+    let start = Instant::now();
+    scanner.scan_urls(targets).await.unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(server.requests().len(), 50);
+    assert!(elapsed >= Duration::from_secs(4), "took {:?}", elapsed);
+}
+
+// a zero rate is rejected up front
+#[test]
+fn test_rate_limit_rejects_zero() {
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--rate-limit", "0"]).is_err());
+}
+
+// -t bounds HTTP requests in flight, whatever --dns-threads is set to
+#[tokio::test]
+async fn test_http_and_dns_concurrency_are_independent() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (current, max) = (Arc::clone(&in_flight), Arc::clone(&peak));
+    let server = MockServer::start(move |_| {
+        let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+        max.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(50));
+        current.fetch_sub(1, Ordering::SeqCst);
+        MockResponse::new(404, "not found")
+    });
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "-q", "-t", "3", "--dns-threads", "50"]).unwrap();
+    assert_eq!(args.threads, 3);
+    assert_eq!(args.get_dns_threads(), 50);
+
+    let targets = (0..24)
+        .map(|i| ScanTarget::new(format!("{}/{}", server.url(), i), None))
+        .collect();
+    Scanner::new_from_common(args).unwrap().scan_urls(targets).await.unwrap();
+
+    assert!(peak.load(Ordering::SeqCst) <= 3, "peak {}", peak.load(Ordering::SeqCst));
+}
+
+// DNS concurrency follows -t unless set explicitly
+#[test]
+fn test_dns_threads_default_to_threads() {
+    let args = CommonArgs::try_parse_from(["rustbuster", "-t", "7"]).unwrap();
+    assert_eq!(args.get_dns_threads(), 7);
+}
+
+// --connect-timeout gives up on an unroutable address long before --timeout
+#[tokio::test]
+async fn test_connect_timeout_fails_fast_on_blackholed_address() {
+    let args = CommonArgs::try_parse_from(["rustbuster", "--timeout", "30", "--connect-timeout", "1"]).unwrap();
+    let client = rustbuster::core::HttpClient::new_from_common(&args).unwrap();
+
+    let start = Instant::now();
+    let result = client.request("http://10.255.255.1/", "GET", &[], None, None).await;
+
+    assert!(result.is_err());
+    assert!(start.elapsed() < Duration::from_secs(5), "took {:?}", start.elapsed());
+}
+
+fn client_with(extra: &[&str]) -> rustbuster::core::HttpClient {
+    let mut args = vec!["rustbuster"];
+    args.extend_from_slice(extra);
+    rustbuster::core::HttpClient::new_from_common(&CommonArgs::try_parse_from(args).unwrap()).unwrap()
+}
+
+// every protocol and pool combination builds a client
+#[test]
+fn test_protocol_and_pool_options_build() {
+    for extra in [
+        &[][..],
+        &["--http1-only"],
+        &["--http2-only"],
+        &["--pool-max-idle", "0"],
+        &["--http1-only", "--pool-max-idle", "32"],
+        &["--http2-only", "--pool-max-idle", "32"],
+    ] {
+        client_with(extra);
+    }
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--http1-only", "--http2-only"]).is_err());
+}
+
+// the local server only speaks HTTP/1.1, so forcing HTTP/2 cannot reach it
+#[tokio::test]
+async fn test_protocol_options_pick_the_wire_protocol() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let url = format!("{}/", server.url());
+
+    let response = client_with(&["--http1-only"]).request(&url, "GET", &[], None, None).await.unwrap();
+    assert_eq!(response.version(), reqwest::Version::HTTP_11);
+
+    assert!(client_with(&["--http2-only"]).request(&url, "GET", &[], None, None).await.is_err());
+}
+
+fn gzip_server(text: &'static str) -> (MockServer, usize) {
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let compressed_len = compressed.len();
+
+    let server = MockServer::start(move |_| {
+        MockResponse::bytes(200, compressed.clone()).header("Content-Encoding", "gzip")
+    });
+    (server, compressed_len)
+}
+
+// gzip bodies are decoded by default, so their text and size are the real content
+#[tokio::test]
+async fn test_gzip_body_decoded_by_default() {
+    let text = "<html><body>admin panel admin panel admin panel</body></html>";
+    let (server, _) = gzip_server(text);
+    let url = format!("{}/admin", server.url());
+
+    let client = client_with(&[]);
+    let response = client.request(&url, "GET", &[], None, None).await.unwrap();
+    assert_eq!(client.read_body(response).await.text(), text);
+
+    let accept = server.requests()[0].header("Accept-Encoding").unwrap_or("").to_string();
+    assert!(accept.contains("gzip"), "{}", accept);
+
+    let scanner = Scanner::new_from_common(CommonArgs::try_parse_from(["rustbuster", "-q"]).unwrap()).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+    assert_eq!(results[0].content_length, text.len() as u64);
+}
+
+// --no-compression leaves the bytes as sent, so sizes are the compressed length
+#[tokio::test]
+async fn test_no_compression_keeps_raw_size() {
+    let (server, compressed_len) = gzip_server("<html><body>admin panel</body></html>");
+    let url = format!("{}/admin", server.url());
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "-q", "--no-compression"]).unwrap();
+    let scanner = Scanner::new_from_common(args).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+
+    assert_eq!(results[0].content_length, compressed_len as u64);
+    assert!(server.requests()[0].header("Accept-Encoding").is_none());
+}
+
+// each request waits a random time inside the --random-delay range
+#[tokio::test]
+async fn test_random_delay_stays_within_range() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let url = format!("{}/", server.url());
+    let client = client_with(&["--random-delay", "50-150"]);
+
+    for _ in 0..6 {
+        let start = Instant::now();
+        client.request(&url, "GET", &[], None, None).await.unwrap();
+        let gap = start.elapsed();
+        assert!(gap >= Duration::from_millis(50), "gap {:?}", gap);
+        assert!(gap < Duration::from_millis(150 + 100), "gap {:?}", gap);
+    }
+}
+
+// --random-delay cannot be combined with --delay, and the range must be ordered
+#[test]
+fn test_random_delay_argument_validation() {
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--random-delay", "10-20", "--delay", "5"]).is_err());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--random-delay", "20-10"]).is_err());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--random-delay", "fast"]).is_err());
+    assert_eq!(
+        CommonArgs::try_parse_from(["rustbuster", "--random-delay", "10-20"]).unwrap().random_delay,
+        Some((10, 20))
+    );
+}
+
+// --cookies-file rotates its lines round-robin, and -c overrides it
+#[tokio::test]
+async fn test_cookies_file_rotates_per_request() {
+    use std::io::Write;
+
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let url = format!("{}/", server.url());
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "session=alice").unwrap();
+    writeln!(file).unwrap();
+    writeln!(file, "session=bob").unwrap();
+    let path = file.path().to_str().unwrap();
+
+    let client = client_with(&["--cookies-file", path]);
+    for _ in 0..3 {
+        client.request(&url, "GET", &[], None, None).await.unwrap();
+    }
+    client.request(&url, "GET", &[], Some("session=carol"), None).await.unwrap();
+
+    let cookies: Vec<String> = server
+        .requests()
+        .iter()
+        .map(|r| r.header("Cookie").unwrap_or("").to_string())
+        .collect();
+    assert_eq!(cookies, vec!["session=alice", "session=bob", "session=alice", "session=carol"]);
+}
+
+fn header_values(server: &MockServer, name: &str) -> Vec<String> {
+    server
+        .requests()
+        .iter()
+        .map(|r| r.header(name).unwrap_or("").to_string())
+        .collect()
+}
+
+// --spoof-ip sends a fresh public address in every forwarding header
+#[tokio::test]
+async fn test_spoof_ip_headers_differ_per_request() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let url = format!("{}/", server.url());
+    let client = client_with(&["--spoof-ip"]);
+
+    for _ in 0..5 {
+        client.request(&url, "GET", &[], None, None).await.unwrap();
+    }
+
+    let forwarded = header_values(&server, "X-Forwarded-For");
+    assert_eq!(forwarded, header_values(&server, "X-Real-IP"));
+    assert_eq!(forwarded, header_values(&server, "X-Originating-IP"));
+    for ip in &forwarded {
+        let ip: std::net::Ipv4Addr = ip.parse().unwrap();
+        assert!(!ip.is_private() && !ip.is_loopback(), "{}", ip);
+    }
+    let distinct: std::collections::HashSet<&String> = forwarded.iter().collect();
+    assert!(distinct.len() > 1, "{:?}", forwarded);
+}
+
+// --spoof-ip-file rotates its addresses and -H still wins for the headers it sets
+#[tokio::test]
+async fn test_spoof_ip_file_rotates_and_respects_user_headers() {
+    use std::io::Write;
+
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let url = format!("{}/", server.url());
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "10.0.0.1\n10.0.0.2").unwrap();
+    let client = client_with(&["--spoof-ip-file", file.path().to_str().unwrap()]);
+
+    let user = vec![("X-Real-IP".to_string(), "1.2.3.4".to_string())];
+    for _ in 0..2 {
+        client.request(&url, "GET", &user, None, None).await.unwrap();
+    }
+
+    assert_eq!(header_values(&server, "X-Forwarded-For"), vec!["10.0.0.1", "10.0.0.2"]);
+    assert_eq!(header_values(&server, "X-Real-IP"), vec!["1.2.3.4", "1.2.3.4"]);
+}
+
+async fn authorization_sent(flags: &[&str]) -> Option<String> {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let mut args = vec!["rustbuster"];
+    args.extend_from_slice(flags);
+    let args = CommonArgs::try_parse_from(args).unwrap();
+
+    let client = rustbuster::core::HttpClient::new_from_common(&args).unwrap();
+    client.request(&server.url(), "GET", &[], None, None).await.unwrap();
+    server.requests()[0].header("Authorization").map(str::to_string)
+}
+
+// --basic-auth sends the base64 of USER:PASS
+#[tokio::test]
+async fn test_basic_auth_header() {
+    let value = authorization_sent(&["--basic-auth", "admin:s3cret"]).await;
+    assert_eq!(value.as_deref(), Some("Basic YWRtaW46czNjcmV0"));
+}
+
+// --bearer sends the token as is
+#[tokio::test]
+async fn test_bearer_header() {
+    let value = authorization_sent(&["--bearer", "abc.def.ghi"]).await;
+    assert_eq!(value.as_deref(), Some("Bearer abc.def.ghi"));
+}
+
+// the shortcuts refuse to fight with each other or with an explicit -H
+#[test]
+fn test_auth_flags_conflict() {
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--basic-auth", "a:b", "--bearer", "t"]).is_err());
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "--bearer", "t", "-H", "authorization: Basic x"]).unwrap();
+    assert!(rustbuster::core::HttpClient::new_from_common(&args).is_err());
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "--basic-auth", "nocolon"]).unwrap();
+    assert!(args.get_authorization().is_err());
+}
+
+/// Sends one request with `flags` and the -H pairs `user`, returning the server's view of it
+async fn request_with(flags: &[&str], user: &[(&str, &str)]) -> common::RecordedRequest {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let client = client_with(flags);
+    let user: Vec<(String, String)> = user.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    client.request(&server.url(), "GET", &user, None, None).await.unwrap();
+    server.requests().remove(0)
+}
+
+// --json asks for and declares JSON
+#[tokio::test]
+async fn test_json_sets_accept_and_content_type() {
+    let request = request_with(&["--json"], &[]).await;
+    assert_eq!(request.header("Accept"), Some("application/json"));
+    assert_eq!(request.header("Content-Type"), Some("application/json"));
+}
+
+// an explicit -H replaces the default instead of doubling it, and --accept beats --json
+#[tokio::test]
+async fn test_header_defaults_overridden() {
+    let request = request_with(&["--json"], &[("accept", "text/html")]).await;
+    assert_eq!(request.header("Accept"), Some("text/html"));
+    assert_eq!(request.headers.iter().filter(|(key, _)| key.eq_ignore_ascii_case("Accept")).count(), 1);
+    assert_eq!(request.header("Content-Type"), Some("application/json"));
+
+    let request = request_with(&["--json", "--accept", "application/xml"], &[]).await;
+    assert_eq!(request.header("Accept"), Some("application/xml"));
+
+    let request = request_with(&["--content-type", "text/plain"], &[]).await;
+    assert_eq!(request.header("Content-Type"), Some("text/plain"));
+}
+
+// --ipv4-only still reaches an IPv4 server, while --ipv6-only refuses to use its address
+#[tokio::test]
+async fn test_ip_family_restriction() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+
+    let client = client_with(&["--ipv4-only"]);
+    let response = client.request(&server.url(), "GET", &[], None, None).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    let client = client_with(&["--ipv6-only"]);
+    assert!(client.request(&server.url(), "GET", &[], None, None).await.is_err());
+    assert_eq!(server.requests().len(), 1);
+
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--ipv4-only", "--ipv6-only"]).is_err());
+}
+
+// --interface binds requests to a local address, which has to be a valid IP
+#[tokio::test]
+async fn test_interface_binds_local_address() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+
+    let client = client_with(&["--interface", "127.0.0.1"]);
+    let response = client.request(&server.url(), "GET", &[], None, None).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--interface", "eth0"]).is_err());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--interface", "::1", "--ipv4-only"]).is_err());
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+// a PEM certificate and key pair loads into the client
+#[test]
+fn test_client_certificate_loads() {
+    let (cert, key) = (fixture("client-cert.pem"), fixture("client-key.pem"));
+    let args = CommonArgs::try_parse_from(["rustbuster", "--client-cert", &cert, "--client-key", &key]).unwrap();
+    assert!(rustbuster::core::HttpClient::new_from_common(&args).is_ok());
+}
+
+// half a pair is refused when parsing, and files that are not PEM fail the build
+#[test]
+fn test_client_certificate_errors() {
+    use std::io::Write;
+
+    let cert = fixture("client-cert.pem");
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--client-cert", &cert]).is_err());
+
+    let mut not_a_key = tempfile::NamedTempFile::new().unwrap();
+    writeln!(not_a_key, "not a key").unwrap();
+    let key = not_a_key.path().to_str().unwrap();
+    let args = CommonArgs::try_parse_from(["rustbuster", "--client-cert", &cert, "--client-key", key]).unwrap();
+    assert!(rustbuster::core::HttpClient::new_from_common(&args).is_err());
+}
+
+/// Serves a login that sets a session cookie and redirects to a page that needs it
+fn login_server() -> MockServer {
+    MockServer::start(|req| match req.path.as_str() {
+        "/login" => MockResponse::new(302, "")
+            .header("Location", "/home")
+            .header("Set-Cookie", "session=abc123; Path=/"),
+        _ => MockResponse::new(200, "ok"),
+    })
+}
+
+fn cookie_sent(server: &MockServer, path: &str) -> Option<String> {
+    server
+        .requests()
+        .iter()
+        .find(|request| request.path == path)
+        .and_then(|request| request.header("Cookie").map(str::to_string))
+}
+
+// --cookie-jar carries Set-Cookie through the redirect and into later requests, next to -c
+#[tokio::test]
+async fn test_cookie_jar_keeps_set_cookie() {
+    let server = login_server();
+    let client = client_with(&["--cookie-jar", "-r"]);
+    client.request(&format!("{}/login", server.url()), "GET", &[], Some("lang=en"), None).await.unwrap();
+    client.request(&format!("{}/profile", server.url()), "GET", &[], None, None).await.unwrap();
+
+    let home = cookie_sent(&server, "/home").unwrap();
+    assert!(home.contains("session=abc123"));
+    assert!(home.contains("lang=en"));
+    assert!(cookie_sent(&server, "/profile").unwrap().contains("session=abc123"));
+}
+
+// without the jar only the static -c header is sent
+#[tokio::test]
+async fn test_cookies_static_without_jar() {
+    let server = login_server();
+    let client = client_with(&["-r"]);
+    client.request(&format!("{}/login", server.url()), "GET", &[], Some("lang=en"), None).await.unwrap();
+    client.request(&format!("{}/profile", server.url()), "GET", &[], None, None).await.unwrap();
+
+    assert_eq!(cookie_sent(&server, "/home").as_deref(), Some("lang=en"));
+    assert_eq!(cookie_sent(&server, "/profile"), None);
+}
+
+/// Serves /start -> /middle -> /end
+fn redirect_chain_server() -> MockServer {
+    MockServer::start(|req| match req.path.as_str() {
+        "/start" => MockResponse::new(302, "").header("Location", "/middle"),
+        "/middle" => MockResponse::new(301, "").header("Location", "/end"),
+        _ => MockResponse::new(200, "arrived"),
+    })
+}
+
+// a followed chain records where it ended and how many hops it took
+#[tokio::test]
+async fn test_redirect_chain_recorded() {
+    let server = redirect_chain_server();
+    let url = format!("{}/start", server.url());
+
+    let client = client_with(&["-r"]);
+    let response = client.request(&url, "GET", &[], None, None).await.unwrap();
+    let result = rustbuster::core::http_client::ScanResult::from_response(url.clone(), &response, 0);
+    assert_eq!(result.status_code, 200);
+    assert_eq!(result.final_url, Some(format!("{}/end", server.url())));
+    assert_eq!(result.redirect_count, 2);
+
+    let client = client_with(&[]);
+    let response = client.request(&url, "GET", &[], None, None).await.unwrap();
+    let result = rustbuster::core::http_client::ScanResult::from_response(url, &response, 0);
+    assert_eq!(result.status_code, 302);
+    assert_eq!((result.final_url, result.redirect_count), (None, 0));
+}
+
+// --max-redirects fails requests whose chain is longer, and only makes sense with -r
+#[tokio::test]
+async fn test_max_redirects_caps_chain() {
+    let server = redirect_chain_server();
+    let url = format!("{}/start", server.url());
+
+    assert!(client_with(&["-r", "--max-redirects", "1"]).request(&url, "GET", &[], None, None).await.is_err());
+    assert!(client_with(&["-r", "--max-redirects", "2"]).request(&url, "GET", &[], None, None).await.is_ok());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--max-redirects", "2"]).is_err());
+}
+
+// the first service that answers with an address wins; dead or chatty services are skipped
+#[tokio::test]
+async fn test_external_ip_from_stub_services() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/html" => MockResponse::new(200, "<html>rate limited</html>"),
+        "/ip" => MockResponse::new(200, "203.0.113.7\n"),
+        _ => MockResponse::new(404, ""),
+    });
+    let dead = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        format!("http://{}/ip", listener.local_addr().unwrap())
+    };
+    let html = format!("{}/html", server.url());
+    let ip = format!("{}/ip", server.url());
+
+    let client = client_with(&[]);
+    let found = client.check_external_ip_from(&[&dead, &html, &ip]).await.unwrap();
+    assert_eq!(found.to_string(), "203.0.113.7");
+    assert!(client.check_external_ip_from(&[&dead, &html]).await.is_err());
+}
@@ -3,7 +3,7 @@ use std::process::Command;
 #[test]
 fn test_help_command() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "--help"])
+        .args(["run", "--", "--help"])
         .output()
         .expect("Failed to execute command");
 
@@ -19,7 +19,7 @@ fn test_help_command() {
 #[test]
 fn test_version_command() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "--version"])
+        .args(["run", "--", "--version"])
         .output()
         .expect("Failed to execute command");
 
@@ -33,7 +33,7 @@ fn test_version_command() {
 #[test]
 fn test_dir_mode_requires_url() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "dir", "-w", "wordlist.txt"])
+        .args(["run", "--", "dir", "-w", "wordlist.txt"])
         .output()
         .expect("Failed to execute command");
     
@@ -45,7 +45,7 @@ fn test_dir_mode_requires_url() {
 #[test]
 fn test_dns_mode_requires_domain() {
     let output = Command::new("cargo")
-        .args(&["run", "--", "dns", "-w", "wordlist.txt"])
+        .args(["run", "--", "dns", "-w", "wordlist.txt"])
         .output()
         .expect("Failed to execute command");
     
@@ -53,3 +53,142 @@ fn test_dns_mode_requires_domain() {
     let stderr = String::from_utf8_lossy(&output.stderr);
     assert!(stderr.contains("error") || stderr.contains("USAGE") || stderr.contains("Usage"));
 }
+
+mod common;
+
+use common::{MockResponse, MockServer};
+
+fn run_dir_scan(server: &MockServer, extra: &[&str]) -> std::process::ExitStatus {
+    let wordlist = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(wordlist.path(), "admin\n").unwrap();
+
+    Command::new(env!("CARGO_BIN_EXE_rustbuster"))
+        .args(["dir", "-u", &server.url(), "-w", wordlist.path().to_str().unwrap(), "--no-tui", "-q"])
+        .args(extra)
+        .output()
+        .expect("Failed to execute command")
+        .status
+}
+
+// --fail-on-findings turns a hit into exit status 2, while a clean run still exits 0
+#[test]
+fn test_fail_on_findings_exit_code() {
+    let hit = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "ok"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    assert_eq!(run_dir_scan(&hit, &["--fail-on-findings"]).code(), Some(2));
+    assert_eq!(run_dir_scan(&hit, &[]).code(), Some(0));
+
+    let miss = MockServer::start(|_| MockResponse::new(404, "not found"));
+    assert_eq!(run_dir_scan(&miss, &["--fail-on-findings"]).code(), Some(0));
+}
+
+// fatal errors exit with 1
+#[test]
+fn test_fatal_error_exit_code() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rustbuster"))
+        .args(["dir", "-u", "http://127.0.0.1:9", "-w", "/nonexistent/wordlist.txt", "--no-tui", "-q"])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+fn url_only_lines(server: &MockServer, extra: &[&str]) -> Vec<String> {
+    let wordlist = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(wordlist.path(), "admin\nsecret\nlogin\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rustbuster"))
+        .args(["dir", "-u", &server.url(), "-w", wordlist.path().to_str().unwrap(), "--no-tui"])
+        .args(extra)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines: Vec<String> = stdout.lines().map(str::to_string).collect();
+    lines.sort();
+    lines
+}
+
+// --url-only prints nothing but the URL of each hit, and only those --match-regex keeps
+#[test]
+fn test_url_only_prints_bare_urls() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin panel"),
+        "/secret" => MockResponse::new(200, "api token here"),
+        _ => MockResponse::new(404, "not found"),
+    });
+
+    assert_eq!(
+        url_only_lines(&server, &["--url-only"]),
+        vec![format!("{}/admin", server.url()), format!("{}/secret", server.url())]
+    );
+    assert_eq!(
+        url_only_lines(&server, &["--url-only", "--match-regex", "token"]),
+        vec![format!("{}/secret", server.url())]
+    );
+    assert_eq!(
+        url_only_lines(&server, &["--raw", "--filter-regex", "token"]),
+        vec![format!("{}/admin", server.url())]
+    );
+}
+
+// Without -x the %EXT% words are not requested, and the warning says how many were skipped
+#[test]
+fn test_ext_placeholder_words_skipped_with_warning() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(wordlist.path(), "admin\nconfig.%EXT%.bak\nindex.%EXT%\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rustbuster"))
+        .args(["dir", "-u", &server.url(), "-w", wordlist.path().to_str().unwrap(), "--no-tui", "-q"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Skipping 2 words containing %EXT%"), "{}", stderr);
+    assert!(!server.paths().iter().any(|path| path.contains("EXT")));
+    assert!(server.paths().contains(&"/admin".to_string()));
+}
+
+// A target typed without a scheme is scanned over http, with one warning saying so
+#[test]
+fn test_missing_scheme_warned_once() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let wordlist = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(wordlist.path(), "admin\n").unwrap();
+    let host = server.url().trim_start_matches("http://").to_string();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rustbuster"))
+        .args(["dir", "-u", &host, "-w", wordlist.path().to_str().unwrap(), "--no-tui", "-q"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stderr.matches("No scheme in").count(), 1, "{}", stderr);
+    assert!(server.paths().contains(&"/admin".to_string()));
+}
+
+// --on-hit commands share rustbuster's stdout, so their output can be piped on
+#[test]
+fn test_on_hit_output_reaches_stdout() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "ok"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let wordlist = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(wordlist.path(), "admin\nmissing\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rustbuster"))
+        .args(["dir", "-u", &server.url(), "-w", wordlist.path().to_str().unwrap(), "--no-tui", "-q"])
+        .args(["--on-hit", "echo hook-saw {url}"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("hook-saw {}/admin", server.url())), "{}", stdout);
+}
@@ -0,0 +1,450 @@
+//! Tests for result output files
+
+mod common;
+
+use clap::Parser;
+use common::{MockResponse, MockServer};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::{ScanTarget, Scanner};
+use tempfile::TempDir;
+
+// jsonl output holds one standalone JSON object per result
+#[tokio::test]
+async fn test_jsonl_lines_parse_independently() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(301, "").header("Location", "/admin/"),
+        _ => MockResponse::new(200, "ok"),
+    });
+    let dir = TempDir::new().unwrap();
+    let output = dir.path().join("results.jsonl");
+
+    let args = CommonArgs::try_parse_from([
+        "rustbuster",
+        "-q",
+        "-o",
+        output.to_str().unwrap(),
+        "--output-format",
+        "jsonl",
+    ])
+    .unwrap();
+
+    let targets = ["admin", "login", "backup"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+    let scanner = Scanner::new_from_common(args).unwrap();
+    scanner.scan_urls(targets).await.unwrap();
+
+    let content = std::fs::read_to_string(&output).unwrap();
+    let mut urls: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            value["url"].as_str().unwrap().to_string()
+        })
+        .collect();
+    urls.sort();
+
+    let expected: Vec<String> = ["admin", "backup", "login"]
+        .iter()
+        .map(|path| format!("{}/{}", server.url(), path))
+        .collect();
+    assert_eq!(urls, expected);
+}
+
+// --save-bodies writes one file per finding and skips other responses
+#[tokio::test]
+async fn test_save_bodies_writes_file_per_finding() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin panel"),
+        "/login" => MockResponse::new(200, "login form"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let bodies = dir.path().join("bodies");
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "-q", "--save-bodies", bodies.to_str().unwrap()]).unwrap();
+    let targets = ["admin", "login", "missing"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+    let scanner = Scanner::new_from_common(args).unwrap();
+    scanner.scan_urls(targets).await.unwrap();
+
+    let mut saved: Vec<(String, String)> = std::fs::read_dir(&bodies)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_str().unwrap().to_string();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect();
+    saved.sort();
+
+    assert_eq!(saved.len(), 2);
+    assert!(saved[0].0.contains("_admin_"), "{}", saved[0].0);
+    assert_eq!(saved[0].1, "admin panel");
+    assert!(saved[1].0.contains("_login_"), "{}", saved[1].0);
+    assert_eq!(saved[1].1, "login form");
+}
+
+// --save-bodies skips responses a filter hides, and a body that cannot be written does not
+// fail the request
+#[tokio::test]
+async fn test_save_bodies_only_for_reported_results() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin panel"),
+        "/login" => MockResponse::new(200, "login form"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let bodies = dir.path().join("bodies");
+    let targets = || -> Vec<ScanTarget> {
+        ["admin", "login", "missing"]
+            .iter()
+            .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+            .collect()
+    };
+    let scanner = |extra: &[&str]| {
+        let mut args = vec!["rustbuster", "-q", "--save-bodies", bodies.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Scanner::new_from_common(CommonArgs::try_parse_from(args).unwrap()).unwrap()
+    };
+
+    scanner(&["--filter-regex", "login"]).scan_urls(targets()).await.unwrap();
+    let saved: Vec<String> = std::fs::read_dir(&bodies)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(saved.len(), 1);
+    assert!(saved[0].contains("_admin_"), "{}", saved[0]);
+
+    let failing = scanner(&["--max-errors", "1"]);
+    std::fs::remove_dir_all(&bodies).unwrap();
+    std::fs::write(&bodies, "not a directory").unwrap();
+    assert_eq!(failing.scan_urls(targets()).await.unwrap().len(), 2);
+}
+
+// long URLs get bounded names that stay distinct
+#[test]
+fn test_body_file_names_are_bounded_and_unique() {
+    use rustbuster::utils::body_store::file_name;
+
+    let base = format!("http://example.com/{}", "a".repeat(300));
+    let first = file_name(&format!("{}1", base));
+    let second = file_name(&format!("{}2", base));
+
+    assert!(first.len() < 130, "{}", first);
+    assert_ne!(first, second);
+}
+
+// each watched status alerts once, whatever the order and however often it repeats
+#[test]
+fn test_first_hit_fires_once_per_code() {
+    use rustbuster::output::handler::FirstHitNotifier;
+
+    let notifier = FirstHitNotifier::new(vec![200, 403]);
+    assert!(notifier.first_hit(200));
+    assert!(!notifier.first_hit(200));
+    assert!(!notifier.first_hit(404));
+    assert!(notifier.first_hit(403));
+    assert!(!notifier.first_hit(403));
+}
+
+// --notify-on takes a comma-separated list
+#[test]
+fn test_notify_on_parses_codes() {
+    let args = CommonArgs::try_parse_from(["rustbuster", "--notify-on", "200,401"]).unwrap();
+    assert_eq!(args.notify_on, vec![200, 401]);
+}
+
+// commas, quotes and line breaks in fields survive a CSV round trip
+#[tokio::test]
+async fn test_csv_fields_are_escaped() {
+    let location = "/search?q=a,b&tag=\"x\"";
+    let server = MockServer::start(move |req| match req.path.as_str() {
+        "/old" => MockResponse::new(301, "").header("Location", location),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let output = dir.path().join("results.csv");
+
+    let args = CommonArgs::try_parse_from([
+        "rustbuster",
+        "-q",
+        "-o",
+        output.to_str().unwrap(),
+        "--output-format",
+        "csv",
+    ])
+    .unwrap();
+    let scanner = Scanner::new_from_common(args).unwrap();
+    let results = scanner
+        .scan_urls(vec![ScanTarget::new(format!("{}/old", server.url()), None)])
+        .await
+        .unwrap();
+    scanner.finish(results.len(), &results).unwrap();
+
+    let mut reader = csv::Reader::from_path(&output).unwrap();
+    let column = reader.headers().unwrap().iter().position(|h| h == "Redirect Location").unwrap();
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].len(), 12);
+    assert_eq!(&rows[0][column], location);
+}
+
+// the console and TUI paths write byte-identical files for the same result, headers and
+// fingerprints included
+#[test]
+fn test_console_and_tui_files_match() {
+    use rustbuster::core::http_client::ScanResult;
+    use rustbuster::output::tui::{write_results_to_file, TuiOutput, TuiResult, TuiState};
+    use rustbuster::output::OutputHandler;
+
+    let scan = ScanResult {
+        url: "http://example.com/old".to_string(),
+        status_code: 301,
+        content_length: 12,
+        redirect_location: Some("/new?a=1,2".to_string()),
+        body: None,
+        content_type: Some("text/html".to_string()),
+        server: Some("nginx".to_string()),
+        duration_ms: 42,
+        source: None,
+        golden_diff: None,
+        ttfb_ms: None,
+        body_truncated_at: None,
+        allowed_methods: Some("GET, POST".to_string()),
+        similarity: None,
+        auth_scheme: None,
+        final_url: Some("http://example.com/new?a=1,2".to_string()),
+        redirect_count: 1,
+        headers: [("X-Powered-By".to_string(), "PHP/8".to_string())].into_iter().collect(),
+        technologies: vec!["PHP".to_string()],
+    };
+    let tui = TuiResult::from(scan.clone());
+
+    let dir = TempDir::new().unwrap();
+    for format in ["json", "jsonl", "csv", "plain"] {
+        let console_path = dir.path().join(format!("console.{}", format));
+        let handler = OutputHandler::new(
+            Some(console_path.to_str().unwrap().to_string()),
+            true,
+            format.to_string(),
+            false,
+        );
+        handler.print_result(&scan, false);
+        handler.finalize().unwrap();
+
+        let tui_path = dir.path().join(format!("tui.{}", format));
+        let mut state = TuiState::new(
+            "dir".to_string(),
+            "http://example.com".to_string(),
+            "words.txt".to_string(),
+            1,
+            1,
+        );
+        state.add_result(tui.clone());
+        let output = TuiOutput {
+            file: Some(tui_path.to_str().unwrap().to_string()),
+            format: format.to_string(),
+            json_metadata: false,
+            append: false,
+        };
+        write_results_to_file(&state, &output).unwrap();
+
+        let console = std::fs::read_to_string(&console_path).unwrap();
+        assert_eq!(console, std::fs::read_to_string(&tui_path).unwrap(), "{}", format);
+        assert!(console.contains("42"), "{} lacks the duration: {}", format, console);
+        if format != "plain" {
+            assert!(console.contains("PHP/8"), "{} lacks the headers: {}", format, console);
+        }
+    }
+}
+
+// --output-append adds each scan's results to the json array or csv rows already on disk
+#[tokio::test]
+async fn test_output_append_keeps_earlier_scans() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" | "/login" => MockResponse::new(200, "ok"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+
+    for format in ["json", "csv"] {
+        let output = dir.path().join(format!("results.{}", format));
+        for path in ["admin", "login"] {
+            let args = CommonArgs::try_parse_from([
+                "rustbuster",
+                "-q",
+                "-o",
+                output.to_str().unwrap(),
+                "--output-format",
+                format,
+                "--output-append",
+            ])
+            .unwrap();
+            // scan_urls writes the file when it finishes
+            let scanner = Scanner::new_from_common(args).unwrap();
+            scanner
+                .scan_urls(vec![ScanTarget::new(format!("{}/{}", server.url(), path), None)])
+                .await
+                .unwrap();
+        }
+
+        let urls: Vec<String> = if format == "json" {
+            let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+            value.as_array().unwrap().iter().map(|r| r["url"].as_str().unwrap().to_string()).collect()
+        } else {
+            let mut reader = csv::Reader::from_path(&output).unwrap();
+            reader.records().map(|row| row.unwrap()[0].to_string()).collect()
+        };
+        assert_eq!(urls, vec![format!("{}/admin", server.url()), format!("{}/login", server.url())], "{}", format);
+    }
+}
+
+// --output-append needs an output file and cannot merge into a --json-metadata object
+#[test]
+fn test_output_append_requires_plain_output() {
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--output-append"]).is_err());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "-o", "out.json", "--output-append", "--json-metadata"]).is_err());
+}
+
+// json and csv files are written through a temporary file that is renamed into place
+#[test]
+fn test_output_writes_are_atomic() {
+    use rustbuster::output::serialize::{self, ResultRecord};
+
+    let dir = TempDir::new().unwrap();
+    let record = ResultRecord {
+        url: "http://example.com/admin".to_string(),
+        status_code: 200,
+        content_length: 5,
+        redirect_location: None,
+        content_type: None,
+        server: None,
+        duration_ms: 3,
+        source: None,
+        golden_diff: None,
+        allowed_methods: None,
+        auth_scheme: None,
+        final_url: None,
+        redirect_count: 0,
+        headers: Default::default(),
+        technologies: Vec::new(),
+        body: None,
+    };
+    let entries = || -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+
+    let records = vec![record];
+    let json = dir.path().join("results.json");
+    serialize::write_json(&records, json.to_str().unwrap(), false).unwrap();
+    serialize::write_csv(&records, dir.path().join("results.csv").to_str().unwrap(), false).unwrap();
+    assert_eq!(entries(), vec!["results.csv", "results.json"]);
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+    assert_eq!(value[0]["url"], "http://example.com/admin");
+
+    // A failed write leaves the old file as it was and cleans up after itself
+    std::fs::write(&json, "{\"not\": \"an array\"}").unwrap();
+    assert!(serialize::write_json(&records, json.to_str().unwrap(), true).is_err());
+    assert_eq!(std::fs::read_to_string(&json).unwrap(), "{\"not\": \"an array\"}");
+    assert_eq!(entries(), vec!["results.csv", "results.json"]);
+}
+
+// colors need a terminal and are turned off by --no-color or a non-empty NO_COLOR
+#[test]
+fn test_color_enabled() {
+    use rustbuster::output::handler::color_enabled;
+
+    assert!(color_enabled(false, None, true));
+    assert!(color_enabled(false, Some("".into()), true));
+    assert!(!color_enabled(false, Some("1".into()), true));
+    assert!(!color_enabled(true, None, true));
+    assert!(!color_enabled(false, None, false));
+}
+
+// with color turned off, as NO_COLOR does, result lines carry no escape sequences
+#[test]
+fn test_no_color_result_line() {
+    use rustbuster::core::http_client::ScanResult;
+    use rustbuster::output::OutputHandler;
+
+    let result = ScanResult {
+        url: "http://example.com/admin".to_string(),
+        status_code: 403,
+        content_length: 10,
+        redirect_location: Some("/login".to_string()),
+        body: None,
+        content_type: Some("text/html".to_string()),
+        server: Some("nginx".to_string()),
+        duration_ms: 5,
+        source: None,
+        golden_diff: None,
+        ttfb_ms: None,
+        body_truncated_at: None,
+        allowed_methods: None,
+        similarity: None,
+        auth_scheme: None,
+        final_url: None,
+        redirect_count: 0,
+        headers: Default::default(),
+        technologies: Vec::new(),
+    };
+    colored::control::set_override(false);
+    let line = OutputHandler::new(None, false, "plain".to_string(), false).result_line(&result);
+    colored::control::unset_override();
+    assert!(!line.contains('\x1b'), "{:?}", line);
+    assert!(line.starts_with("http://example.com/admin [403 Forbidden]"), "{}", line);
+}
+
+// --include-body puts each hit's body into json and jsonl output, and only then
+#[tokio::test]
+async fn test_include_body_in_json_output() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "<h1>admin panel</h1>"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+
+    for (format, include) in [("json", true), ("jsonl", true), ("json", false)] {
+        let output = dir.path().join(format!("results-{}.{}", include, format));
+        let mut flags = vec!["rustbuster", "-q", "-o", output.to_str().unwrap(), "--output-format", format];
+        if include {
+            flags.push("--include-body");
+        }
+        let scanner = Scanner::new_from_common(CommonArgs::try_parse_from(flags).unwrap()).unwrap();
+        scanner
+            .scan_urls(vec![ScanTarget::new(format!("{}/admin", server.url()), None)])
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(&output).unwrap();
+        let value: serde_json::Value = match format {
+            "json" => serde_json::from_str::<serde_json::Value>(&content).unwrap()[0].clone(),
+            _ => serde_json::from_str(content.lines().next().unwrap()).unwrap(),
+        };
+        if include {
+            assert_eq!(value["body"], "<h1>admin panel</h1>", "{}", content);
+        } else {
+            assert!(value.get("body").is_none(), "{}", content);
+        }
+    }
+}
+
+// the console progress bar shows an ETA and the request rate
+#[test]
+fn test_progress_template_has_eta_and_rate() {
+    use rustbuster::output::progress::{progress_style, PROGRESS_TEMPLATE};
+
+    assert!(PROGRESS_TEMPLATE.contains("{eta}"));
+    assert!(PROGRESS_TEMPLATE.contains("{rate}"));
+    // the template parses and the rate key is registered
+    let _ = progress_style();
+}
@@ -0,0 +1,136 @@
+//! Tests for the shared recursion queue
+
+mod common;
+
+use common::{common_args, MockResponse, MockServer};
+use rustbuster::core::{ScanTarget, Scanner};
+use rustbuster::modes::recursion::Recursion;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+
+fn targets_under(base: &Url, words: &[&str]) -> Vec<ScanTarget> {
+    words
+        .iter()
+        .map(|word| ScanTarget::new(base.join(word).unwrap().to_string(), None))
+        .collect()
+}
+
+fn word_requests(server: &MockServer) -> Vec<String> {
+    let mut paths: Vec<String> = server
+        .paths()
+        .into_iter()
+        .filter(|path| !path.contains("rustbuster-"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+// one scanner serves every directory and the queue stops at the depth cap
+#[tokio::test]
+async fn test_recursion_reuses_scanner_and_respects_depth() {
+    let server = MockServer::start(|req| {
+        if req.path.ends_with("/deeper") {
+            let location = format!("{}/", req.path);
+            MockResponse::new(301, "").header("Location", &location)
+        } else {
+            MockResponse::new(404, "not found")
+        }
+    });
+
+    let common = common_args(&[]);
+    let scanner = Scanner::new_from_common(common.clone()).unwrap();
+    let recursion = Recursion {
+        common: &common,
+        max_depth: 1,
+        per_dir_budget: None,
+        stop_on_first: false,
+    };
+
+    let start = format!("{}/", server.url());
+    let results = recursion
+        .run(&scanner, vec![start], |base| targets_under(base, &["deeper", "x"]))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        word_requests(&server),
+        vec!["/deeper", "/deeper/deeper", "/deeper/x", "/x"]
+    );
+    assert_eq!(results.iter().filter(|r| r.status_code == 301).count(), 2);
+
+    let counts = scanner.status_counts();
+    assert_eq!(counts.get("301"), Some(&2));
+    assert_eq!(counts.get("404"), Some(&2));
+}
+
+// directories scanned side by side share the --threads limit instead of each getting their own
+#[tokio::test]
+async fn test_recursion_keeps_global_thread_limit() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let server = {
+        let in_flight = Arc::clone(&in_flight);
+        let peak = Arc::clone(&peak);
+        MockServer::start(move |req| {
+            if req.path.contains("rustbuster-") {
+                return MockResponse::new(404, "not found");
+            }
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            MockResponse::new(404, "not found")
+        })
+    };
+
+    let common = common_args(&["-t", "2"]);
+    let scanner = Scanner::new_from_common(common.clone()).unwrap();
+    let recursion = Recursion {
+        common: &common,
+        max_depth: 0,
+        per_dir_budget: None,
+        stop_on_first: false,
+    };
+
+    let starts = vec![format!("{}/one/", server.url()), format!("{}/two/", server.url())];
+    recursion
+        .run(&scanner, starts, |base| targets_under(base, &["a", "b", "c", "d"]))
+        .await
+        .unwrap();
+
+    assert_eq!(word_requests(&server).len(), 8);
+    assert!(peak.load(Ordering::SeqCst) <= 2, "peak {}", peak.load(Ordering::SeqCst));
+}
+
+// --stop-on-first drops a directory's remaining requests at its first hit but still descends
+#[tokio::test]
+async fn test_recursion_stop_on_first() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(301, "").header("Location", "/admin/"),
+        "/a" | "/b" | "/admin/a" | "/admin/b" => MockResponse::new(200, "hit"),
+        _ => MockResponse::new(404, "not found"),
+    });
+
+    // One thread, so the words are requested in order
+    let common = common_args(&["-t", "1"]);
+    let scanner = Scanner::new_from_common(common.clone()).unwrap();
+    let recursion = Recursion {
+        common: &common,
+        max_depth: 1,
+        per_dir_budget: None,
+        stop_on_first: true,
+    };
+
+    let start = format!("{}/", server.url());
+    let results = recursion
+        .run(&scanner, vec![start], |base| targets_under(base, &["admin", "a", "b", "c"]))
+        .await
+        .unwrap();
+
+    assert_eq!(word_requests(&server), vec!["/admin", "/admin/a", "/admin/admin"]);
+    let mut found: Vec<String> = results.iter().map(|r| r.url.replace(&server.url(), "")).collect();
+    found.sort();
+    assert_eq!(found, vec!["/admin", "/admin/a"]);
+}
@@ -0,0 +1,218 @@
+//! Tests for HTML and Markdown reports
+
+mod common;
+
+use clap::Parser;
+use common::{MockResponse, MockServer};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::{ScanTarget, Scanner};
+use rustbuster::utils::report;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// a .md report holds a findings table with one row per result and escaped pipes
+#[tokio::test]
+async fn test_markdown_report_has_table_row_per_finding() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin"),
+        "/a%7Cb" => MockResponse::new(403, "forbidden"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("report.md");
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "-q", "--report", path.to_str().unwrap()]).unwrap();
+    let targets = ["admin", "a%7Cb", "missing"]
+        .iter()
+        .map(|p| ScanTarget::new(format!("{}/{}", server.url(), p), None))
+        .collect();
+    let results = Scanner::new_from_common(args.clone())
+        .unwrap()
+        .scan_urls(targets)
+        .await
+        .unwrap();
+
+    // A raw pipe in a URL must not split the table cell
+    let mut results = results;
+    let admin = results.iter_mut().find(|r| r.url.ends_with("/admin")).unwrap();
+    admin.url = format!("{}/x|y", server.url());
+
+    report::write_report(&args, &server.url(), &results, Duration::from_secs(42)).unwrap();
+    let markdown = std::fs::read_to_string(&path).unwrap();
+
+    assert!(markdown.contains("| URL | Status | Size | Redirect |"), "{}", markdown);
+    assert!(markdown.contains("- **Scan Duration:** 42s"), "{}", markdown);
+    assert!(markdown.contains("/x\\|y"), "{}", markdown);
+
+    let rows: Vec<&str> = markdown
+        .lines()
+        .skip_while(|line| !line.starts_with("| URL |"))
+        .skip(2)
+        .filter(|line| line.starts_with("| "))
+        .collect();
+    assert_eq!(rows.len(), 2, "{}", markdown);
+}
+
+// --report-format overrides the extension
+#[tokio::test]
+async fn test_report_format_flag_overrides_extension() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("report.txt");
+
+    let args = CommonArgs::try_parse_from([
+        "rustbuster",
+        "--report",
+        path.to_str().unwrap(),
+        "--report-format",
+        "md",
+    ])
+    .unwrap();
+    report::write_report(&args, "http://example.com", &[], Duration::from_secs(1)).unwrap();
+
+    let markdown = std::fs::read_to_string(&path).unwrap();
+    assert!(markdown.starts_with("# Rustbuster Scan Report"));
+}
+
+fn result_with_status(index: usize, status_code: u16) -> rustbuster::core::http_client::ScanResult {
+    rustbuster::core::http_client::ScanResult {
+        url: format!("http://example.com/{}", index),
+        status_code,
+        content_length: 0,
+        redirect_location: None,
+        body: None,
+        content_type: None,
+        server: None,
+        duration_ms: 0,
+        source: None,
+        golden_diff: None,
+        ttfb_ms: None,
+        body_truncated_at: None,
+        allowed_methods: None,
+        similarity: None,
+        auth_scheme: None,
+        final_url: None,
+        redirect_count: 0,
+        headers: Default::default(),
+        technologies: Vec::new(),
+    }
+}
+
+// the summary line counts only findings, grouped by status in numeric order
+#[test]
+fn test_summary_line_format() {
+    let args = CommonArgs::try_parse_from(["rustbuster", "-s", "200,301,403"]).unwrap();
+    let results: Vec<_> = (0..1234)
+        .map(|i| {
+            let status = match i {
+                0..=2 => 200,
+                3..=4 => 301,
+                5..=6 => 403,
+                _ => 404,
+            };
+            result_with_status(i, status)
+        })
+        .collect();
+
+    assert_eq!(
+        report::summary_line(&args, "http://example.com/", &results, Duration::from_secs(42)),
+        "example.com: 1234 scanned, 7 found [200:3 301:2 403:2] in 00:42"
+    );
+    assert_eq!(
+        report::summary_line(&args, "http://example.com", &[], Duration::from_secs(3725)),
+        "example.com: 0 scanned, 0 found [] in 1:02:05"
+    );
+}
+
+// the HTML report summarises results per status code and per content type
+#[test]
+fn test_html_report_has_breakdowns() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("report.html");
+    let args = CommonArgs::try_parse_from(["rustbuster", "--report", path.to_str().unwrap()]).unwrap();
+
+    let mut results: Vec<_> = [200, 200, 200, 403]
+        .iter()
+        .enumerate()
+        .map(|(index, status)| result_with_status(index, *status))
+        .collect();
+    results[0].content_type = Some("text/html; charset=utf-8".to_string());
+    results[1].content_type = Some("TEXT/HTML".to_string());
+    results[2].content_type = Some("application/json".to_string());
+    report::write_report(&args, "http://example.com", &results, Duration::from_secs(1)).unwrap();
+
+    let html = std::fs::read_to_string(&path).unwrap();
+    let compact: String = html.split_whitespace().collect::<Vec<_>>().join(" ");
+    assert!(compact.contains("Status Codes"), "{}", html);
+    assert!(compact.contains("<td>200</td> <td>3</td>"), "{}", html);
+    assert!(compact.contains(r#"<div class="bar success" style="width: 75.0%"></div>"#), "{}", html);
+    assert!(compact.contains("<td>403</td> <td>1</td>"), "{}", html);
+    assert!(compact.contains("<td>text/html</td> <td>2</td>"), "{}", html);
+    assert!(compact.contains("<td>application/json</td> <td>1</td>"), "{}", html);
+    assert!(compact.contains("<td>unknown</td> <td>1</td>"), "{}", html);
+}
+
+// timing stats use the rounded mean and the nearest-rank 95th percentile
+#[test]
+fn test_timing_stats() {
+    use rustbuster::utils::report::TimingStats;
+
+    let stats = TimingStats::from_durations((1..=100).rev()).unwrap();
+    assert_eq!(stats, TimingStats { min_ms: 1, avg_ms: 51, max_ms: 100, p95_ms: 95 });
+
+    let stats = TimingStats::from_durations([30, 10, 20]).unwrap();
+    assert_eq!(stats, TimingStats { min_ms: 10, avg_ms: 20, max_ms: 30, p95_ms: 30 });
+    assert_eq!(stats.to_string(), "min 10ms, avg 20ms, max 30ms, p95 30ms");
+
+    assert_eq!(TimingStats::from_durations(Vec::new()), None);
+}
+
+// the HTML report shows the response times of its results
+#[test]
+fn test_html_report_has_timing() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("report.html");
+    let args = CommonArgs::try_parse_from(["rustbuster", "--report", path.to_str().unwrap()]).unwrap();
+
+    let mut results: Vec<_> = (0..4).map(|index| result_with_status(index, 200)).collect();
+    for (result, duration_ms) in results.iter_mut().zip([40, 10, 400, 30]) {
+        result.duration_ms = duration_ms;
+    }
+    report::write_report(&args, "http://example.com", &results, Duration::from_secs(1)).unwrap();
+
+    let html = std::fs::read_to_string(&path).unwrap();
+    let compact: String = html.split_whitespace().collect::<Vec<_>>().join(" ");
+    for (label, value) in [("Min", "10ms"), ("Avg", "120ms"), ("Max", "400ms"), ("P95", "400ms")] {
+        let card = format!(r#"{} Response</div> <div class="stat-value">{}</div>"#, label, value);
+        assert!(compact.contains(&card), "{}", html);
+    }
+}
+
+// a TUI scan keeps what it showed, so the report can be written once the TUI closes
+#[tokio::test]
+async fn test_report_from_tui_scan() {
+    use rustbuster::output::tui::{PauseGate, TuiSender};
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("report.md");
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "-q", "--report", path.to_str().unwrap()]).unwrap();
+    let scanner = Scanner::new_from_common(args.clone()).unwrap();
+    let targets = ["admin", "missing"]
+        .iter()
+        .map(|p| ScanTarget::new(format!("{}/{}", server.url(), p), None))
+        .collect();
+    let (tx, mut rx) = TuiSender::channel(16);
+    let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+    scanner.scan_urls_with_tui(targets, tx, PauseGate::new()).await.unwrap();
+    drain.await.unwrap();
+
+    let results = scanner.tui_results();
+    assert_eq!(results.len(), 1);
+    report::write_report(&args, &server.url(), &results, Duration::from_secs(1)).unwrap();
+    let markdown = std::fs::read_to_string(&path).unwrap();
+    assert!(markdown.contains(&format!("{}/admin", server.url())), "{}", markdown);
+}
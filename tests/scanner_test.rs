@@ -0,0 +1,628 @@
+//! Tests for the dir/fuzz scanner against a mock HTTP server
+
+mod common;
+
+use clap::Parser;
+use common::{common_args, wordlist_file, MockResponse, MockServer};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::scanner::{Baseline, ScanPass};
+use rustbuster::core::{ScanTarget, Scanner, Wordlist};
+use std::sync::Arc;
+use tempfile::NamedTempFile;
+
+// each result carries the wordlist that generated it
+#[tokio::test]
+async fn test_results_tagged_with_source_wordlist() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let first = wordlist_file(&["admin"]);
+    let second = wordlist_file(&["login", "backup"]);
+    let first_path = first.path().to_str().unwrap().to_string();
+    let second_path = second.path().to_str().unwrap().to_string();
+
+    let mut wordlist = Wordlist::from_file(&first_path).unwrap();
+    wordlist.merge(Wordlist::from_file(&second_path).unwrap());
+    assert_eq!(wordlist.source(0), Some(first_path.as_str()));
+    assert_eq!(wordlist.source(2), Some(second_path.as_str()));
+
+    let targets: Vec<ScanTarget> = wordlist
+        .expand_with_sources(&[])
+        .into_iter()
+        .map(|(word, source)| ScanTarget::new(format!("{}/{}", server.url(), word), Some(Arc::clone(&source))))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&[])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+    assert_eq!(results.len(), 3);
+
+    for result in &results {
+        let expected = if result.url.ends_with("/admin") { &first_path } else { &second_path };
+        assert_eq!(result.source.as_deref(), Some(expected.as_str()));
+    }
+}
+
+// --method, -H and -c are applied to every request
+#[tokio::test]
+async fn test_scanner_sends_custom_method_headers_and_cookies() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let args = common_args(&["--method", "POST", "-H", "X-Test: yes", "-c", "session=abc"]);
+    let scanner = Scanner::new_from_common(args).unwrap();
+
+    let target = ScanTarget::new(format!("{}/admin", server.url()), None);
+    scanner.scan_urls(vec![target]).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].header("x-test"), Some("yes"));
+    assert_eq!(requests[0].header("cookie"), Some("session=abc"));
+}
+
+// --golden-dir flags a result whose body changed since the previous run
+#[tokio::test]
+async fn test_scanner_reports_golden_diff() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&hits);
+    let server = MockServer::start(move |_| {
+        let body = if counter.fetch_add(1, Ordering::SeqCst) < 2 { "v1" } else { "v2" };
+        MockResponse::new(200, body)
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let url = format!("{}/page", server.url());
+
+    for expected in [None, None, Some("+1 -1 lines, 2 -> 2 bytes")] {
+        let args = common_args(&["--golden-dir", dir.path().to_str().unwrap()]);
+        let scanner = Scanner::new_from_common(args).unwrap();
+        let results = scanner.scan_urls(vec![ScanTarget::new(url.clone(), None)]).await.unwrap();
+        assert_eq!(results[0].golden_diff.as_deref(), expected);
+    }
+}
+
+// golden copies are only kept for findings, and a golden directory that cannot be written
+// does not turn a finding into a failed request
+#[tokio::test]
+async fn test_golden_dir_only_covers_findings() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin panel"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let golden = dir.path().join("golden");
+    let targets = || -> Vec<ScanTarget> {
+        ["admin", "missing", "other"]
+            .iter()
+            .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+            .collect()
+    };
+
+    let args = common_args(&["--golden-dir", golden.to_str().unwrap()]);
+    let results = Scanner::new_from_common(args).unwrap().scan_urls(targets()).await.unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(std::fs::read_dir(&golden).unwrap().count(), 1);
+
+    let args = common_args(&["--golden-dir", golden.to_str().unwrap(), "--max-errors", "1"]);
+    let scanner = Scanner::new_from_common(args).unwrap();
+    std::fs::remove_dir_all(&golden).unwrap();
+    std::fs::write(&golden, "not a directory").unwrap();
+    let results = scanner.scan_urls(targets()).await.unwrap();
+    assert_eq!(results.len(), 1);
+}
+
+// a redirect to the trailing-slash path is reported as a discovered directory
+#[tokio::test]
+async fn test_redirect_to_slash_reported_as_discovered_dir() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(301, "").header("Location", "/admin/"),
+        "/static/" => MockResponse::new(200, "ok"),
+        _ => MockResponse::new(404, "not found"),
+    });
+
+    let targets = ["admin", "static/", "missing"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&[])).unwrap();
+    let pass = ScanPass::default();
+    scanner.scan_pass(targets, &Baseline::default(), None, &pass).await;
+
+    let (_, mut dirs, _) = pass.into_parts();
+    dirs.sort();
+    assert_eq!(
+        dirs,
+        vec![format!("{}/admin/", server.url()), format!("{}/static/", server.url())]
+    );
+}
+
+// the plain-mode progress bar follows --no-progress and --quiet
+#[test]
+fn test_progress_bar_respects_flags() {
+    let args = CommonArgs::try_parse_from(["rustbuster"]).unwrap();
+    let scanner = Scanner::new_from_common(args).unwrap();
+    let pb = scanner.progress_bar(42).expect("progress bar should be shown");
+    assert_eq!(pb.length(), Some(42));
+
+    let args = CommonArgs::try_parse_from(["rustbuster", "--no-progress"]).unwrap();
+    assert!(Scanner::new_from_common(args).unwrap().progress_bar(42).is_none());
+
+    let scanner = Scanner::new_from_common(common_args(&[])).unwrap();
+    assert!(scanner.progress_bar(42).is_none());
+}
+
+// --show-timing captures time to first byte alongside the total
+#[tokio::test]
+async fn test_show_timing_captures_ttfb() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let url = format!("{}/page", server.url());
+
+    let scanner = Scanner::new_from_common(common_args(&["--show-timing"])).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url.clone(), None)]).await.unwrap();
+    let ttfb = results[0].ttfb_ms.expect("ttfb should be captured");
+    assert!(ttfb <= results[0].duration_ms);
+
+    let scanner = Scanner::new_from_common(common_args(&[])).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+    assert!(results[0].ttfb_ms.is_none());
+}
+
+// --head-first probes with HEAD and keeps the advertised size
+#[tokio::test]
+async fn test_head_first_uses_head_for_plain_scan() {
+    let server = MockServer::start(|_| MockResponse::new(200, "twelve bytes"));
+    let url = format!("{}/page", server.url());
+
+    let scanner = Scanner::new_from_common(common_args(&["--head-first"])).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+
+    let methods: Vec<String> = server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(methods, vec!["HEAD"]);
+    assert_eq!(results[0].content_length, 12);
+}
+
+// body-dependent options such as --match-regex keep using GET
+#[tokio::test]
+async fn test_head_first_uses_get_when_body_needed() {
+    let server = MockServer::start(|_| MockResponse::new(200, "admin panel"));
+    let url = format!("{}/page", server.url());
+
+    let args = common_args(&["--head-first", "--match-regex", "admin"]);
+    let scanner = Scanner::new_from_common(args).unwrap();
+    scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+
+    let methods: Vec<String> = server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(methods, vec!["GET"]);
+}
+
+// a server refusing HEAD gets the request again as GET
+#[tokio::test]
+async fn test_head_first_falls_back_on_405() {
+    let server = MockServer::start(|req| {
+        if req.method == "HEAD" {
+            MockResponse::new(405, "")
+        } else {
+            MockResponse::new(200, "ok")
+        }
+    });
+    let url = format!("{}/page", server.url());
+
+    let scanner = Scanner::new_from_common(common_args(&["--head-first"])).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+
+    let methods: Vec<String> = server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(methods, vec!["HEAD", "GET"]);
+    assert_eq!(results[0].status_code, 200);
+}
+
+// bodies past --max-body-size are cut off while the size keeps the header value
+#[tokio::test]
+async fn test_max_body_size_stops_reading_early() {
+    let large = "a".repeat(64 * 1024);
+    let server = MockServer::start(move |_| MockResponse::new(200, &large));
+    let url = format!("{}/big", server.url());
+
+    let args = common_args(&["--show-timing", "--max-body-size", "1024"]);
+    let scanner = Scanner::new_from_common(args).unwrap();
+    let results = scanner.scan_urls(vec![ScanTarget::new(url, None)]).await.unwrap();
+
+    assert_eq!(results[0].body_truncated_at, Some(1024));
+    assert_eq!(results[0].content_length, 64 * 1024);
+}
+
+// --probe-methods sends OPTIONS only for findings and keeps their Allow header
+#[tokio::test]
+async fn test_probe_methods_captures_allow_header() {
+    let server = MockServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("OPTIONS", _) => MockResponse::new(204, "").header("Allow", "GET, POST"),
+        (_, "/api") => MockResponse::new(200, "api"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let targets = ["api", "missing"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&["--probe-methods", "-e"])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    let api = results.iter().find(|r| r.url.ends_with("/api")).unwrap();
+    assert_eq!(api.allowed_methods.as_deref(), Some("GET, POST"));
+    let missing = results.iter().find(|r| r.url.ends_with("/missing")).unwrap();
+    assert_eq!(missing.allowed_methods, None);
+
+    let options: Vec<String> = server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "OPTIONS")
+        .map(|r| r.path)
+        .collect();
+    assert_eq!(options, vec!["/api"]);
+}
+
+fn wildcard_server() -> MockServer {
+    MockServer::start(|req| match req.path.as_str() {
+        "/real" => MockResponse::new(200, "a real page with other content"),
+        _ => MockResponse::new(200, "catch-all"),
+    })
+}
+
+fn wildcard_targets(server: &MockServer) -> Vec<ScanTarget> {
+    ["admin", "backup", "real"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect()
+}
+
+// results with the wildcard status and size are dropped while a differing page is kept
+#[tokio::test]
+async fn test_wildcard_baseline_filters_matching_results() {
+    let server = wildcard_server();
+
+    let mut scanner = Scanner::new_from_common(common_args(&[])).unwrap();
+    scanner.detect_wildcard(&server.url()).await.unwrap();
+    let results = scanner.scan_urls(wildcard_targets(&server)).await.unwrap();
+
+    let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+    assert_eq!(urls, vec![format!("{}/real", server.url())]);
+    assert_eq!(scanner.status_counts().get("200"), Some(&3));
+}
+
+// --wildcard keeps every result despite the baseline
+#[tokio::test]
+async fn test_wildcard_flag_keeps_matching_results() {
+    let server = wildcard_server();
+
+    let mut scanner = Scanner::new_from_common(common_args(&["--wildcard"])).unwrap();
+    scanner.detect_wildcard(&server.url()).await.unwrap();
+    let results = scanner.scan_urls(wildcard_targets(&server)).await.unwrap();
+
+    assert_eq!(results.len(), 3);
+}
+
+// 200s nearly identical to the soft-404 page are dropped while a distinct page is kept
+#[tokio::test]
+async fn test_similarity_threshold_drops_soft_404_pages() {
+    let soft_404 = |path: &str| {
+        format!(
+            "<html><body><h1>Oops</h1><p>Sorry, the page you were looking for could not be found on this server, please check the address and try again later</p><p>{}</p></body></html>",
+            path
+        )
+    };
+    let server = MockServer::start(move |req| match req.path.as_str() {
+        "/real" => MockResponse::new(200, "<html><body>Admin dashboard with users and settings</body></html>"),
+        path => MockResponse::new(200, &soft_404(path)),
+    });
+    let targets = ["old", "backup", "real"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let mut scanner = Scanner::new_from_common(common_args(&["--similarity-threshold", "0.9"])).unwrap();
+    scanner.calibrate_soft_404(&server.url()).await.unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+    assert_eq!(urls, vec![format!("{}/real", server.url())]);
+}
+
+// thresholds outside 0.0-1.0 are rejected
+#[test]
+fn test_similarity_threshold_must_be_a_ratio() {
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--similarity-threshold", "1.5"]).is_err());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--similarity-threshold", "0.9"]).is_ok());
+}
+
+// near-identical bodies score above 0.9 and unrelated ones well below
+#[test]
+fn test_token_similarity_scores() {
+    use rustbuster::utils::smart_404::token_similarity;
+
+    let page = "Sorry, the page you were looking for could not be found on this server today";
+    assert!(token_similarity(page, &format!("{} /old", page)) > 0.9);
+    assert!(token_similarity(page, "Admin dashboard with users and settings") < 0.1);
+    assert_eq!(token_similarity("", ""), 1.0);
+}
+
+// the schemes offered on a 401 are kept on the result and written to JSON output
+#[tokio::test]
+async fn test_www_authenticate_scheme_captured() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/intranet" => MockResponse::new(401, "")
+            .header("WWW-Authenticate", "NTLM")
+            .header("WWW-Authenticate", "Negotiate"),
+        "/admin" => MockResponse::new(401, "").header("WWW-Authenticate", "Basic realm=\"admin\""),
+        _ => MockResponse::new(200, "ok"),
+    });
+    let targets = ["intranet", "admin", "open"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let output = NamedTempFile::new().unwrap();
+    let path = output.path().to_str().unwrap();
+    let scanner = Scanner::new_from_common(common_args(&["-o", path, "--output-format", "jsonl"])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    let scheme = |suffix: &str| {
+        results.iter().find(|r| r.url.ends_with(suffix)).unwrap().auth_scheme.clone()
+    };
+    assert_eq!(scheme("/intranet").as_deref(), Some("NTLM, Negotiate"));
+    assert_eq!(scheme("/admin").as_deref(), Some("Basic"));
+    assert_eq!(scheme("/open"), None);
+
+    let written = std::fs::read_to_string(output.path()).unwrap();
+    let intranet = written
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|value| value["url"].as_str().unwrap().ends_with("/intranet"))
+        .unwrap();
+    assert_eq!(intranet["auth_scheme"], "NTLM, Negotiate");
+}
+
+/// A server that accepts connections and closes them straight away, counting each one
+fn dropping_server() -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let accepted = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let counter = Arc::clone(&accepted);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            drop(stream);
+        }
+    });
+    (url, accepted)
+}
+
+// --max-errors stops a scan against a dead server shortly after the limit and reports why
+#[tokio::test]
+async fn test_max_errors_aborts_scan() {
+    let (url, accepted) = dropping_server();
+    let targets = (0..500)
+        .map(|i| ScanTarget::new(format!("{}/{}", url, i), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&["-t", "2", "--max-errors", "10"])).unwrap();
+    let Err(err) = scanner.scan_urls(targets).await else {
+        panic!("the scan should have been aborted");
+    };
+    assert!(err.to_string().contains("--max-errors 10"), "{}", err);
+
+    let requests = accepted.load(std::sync::atomic::Ordering::SeqCst);
+    assert!((10..=20).contains(&requests), "{} requests", requests);
+}
+
+// the consecutive limit resets on every response
+#[test]
+fn test_consecutive_error_budget_resets_on_success() {
+    use rustbuster::core::error_budget::ErrorBudget;
+
+    let budget = ErrorBudget::new(None, Some(3));
+    budget.record_error();
+    budget.record_error();
+    budget.record_success();
+    budget.record_error();
+    budget.record_error();
+    assert!(!budget.is_exhausted());
+    assert!(budget.check().is_ok());
+
+    budget.record_error();
+    assert!(budget.is_exhausted());
+    assert!(budget.check().unwrap_err().to_string().contains("3 consecutive"));
+    assert_eq!(budget.errors(), 5);
+}
+
+/// Scans a fast and a slow page with `flags` and returns the paths that were kept
+async fn latency_filtered_paths(flags: &[&str]) -> Vec<String> {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/fast" => MockResponse::new(200, "fast"),
+        "/slow" => MockResponse::new(200, "slow").delay(std::time::Duration::from_millis(300)),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let targets = ["fast", "slow"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(flags)).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+    let mut paths: Vec<String> = results
+        .iter()
+        .map(|result| result.url.rsplit('/').next().unwrap().to_string())
+        .collect();
+    paths.sort();
+    paths
+}
+
+// --min-time keeps only the responses that were slow enough
+#[tokio::test]
+async fn test_min_time_keeps_slow_responses() {
+    assert_eq!(latency_filtered_paths(&["--min-time", "100"]).await, vec!["slow"]);
+}
+
+// --max-time drops the slow ones instead, and neither flag means both are kept
+#[tokio::test]
+async fn test_max_time_keeps_fast_responses() {
+    assert_eq!(latency_filtered_paths(&["--max-time", "100"]).await, vec!["fast"]);
+    assert_eq!(latency_filtered_paths(&[]).await, vec!["fast", "slow"]);
+}
+
+/// Scans a redirect-to-slash directory and a PHP file, returning what the scanner kept
+async fn classified_urls(flags: &[&str]) -> Vec<rustbuster::core::http_client::ScanResult> {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(301, "").header("Location", "/admin/"),
+        "/index.php" => MockResponse::new(200, "<?php ?>"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let targets = ["admin", "index.php"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let mut args = vec!["-s", "200,301"];
+    args.extend_from_slice(flags);
+    let scanner = Scanner::new_from_common(common_args(&args)).unwrap();
+    scanner.scan_urls(targets).await.unwrap()
+}
+
+// a redirect to the slashed path is a directory, a 200 on a .php path is a file
+#[tokio::test]
+async fn test_only_dirs_and_only_files() {
+    let all = classified_urls(&[]).await;
+    assert_eq!(all.len(), 2);
+    for result in &all {
+        assert_eq!(rustbuster::core::scanner::is_directory(result), result.url.ends_with("/admin"));
+    }
+
+    let dirs = classified_urls(&["--only-dirs"]).await;
+    assert_eq!(dirs.len(), 1);
+    assert!(dirs[0].url.ends_with("/admin"));
+
+    let files = classified_urls(&["--only-files"]).await;
+    assert_eq!(files.len(), 1);
+    assert!(files[0].url.ends_with("/index.php"));
+
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--only-dirs", "--only-files"]).is_err());
+}
+
+// --show-headers keeps the named headers whatever their case and writes them to JSON and CSV
+#[tokio::test]
+async fn test_show_headers_captured() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/index.php" => MockResponse::new(200, "ok").header("X-Powered-By", "PHP/8").header("Via", "1.1 cache"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let target = || vec![ScanTarget::new(format!("{}/index.php", server.url()), None)];
+
+    let json = NamedTempFile::new().unwrap();
+    let flags = ["--show-headers", "x-powered-by,CF-Ray", "-o", json.path().to_str().unwrap(), "--output-format", "jsonl"];
+    let scanner = Scanner::new_from_common(common_args(&flags)).unwrap();
+    let results = scanner.scan_urls(target()).await.unwrap();
+    assert_eq!(results[0].headers.get("x-powered-by").map(String::as_str), Some("PHP/8"));
+    assert_eq!(results[0].headers.len(), 1);
+
+    let written = std::fs::read_to_string(json.path()).unwrap();
+    let value: serde_json::Value = serde_json::from_str(written.lines().next().unwrap()).unwrap();
+    assert_eq!(value["headers"]["x-powered-by"], "PHP/8");
+
+    let csv = NamedTempFile::new().unwrap();
+    let flags = ["--show-headers", "X-Powered-By,Via", "-o", csv.path().to_str().unwrap(), "--output-format", "csv"];
+    let scanner = Scanner::new_from_common(common_args(&flags)).unwrap();
+    let results = scanner.scan_urls(target()).await.unwrap();
+    scanner.finish(results.len(), &results).unwrap();
+    let written = std::fs::read_to_string(csv.path()).unwrap();
+    assert!(written.lines().next().unwrap().contains(",Headers"));
+    assert!(written.contains(",Via: 1.1 cache; X-Powered-By: PHP/8,"), "{}", written);
+}
+
+// --on-hit runs the command once per hit with the placeholders filled in, and the scan
+// waits for the commands before it returns, in the console and TUI paths alike
+#[tokio::test]
+async fn test_on_hit_runs_command_per_hit() {
+    use rustbuster::output::tui::{PauseGate, TuiSender};
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/a" | "/b" => MockResponse::new(200, "ok"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let targets = || {
+        ["a", "b", "c"]
+            .iter()
+            .map(|word| ScanTarget::new(format!("{}/{}", server.url(), word), None))
+            .collect::<Vec<_>>()
+    };
+
+    for tui in [false, true] {
+        let hits = NamedTempFile::new().unwrap();
+        let command = format!("sh -c 'echo \"$1\" >> {}' hook '{{url}} {{status}} {{size}}'", hits.path().display());
+        let scanner = Scanner::new_from_common(common_args(&["--on-hit", &command])).unwrap();
+
+        if tui {
+            let (tx, mut rx) = TuiSender::channel(16);
+            let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+            scanner.scan_urls_with_tui(targets(), tx, PauseGate::new()).await.unwrap();
+            drain.await.unwrap();
+        } else {
+            scanner.scan_urls(targets()).await.unwrap();
+        }
+
+        let mut lines: Vec<String> = std::fs::read_to_string(hits.path())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec![format!("{}/a 200 2", server.url()), format!("{}/b 200 2", server.url())],
+            "tui: {}",
+            tui
+        );
+    }
+}
+
+// placeholders are filled in once, so a URL holding the text {size} reaches the command as is
+#[tokio::test]
+async fn test_on_hit_leaves_placeholders_inside_the_url() {
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let hits = NamedTempFile::new().unwrap();
+    let command = format!("sh -c 'echo \"$1\" >> {}' hook '{{url}} {{status}}'", hits.path().display());
+    let scanner = Scanner::new_from_common(common_args(&["--on-hit", &command])).unwrap();
+
+    let url = format!("{}/x{{size}}", server.url());
+    scanner.scan_urls(vec![ScanTarget::new(url.clone(), None)]).await.unwrap();
+
+    let written = std::fs::read_to_string(hits.path()).unwrap();
+    assert_eq!(written.trim(), format!("{} 200", url));
+}
+
+// a TUI scan drops responses matching the wildcard baseline, like the console path
+#[tokio::test]
+async fn test_tui_scan_filters_wildcard_responses() {
+    use rustbuster::output::tui::{PauseGate, TuiMessage, TuiSender};
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "the real admin page"),
+        _ => MockResponse::new(200, "catch-all"),
+    });
+    let mut scanner = Scanner::new_from_common(common_args(&[])).unwrap();
+    scanner.detect_wildcard(&server.url()).await.unwrap();
+    let targets = ["admin", "login", "backup"]
+        .iter()
+        .map(|word| ScanTarget::new(format!("{}/{}", server.url(), word), None))
+        .collect();
+
+    let (tx, mut rx) = TuiSender::channel(16);
+    let shown = tokio::spawn(async move {
+        let mut urls = Vec::new();
+        while let Some(message) = rx.recv().await {
+            if let TuiMessage::Result(result) = message {
+                urls.push(result.url);
+            }
+        }
+        urls
+    });
+    scanner.scan_urls_with_tui(targets, tx, PauseGate::new()).await.unwrap();
+
+    assert_eq!(shown.await.unwrap(), vec![format!("{}/admin", server.url())]);
+}
@@ -0,0 +1,246 @@
+//! Tests for saving and resuming scan sessions
+
+mod common;
+
+use clap::Parser;
+use common::{dir_args, wordlist_file, MockResponse, MockServer};
+use rustbuster::modes::dir;
+use tempfile::{NamedTempFile, TempDir};
+
+// a resumed scan skips finished URLs and writes only new findings to the delta output
+#[tokio::test]
+async fn test_resumed_session_writes_only_new_findings() {
+    let home = TempDir::new().unwrap();
+    let sessions = home.path().join("sessions");
+    let sessions = sessions.to_str().unwrap();
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" | "/backup" => MockResponse::new(200, "found"),
+        _ => MockResponse::new(404, "not found"),
+    });
+
+    let first = wordlist_file(&["admin", "missing"]);
+    let args = dir_args(&server.url(), &first, &["--save-session", "campaign", "--session-dir", sessions]);
+    dir::run(args).await.unwrap();
+
+    let delta = home.path().join("delta.txt");
+    let second = wordlist_file(&["admin", "missing", "backup"]);
+    let args = dir_args(
+        &server.url(),
+        &second,
+        &[
+            "--resume-session",
+            "campaign",
+            "--session-dir",
+            sessions,
+            "--delta-output",
+            delta.to_str().unwrap(),
+        ],
+    );
+    dir::run(args).await.unwrap();
+
+    let paths = server.paths();
+    assert_eq!(paths.iter().filter(|p| *p == "/admin").count(), 1);
+    assert_eq!(paths.iter().filter(|p| *p == "/backup").count(), 1);
+
+    let written = std::fs::read_to_string(&delta).unwrap();
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with(&format!("{}/backup [200]", server.url())));
+}
+
+// saved sessions are listed by name and can be deleted
+#[test]
+fn test_list_and_delete_sessions() {
+    use rustbuster::utils::session::Session;
+
+    let home = TempDir::new().unwrap();
+    let dir = home.path().join("sessions");
+
+    assert!(Session::list_sessions(&dir).unwrap().is_empty());
+    for name in ["staging", "prod"] {
+        Session::new(name.to_string(), "http://example.com".to_string(), "words.txt".to_string(), 10)
+            .with_dir(dir.clone())
+            .save()
+            .unwrap();
+    }
+    assert_eq!(Session::list_sessions(&dir).unwrap(), vec!["prod", "staging"]);
+
+    Session::delete(&dir, "prod").unwrap();
+    assert_eq!(Session::list_sessions(&dir).unwrap(), vec!["staging"]);
+    assert!(Session::delete(&dir, "prod").is_err());
+}
+
+// the sessions subcommand parses list and delete
+#[test]
+fn test_sessions_subcommand_parses() {
+    use rustbuster::cli::{Cli, Commands, SessionsAction};
+
+    let cli = Cli::try_parse_from(["rustbuster", "sessions", "delete", "scan1", "--session-dir", "/tmp/s"]).unwrap();
+    match cli.command {
+        Commands::Sessions(args) => {
+            assert_eq!(args.session_dir.as_deref(), Some("/tmp/s"));
+            match args.action {
+                SessionsAction::Delete { name } => assert_eq!(name, "scan1"),
+                SessionsAction::List => panic!("expected delete"),
+            }
+        }
+        _ => panic!("expected the sessions subcommand"),
+    }
+    assert!(Cli::try_parse_from(["rustbuster", "sessions", "list"]).is_ok());
+}
+
+// a second run with the same --resume-state file only requests URLs the first did not finish
+#[tokio::test]
+async fn test_resume_state_skips_completed_urls() {
+    use rustbuster::cli::CommonArgs;
+    use rustbuster::core::{ScanTarget, Scanner};
+    use rustbuster::utils::checkpoint::{ScanState, STATE_VERSION};
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let state_path = dir.path().join("scan.rbstate");
+    let state = state_path.to_str().unwrap();
+    let targets = |paths: &[&str]| -> Vec<ScanTarget> {
+        paths
+            .iter()
+            .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+            .collect()
+    };
+    let scanner = || {
+        let args = CommonArgs::try_parse_from(["rustbuster", "-q", "--resume-state", state]).unwrap();
+        Scanner::new_from_common(args).unwrap()
+    };
+
+    scanner().scan_urls(targets(&["admin", "login"])).await.unwrap();
+    let saved = ScanState::load(state).unwrap();
+    assert_eq!(saved.version, STATE_VERSION);
+    assert_eq!(saved.completed.len(), 2);
+    assert_eq!(saved.results.len(), 1);
+    assert_eq!(saved.results[0].url, format!("{}/admin", server.url()));
+
+    scanner().scan_urls(targets(&["admin", "login", "backup"])).await.unwrap();
+    let paths = server.paths();
+    assert_eq!(paths.iter().filter(|p| *p == "/admin").count(), 1);
+    assert_eq!(paths.iter().filter(|p| *p == "/login").count(), 1);
+    assert_eq!(paths.iter().filter(|p| *p == "/backup").count(), 1);
+    assert_eq!(ScanState::load(state).unwrap().completed.len(), 3);
+}
+
+// fuzz targets that share one URL and differ only in their body are each resumed on their own
+#[tokio::test]
+async fn test_resume_state_keys_fuzz_targets_by_request() {
+    use rustbuster::cli::FuzzArgs;
+    use rustbuster::modes::fuzz;
+
+    let server = MockServer::start(|_| MockResponse::new(200, "ok"));
+    let dir = TempDir::new().unwrap();
+    let state_path = dir.path().join("fuzz.rbstate");
+    let url = format!("{}/api", server.url());
+    let run = |wordlist: &NamedTempFile| {
+        FuzzArgs::try_parse_from([
+            "fuzz",
+            "-u",
+            &url,
+            "-w",
+            wordlist.path().to_str().unwrap(),
+            "--method",
+            "POST",
+            "--data",
+            "q=FUZZ",
+            "--resume-state",
+            state_path.to_str().unwrap(),
+            "--no-tui",
+            "-q",
+        ])
+        .unwrap()
+    };
+
+    fuzz::run(run(&wordlist_file(&["admin", "login"]))).await.unwrap();
+    fuzz::run(run(&wordlist_file(&["admin", "login", "backup"]))).await.unwrap();
+
+    let mut bodies: Vec<String> = server.requests().into_iter().map(|r| r.body).collect();
+    bodies.sort();
+    assert_eq!(bodies, vec!["q=admin", "q=backup", "q=login"]);
+}
+
+// the TUI path records the state file too, and a resumed TUI scan skips what it holds
+#[tokio::test]
+async fn test_resume_state_in_tui_scan() {
+    use rustbuster::cli::CommonArgs;
+    use rustbuster::core::{ScanTarget, Scanner};
+    use rustbuster::output::tui::{PauseGate, TuiSender};
+    use rustbuster::utils::checkpoint::ScanState;
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "admin"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let state_path = dir.path().join("scan.rbstate");
+    let state = state_path.to_str().unwrap();
+    let targets = |paths: &[&str]| -> Vec<ScanTarget> {
+        paths
+            .iter()
+            .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+            .collect()
+    };
+    let tui_scan = |paths: &'static [&'static str]| {
+        let args = CommonArgs::try_parse_from(["rustbuster", "-q", "--resume-state", state]).unwrap();
+        let scanner = Scanner::new_from_common(args).unwrap();
+        let remaining = scanner.remaining_targets(targets(paths));
+        async move {
+            let (tx, mut rx) = TuiSender::channel(16);
+            let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+            scanner.scan_urls_with_tui(remaining, tx, PauseGate::new()).await.unwrap();
+            scanner.save_progress().unwrap();
+            drain.await.unwrap();
+        }
+    };
+
+    tui_scan(&["admin", "login"]).await;
+    let saved = ScanState::load(state).unwrap();
+    assert_eq!(saved.completed.len(), 2);
+    assert_eq!(saved.results.len(), 1);
+
+    tui_scan(&["admin", "login", "backup"]).await;
+    let paths = server.paths();
+    assert_eq!(paths.iter().filter(|p| *p == "/admin").count(), 1);
+    assert_eq!(paths.iter().filter(|p| *p == "/backup").count(), 1);
+    assert_eq!(ScanState::load(state).unwrap().completed.len(), 3);
+}
+
+// a TUI scan writes its findings to the delta output the same way
+#[tokio::test]
+async fn test_tui_scan_writes_delta_output() {
+    use rustbuster::cli::CommonArgs;
+    use rustbuster::core::{ScanTarget, Scanner};
+    use rustbuster::output::tui::{PauseGate, TuiSender};
+
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/admin" => MockResponse::new(200, "found"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let dir = TempDir::new().unwrap();
+    let delta = dir.path().join("delta.txt");
+    let args =
+        CommonArgs::try_parse_from(["rustbuster", "-q", "--delta-output", delta.to_str().unwrap()]).unwrap();
+    let scanner = Scanner::new_from_common(args).unwrap();
+    let targets = ["admin", "missing"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let (tx, mut rx) = TuiSender::channel(16);
+    let drain = tokio::spawn(async move { while rx.recv().await.is_some() {} });
+    scanner.scan_urls_with_tui(targets, tx, PauseGate::new()).await.unwrap();
+    drain.await.unwrap();
+
+    let written = std::fs::read_to_string(&delta).unwrap();
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with(&format!("{}/admin [200]", server.url())));
+}
@@ -0,0 +1,196 @@
+//! Tests for status code filtering and grouping
+
+mod common;
+
+use clap::Parser;
+use common::{common_args, MockResponse, MockServer};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::{ScanTarget, Scanner, StatusFilter};
+
+// exact codes are matched and counted as given by default
+#[test]
+fn test_status_filter_exact_by_default() {
+    let filter = StatusFilter::from_common(&common_args(&["-s", "200"]));
+    assert!(filter.matches(200));
+    assert!(!filter.matches(204));
+    assert_eq!(filter.group(204), "204");
+}
+
+// --normalize-status widens each code to its family
+#[test]
+fn test_status_filter_matches_families_when_normalized() {
+    let filter = StatusFilter::from_common(&common_args(&["-s", "200", "--normalize-status"]));
+    assert!(filter.matches(204));
+    assert!(!filter.matches(301));
+    assert_eq!(filter.group(204), "2xx");
+
+    let filter = StatusFilter::from_common(&common_args(&["-n", "404", "--normalize-status"]));
+    assert!(!filter.matches(403));
+    assert!(filter.matches(500));
+}
+
+// scanner counts are keyed by family when normalized
+#[tokio::test]
+async fn test_scanner_counts_status_families() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/ok" => MockResponse::new(200, "ok"),
+        "/empty" => MockResponse::new(204, ""),
+        "/gone" => MockResponse::new(410, "gone"),
+        _ => MockResponse::new(404, "not found"),
+    });
+
+    let targets = ["ok", "empty", "gone", "missing"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&["--normalize-status"])).unwrap();
+    scanner.scan_urls(targets).await.unwrap();
+
+    let counts = scanner.status_counts();
+    assert_eq!(counts.get("2xx"), Some(&2));
+    assert_eq!(counts.get("4xx"), Some(&2));
+    assert_eq!(counts.len(), 2);
+}
+
+// --ext-status judges each URL by the rule for its extension and falls back to -s otherwise
+#[test]
+fn test_ext_status_rules_override_by_extension() {
+    let filter = StatusFilter::from_common(&common_args(&[
+        "-s",
+        "200",
+        "--ext-status",
+        ".bak:200,403",
+        "php:200",
+    ]));
+
+    assert!(filter.matches_url("http://example.com/index.php.bak", 403));
+    assert!(filter.matches_url("http://example.com/site.BAK", 200));
+    assert!(!filter.matches_url("http://example.com/index.php", 403));
+    assert!(filter.matches_url("http://example.com/index.php?x=1.bak", 200));
+    assert!(!filter.matches_url("http://example.com/index.html", 403));
+    assert!(filter.matches_url("http://example.com/index.html", 200));
+    assert!(!filter.matches_url("http://example.com/.bak", 403));
+}
+
+// malformed rules are rejected when parsing arguments
+#[test]
+fn test_ext_status_rejects_malformed_rules() {
+    for rule in ["bak", ".bak:abc", ":200"] {
+        assert!(
+            CommonArgs::try_parse_from(["rustbuster", "--ext-status", rule]).is_err(),
+            "accepted {}",
+            rule
+        );
+    }
+}
+
+// --limit-per-status caps each status on its own and still counts what it hides
+#[tokio::test]
+async fn test_limit_per_status_caps_each_status_independently() {
+    let server = MockServer::start(|req| {
+        if req.path.starts_with("/forbidden") {
+            MockResponse::new(403, "forbidden")
+        } else if req.path == "/ok" {
+            MockResponse::new(200, "ok")
+        } else {
+            MockResponse::new(404, "not found")
+        }
+    });
+
+    let mut paths: Vec<String> = (0..5).map(|i| format!("forbidden{}", i)).collect();
+    paths.extend((0..3).map(|i| format!("missing{}", i)));
+    paths.push("ok".to_string());
+    let targets = paths
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&["--limit-per-status", "2", "-s", "200,403,404"])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    let kept = |status: u16| results.iter().filter(|r| r.status_code == status).count();
+    assert_eq!(kept(403), 2);
+    assert_eq!(kept(404), 2);
+    assert_eq!(kept(200), 1);
+
+    let counts = scanner.status_counts();
+    assert_eq!(counts.get("403"), Some(&5));
+    assert_eq!(counts.get("404"), Some(&3));
+
+    let suppressed = scanner.suppressed_counts();
+    assert_eq!(suppressed.get("403"), Some(&3));
+    assert_eq!(suppressed.get("404"), Some(&1));
+    assert_eq!(suppressed.get("200"), None);
+}
+
+// statuses outside -s are dropped from the results but still counted
+#[tokio::test]
+async fn test_scanner_drops_statuses_outside_filter() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/moved" => MockResponse::new(301, "").header("Location", "/elsewhere"),
+        "/ok" => MockResponse::new(200, "ok"),
+        _ => MockResponse::new(404, "not found"),
+    });
+    let targets = ["moved", "ok", "missing"]
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&["-s", "200,301"])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    let mut statuses: Vec<u16> = results.iter().map(|r| r.status_code).collect();
+    statuses.sort_unstable();
+    assert_eq!(statuses, vec![200, 301]);
+    assert_eq!(scanner.status_counts().get("404"), Some(&1));
+}
+
+// -e keeps every response whatever its status
+#[tokio::test]
+async fn test_expanded_keeps_filtered_statuses() {
+    let server = MockServer::start(|_| MockResponse::new(404, "not found"));
+    let targets = vec![ScanTarget::new(format!("{}/missing", server.url()), None)];
+
+    let scanner = Scanner::new_from_common(common_args(&["-s", "200,301", "-e"])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].status_code, 404);
+}
+
+// --auto-filter learns the dominant size during warmup and hides it afterwards
+#[tokio::test]
+async fn test_auto_filter_hides_most_common_size_after_warmup() {
+    let server = MockServer::start(|req| {
+        if req.path.starts_with("/real") {
+            MockResponse::new(200, "a page with real content")
+        } else {
+            MockResponse::new(200, "soft 404")
+        }
+    });
+
+    // nine in ten responses share one size; the warmup covers the first ten
+    let mut paths: Vec<String> = (0..18).map(|i| format!("noise{}", i)).collect();
+    paths.insert(5, "real0".to_string());
+    paths.push("real1".to_string());
+    let targets = paths
+        .iter()
+        .map(|path| ScanTarget::new(format!("{}/{}", server.url(), path), None))
+        .collect();
+
+    let scanner = Scanner::new_from_common(common_args(&["--auto-filter", "10", "-t", "1"])).unwrap();
+    let results = scanner.scan_urls(targets).await.unwrap();
+
+    let noise = results.iter().filter(|r| r.url.contains("/noise")).count();
+    assert_eq!(noise, 9);
+    assert!(results.iter().any(|r| r.url.ends_with("/real0")));
+    assert!(results.iter().any(|r| r.url.ends_with("/real1")));
+}
+
+// --auto-filter alone samples a default number of results
+#[test]
+fn test_auto_filter_default_sample() {
+    assert_eq!(common_args(&["--auto-filter"]).auto_filter, Some(50));
+    assert_eq!(common_args(&[]).auto_filter, None);
+}
@@ -0,0 +1,496 @@
+//! Tests for TUI result rendering
+
+use rustbuster::output::tui::{
+    result_line, write_results_to_file, TuiMessage, TuiOutput, TuiResult, TuiState,
+};
+use tempfile::TempDir;
+
+fn tui_result(ttfb_ms: Option<u64>) -> TuiResult {
+    TuiResult {
+        url: "http://example.com/admin".to_string(),
+        status_code: 200,
+        content_length: 512,
+        redirect_location: None,
+        content_type: None,
+        server: None,
+        duration_ms: 410,
+        source: None,
+        golden_diff: None,
+        ttfb_ms,
+        allowed_methods: None,
+        auth_scheme: None,
+        final_url: None,
+        redirect_count: 0,
+        headers: Default::default(),
+        technologies: Vec::new(),
+        body: None,
+    }
+}
+
+fn line_text(result: &TuiResult) -> String {
+    result_line(result)
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+// the line shows ttfb and total when timing was captured
+#[test]
+fn test_result_line_shows_ttfb_and_total() {
+    let text = line_text(&tui_result(Some(23)));
+    assert!(text.contains("[23ms/410ms]"), "{}", text);
+}
+
+// only the total is shown otherwise
+#[test]
+fn test_result_line_shows_total_only_by_default() {
+    let text = line_text(&tui_result(None));
+    assert!(text.contains(" [410ms]"), "{}", text);
+    assert!(!text.contains("ms/"), "{}", text);
+}
+
+// --json-metadata wraps the results with the final scan counters
+#[test]
+fn test_json_output_includes_metadata() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("results.json");
+
+    let mut state = TuiState::new(
+        "dir".to_string(),
+        "http://example.com".to_string(),
+        "words.txt".to_string(),
+        10,
+        3,
+    );
+    state.add_result(tui_result(None));
+    state.increment_scanned();
+    state.increment_scanned();
+    state.increment_scanned();
+    state.increment_errors();
+
+    let output = TuiOutput {
+        file: Some(path.to_str().unwrap().to_string()),
+        format: "json".to_string(),
+        json_metadata: true,
+        append: false,
+    };
+    write_results_to_file(&state, &output).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(value["target"], "http://example.com");
+    assert_eq!(value["mode"], "dir");
+    assert_eq!(value["total_scanned"], 3);
+    assert_eq!(value["found"], 1);
+    assert_eq!(value["errors"], 1);
+    assert!(value["duration_ms"].is_u64());
+    assert!(value["started_at"].is_string());
+    assert_eq!(value["rustbuster_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(value["results"][0]["url"], "http://example.com/admin");
+}
+
+// without the flag json stays a bare array
+#[test]
+fn test_json_output_without_metadata_is_array() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("results.json");
+
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, 1);
+    state.add_result(tui_result(None));
+
+    let output = TuiOutput {
+        file: Some(path.to_str().unwrap().to_string()),
+        format: "json".to_string(),
+        json_metadata: false,
+        append: false,
+    };
+    write_results_to_file(&state, &output).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(value.is_array());
+}
+
+// line-based formats are appended as results arrive, so a scan cut short keeps its findings
+#[test]
+fn test_live_output_survives_interrupted_scan() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("results.jsonl");
+    std::fs::write(&path, "stale line from an earlier run\n").unwrap();
+
+    let output = TuiOutput {
+        file: Some(path.to_str().unwrap().to_string()),
+        format: "jsonl".to_string(),
+        json_metadata: false,
+        append: false,
+    };
+    assert!(output.is_live());
+    output.start_live().unwrap();
+
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, 5);
+    let mut second = tui_result(None);
+    second.url = "http://example.com/backup".to_string();
+    for msg in [
+        TuiMessage::Result(Box::new(tui_result(None))),
+        TuiMessage::Result(Box::new(second)),
+    ] {
+        state.apply_message(msg, &output);
+    }
+    // the scan stops here without ever sending Done
+    assert!(!state.scan_complete);
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let urls: Vec<String> = content
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["url"].to_string())
+        .collect();
+    assert_eq!(urls, vec!["\"http://example.com/admin\"", "\"http://example.com/backup\""]);
+}
+
+// json and csv still wait for the final write
+#[test]
+fn test_array_formats_are_not_live() {
+    let output = TuiOutput {
+        file: Some("results.csv".to_string()),
+        format: "csv".to_string(),
+        json_metadata: false,
+        append: false,
+    };
+    assert!(!output.is_live());
+}
+
+fn state_with_urls(results: &[(&str, u16)]) -> TuiState {
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, results.len());
+    for (url, status) in results {
+        let mut result = tui_result(None);
+        result.url = url.to_string();
+        result.status_code = *status;
+        state.add_result(result);
+    }
+    state
+}
+
+// the search matches URLs case-insensitively and status codes as text
+#[test]
+fn test_filtered_indices_match_url_and_status() {
+    let state = state_with_urls(&[
+        ("http://example.com/admin", 200),
+        ("http://example.com/Backup.zip", 403),
+        ("http://example.com/ADMIN/login", 301),
+    ]);
+
+    assert_eq!(state.filtered_indices("admin"), vec![0, 2]);
+    assert_eq!(state.filtered_indices("backup"), vec![1]);
+    assert_eq!(state.filtered_indices("403"), vec![1]);
+    assert!(state.filtered_indices("missing").is_empty());
+}
+
+// typing narrows the visible results and Esc brings them all back
+#[test]
+fn test_filter_prompt_keys() {
+    use crossterm::event::KeyCode;
+
+    let mut state = state_with_urls(&[("http://example.com/admin", 200), ("http://example.com/api", 200)]);
+    state.start_filter();
+    for c in "adm".chars() {
+        state.edit_filter(KeyCode::Char(c));
+    }
+    state.edit_filter(KeyCode::Enter);
+    assert!(!state.editing_filter);
+    assert_eq!(state.visible_indices(), vec![0]);
+
+    state.clear_filter();
+    assert_eq!(state.visible_indices(), vec![0, 1]);
+}
+
+fn timed_result(url: &str, status_code: u16, content_length: u64, duration_ms: u64) -> TuiResult {
+    TuiResult {
+        url: url.to_string(),
+        status_code,
+        content_length,
+        duration_ms,
+        ..tui_result(None)
+    }
+}
+
+fn urls(state: &TuiState) -> Vec<&str> {
+    state.results.iter().map(|r| r.url.as_str()).collect()
+}
+
+// each sort key orders the results by its column, cycling back to status
+#[test]
+fn test_sort_keys_order_results() {
+    use rustbuster::output::tui::SortKey;
+
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, 3);
+    state.add_result(timed_result("/c", 403, 10, 300));
+    state.add_result(timed_result("/a", 200, 300, 20));
+    state.add_result(timed_result("/b", 301, 20, 100));
+
+    state.cycle_sort();
+    assert_eq!(state.sort, Some(SortKey::Status));
+    assert_eq!(urls(&state), vec!["/a", "/b", "/c"]);
+
+    state.cycle_sort();
+    assert_eq!(state.sort, Some(SortKey::Size));
+    assert_eq!(urls(&state), vec!["/c", "/b", "/a"]);
+
+    state.cycle_sort();
+    assert_eq!(state.sort, Some(SortKey::Duration));
+    assert_eq!(urls(&state), vec!["/a", "/b", "/c"]);
+
+    state.cycle_sort();
+    assert_eq!(state.sort, Some(SortKey::Url));
+    assert_eq!(urls(&state), vec!["/a", "/b", "/c"]);
+
+    state.cycle_sort();
+    assert_eq!(state.sort, Some(SortKey::Status));
+}
+
+// results arriving after a sort land in place and the scroll offset stays in range
+#[test]
+fn test_sorted_insert_and_scroll_clamp() {
+    use rustbuster::output::tui::SortKey;
+
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, 3);
+    state.add_result(timed_result("/a", 200, 50, 10));
+    state.add_result(timed_result("/b", 200, 10, 10));
+    state.scroll_offset = 7;
+
+    state.sort_by(SortKey::Size);
+    assert_eq!(state.scroll_offset, 1);
+
+    state.add_result(timed_result("/c", 200, 20, 10));
+    assert_eq!(urls(&state), vec!["/b", "/c", "/a"]);
+}
+
+// recent errors are kept oldest first and capped, while the total keeps counting
+#[test]
+fn test_recent_errors_ring_buffer() {
+    use rustbuster::output::tui::RECENT_ERRORS;
+
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, 10);
+    let output = TuiOutput {
+        file: None,
+        format: "plain".to_string(),
+        json_metadata: false,
+        append: false,
+    };
+    for i in 0..RECENT_ERRORS + 3 {
+        state.apply_message(
+            TuiMessage::Error {
+                url: format!("http://example.com/{}", i),
+                reason: "Connection refused".to_string(),
+            },
+            &output,
+        );
+    }
+
+    assert_eq!(state.errors, RECENT_ERRORS + 3);
+    assert_eq!(state.recent_errors.len(), RECENT_ERRORS);
+    assert_eq!(state.recent_errors.front().unwrap().0, "http://example.com/3");
+    assert_eq!(state.recent_errors.back().unwrap().0, format!("http://example.com/{}", RECENT_ERRORS + 2));
+}
+
+// the error message carries the innermost cause rather than the whole chain
+#[test]
+fn test_error_message_uses_root_cause() {
+    let err = anyhow::anyhow!("Connection refused").context("error sending request");
+    match TuiMessage::error("http://example.com/a", &err) {
+        TuiMessage::Error { url, reason } => {
+            assert_eq!(url, "http://example.com/a");
+            assert_eq!(reason, "Connection refused");
+        }
+        _ => panic!("expected an error message"),
+    }
+}
+
+// workers wait at the pause gate while paused and carry on once resumed
+#[tokio::test]
+async fn test_pause_gate_blocks_until_resumed() {
+    use rustbuster::output::tui::PauseGate;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    let gate = PauseGate::new();
+    assert!(gate.toggle());
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let workers: Vec<_> = (0..3)
+        .map(|_| {
+            let gate = gate.clone();
+            let requests = Arc::clone(&requests);
+            tokio::spawn(async move {
+                gate.wait().await;
+                requests.fetch_add(1, Ordering::SeqCst);
+            })
+        })
+        .collect();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+    assert!(!gate.toggle());
+    for worker in workers {
+        tokio::time::timeout(Duration::from_secs(2), worker).await.unwrap().unwrap();
+    }
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+// space marks the highlighted row and unmarks it again, and copies fall back to the cursor row
+#[test]
+fn test_selection_toggle() {
+    let mut state = state_with_urls(&[
+        ("http://example.com/admin", 200),
+        ("http://example.com/api", 200),
+        ("http://example.com/backup", 200),
+    ]);
+
+    let selected_urls = |state: &TuiState| -> Vec<String> {
+        state.selected_results().iter().map(|r| r.url.clone()).collect()
+    };
+    assert_eq!(selected_urls(&state), vec!["http://example.com/admin"]);
+
+    state.move_cursor(2, 20);
+    state.toggle_selected();
+    state.move_cursor(-2, 20);
+    state.toggle_selected();
+    assert_eq!(state.cursor, 0);
+    assert_eq!(
+        selected_urls(&state),
+        vec!["http://example.com/admin", "http://example.com/backup"]
+    );
+
+    state.toggle_selected();
+    assert_eq!(selected_urls(&state), vec!["http://example.com/backup"]);
+
+    // the cursor stays within the visible rows and keeps itself on screen
+    state.move_cursor(10, 2);
+    assert_eq!(state.cursor, 2);
+    assert_eq!(state.scroll_offset, 1);
+}
+
+// exporting writes just the chosen rows in the plain format
+#[test]
+fn test_export_selected_results() {
+    use rustbuster::output::tui::export_results;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("selected.txt");
+    let mut state = state_with_urls(&[("http://example.com/admin", 200), ("http://example.com/api", 200)]);
+    state.move_cursor(1, 20);
+    state.toggle_selected();
+
+    export_results(&state.selected_results(), path.to_str().unwrap()).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.contains("http://example.com/api"), "{}", content);
+}
+
+// a flood of workers against a tiny channel and a slow reader still ends with exact totals
+#[tokio::test]
+async fn test_counters_stay_exact_under_message_flood() {
+    use rustbuster::output::tui::TuiSender;
+    use std::time::Duration;
+
+    const URLS: usize = 2000;
+    let (tx, mut rx) = TuiSender::channel(1);
+    let counters = tx.counters();
+
+    let workers: Vec<_> = (0..URLS)
+        .map(|i| {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                tx.scanned();
+                if i % 10 == 0 {
+                    let mut result = tui_result(None);
+                    result.url = format!("http://example.com/{}", i);
+                    tx.result(result).await;
+                } else if i % 10 == 1 {
+                    tx.error(format!("http://example.com/{}", i), &anyhow::anyhow!("Connection refused"));
+                }
+            })
+        })
+        .collect();
+    let scan = tokio::spawn(async move {
+        for worker in workers {
+            worker.await.unwrap();
+        }
+        tx.done().await;
+    });
+
+    let output = TuiOutput {
+        file: None,
+        format: "plain".to_string(),
+        json_metadata: false,
+        append: false,
+    };
+    let mut state = TuiState::new("dir".to_string(), String::new(), String::new(), 10, URLS);
+    while !state.scan_complete {
+        let msg = rx.recv().await.expect("channel closed before Done");
+        state.apply_message(msg, &output);
+        state.sync_counters(&counters);
+        tokio::time::sleep(Duration::from_micros(50)).await;
+    }
+    scan.await.unwrap();
+    state.sync_counters(&counters);
+
+    assert_eq!(state.scanned, URLS);
+    assert_eq!(state.found, URLS / 10);
+    assert_eq!(state.errors, URLS / 10);
+}
+
+// the TUI's CSV output escapes fields the same way
+#[test]
+fn test_csv_output_escapes_fields() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("results.csv");
+    let mut state = TuiState::new(
+        "dir".to_string(),
+        "http://example.com".to_string(),
+        "words.txt".to_string(),
+        1,
+        1,
+    );
+    state.add_result(TuiResult {
+        redirect_location: Some("/next?a=1,2".to_string()),
+        server: Some("say \"hi\"\nagain".to_string()),
+        ..tui_result(None)
+    });
+    let output = TuiOutput {
+        file: Some(path.to_str().unwrap().to_string()),
+        format: "csv".to_string(),
+        json_metadata: false,
+        append: false,
+    };
+    write_results_to_file(&state, &output).unwrap();
+
+    let mut reader = csv::Reader::from_path(&path).unwrap();
+    let row = reader.records().next().unwrap().unwrap();
+    assert_eq!(row.len(), 12);
+    assert_eq!(&row[4], "/next?a=1,2");
+    assert_eq!(&row[6], "say \"hi\"\nagain");
+}
+
+// quitting while paused opens the gate and ends the scan instead of waiting on it forever
+#[tokio::test]
+async fn test_quit_while_paused_ends_scan() {
+    use rustbuster::output::tui::{stop_scan, PauseGate};
+    use std::time::Duration;
+
+    let gate = PauseGate::new();
+    assert!(gate.toggle());
+
+    let worker_gate = gate.clone();
+    let scan = tokio::spawn(async move {
+        loop {
+            worker_gate.wait().await;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let stopped = tokio::time::timeout(Duration::from_secs(2), stop_scan(scan, &gate)).await;
+    assert!(stopped.expect("the scan should end once the TUI quits").is_ok());
+    assert!(!gate.is_paused());
+}
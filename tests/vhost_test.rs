@@ -0,0 +1,120 @@
+//! Tests for virtual host baseline filtering
+
+mod common;
+
+use clap::Parser;
+use common::{MockResponse, MockServer};
+use rustbuster::cli::CommonArgs;
+use rustbuster::core::HttpClient;
+use rustbuster::modes::vhost::{self, VhostBaseline};
+
+fn vhost_server() -> MockServer {
+    MockServer::start(|req| match req.header("host") {
+        Some(host) if host.starts_with("admin.") => MockResponse::new(200, "<h1>Admin panel</h1>"),
+        _ => MockResponse::new(200, "default page"),
+    })
+}
+
+async fn probe(client: &HttpClient, url: &str, host: &str) -> (u16, u64) {
+    let headers = vec![("Host".to_string(), host.to_string())];
+    let response = client.request(url, "GET", &headers, None, None).await.unwrap();
+    let status = response.status().as_u16();
+    (status, vhost::response_size(client, "GET", response).await)
+}
+
+fn gzip(text: &str) -> Vec<u8> {
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+// vhosts answering with the default page are suppressed, a distinct one is reported
+#[tokio::test]
+async fn test_vhost_baseline_suppresses_default_responses() {
+    let server = vhost_server();
+    let common = CommonArgs::try_parse_from(["rustbuster"]).unwrap();
+    let client = HttpClient::new_from_common(&common).unwrap();
+
+    let baseline = VhostBaseline::fetch(&client, &server.url(), "GET", &[], None, "example.com")
+        .await
+        .expect("baseline request should succeed");
+    assert_eq!(baseline.status_code, 200);
+    assert_eq!(baseline.content_length, "default page".len() as u64);
+
+    let (status, size) = probe(&client, &server.url(), "www.example.com").await;
+    assert!(baseline.matches(status, size));
+
+    let (status, size) = probe(&client, &server.url(), "admin.example.com").await;
+    assert!(!baseline.matches(status, size));
+}
+
+// the baseline request uses a random Host under the base domain
+#[tokio::test]
+async fn test_vhost_baseline_uses_random_host() {
+    let server = vhost_server();
+    let common = CommonArgs::try_parse_from(["rustbuster"]).unwrap();
+    let client = HttpClient::new_from_common(&common).unwrap();
+
+    VhostBaseline::fetch(&client, &server.url(), "GET", &[], None, "example.com").await;
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let host = requests[0].header("host").unwrap();
+    assert!(host.starts_with("rustbuster-"));
+    assert!(host.ends_with(".example.com"));
+}
+
+// gzip responses carry no usable length, so sizes are measured from the decoded body and a
+// vhost with the baseline's status but different content is still reported
+#[tokio::test]
+async fn test_vhost_baseline_measures_compressed_bodies() {
+    let server = MockServer::start(|req| {
+        let body = match req.header("host") {
+            Some(host) if host.starts_with("admin.") => gzip("<h1>Admin panel</h1>"),
+            _ => gzip("default page"),
+        };
+        MockResponse::bytes(200, body).header("Content-Encoding", "gzip")
+    });
+    let common = CommonArgs::try_parse_from(["rustbuster"]).unwrap();
+    let client = HttpClient::new_from_common(&common).unwrap();
+
+    let baseline = VhostBaseline::fetch(&client, &server.url(), "GET", &[], None, "example.com")
+        .await
+        .expect("baseline request should succeed");
+    assert_eq!(baseline.content_length, "default page".len() as u64);
+
+    let (status, size) = probe(&client, &server.url(), "www.example.com").await;
+    assert!(baseline.matches(status, size));
+
+    let (status, size) = probe(&client, &server.url(), "admin.example.com").await;
+    assert_eq!(size, "<h1>Admin panel</h1>".len() as u64);
+    assert!(!baseline.matches(status, size));
+}
+
+// -e shows every vhost, but only those past the status filter and baseline count as found
+#[tokio::test]
+async fn test_vhost_expanded_output_counts_only_findings() {
+    use rustbuster::cli::VhostArgs;
+    use std::io::Write;
+
+    let server = vhost_server();
+    let mut wordlist = tempfile::NamedTempFile::new().unwrap();
+    writeln!(wordlist, "www\nmail").unwrap();
+    let args = VhostArgs::try_parse_from([
+        "vhost",
+        "-u",
+        &server.url(),
+        "-w",
+        wordlist.path().to_str().unwrap(),
+        "--no-tui",
+        "-q",
+        "-e",
+    ])
+    .unwrap();
+
+    let summary = vhost::run(args).await.unwrap();
+    assert_eq!(summary.found, 0);
+}
@@ -1,5 +1,10 @@
 //! Unit tests for wordlist functionality
 
+mod common;
+
+use clap::Parser;
+use common::wordlist_file;
+use rustbuster::cli::CommonArgs;
 use rustbuster::core::wordlist::Wordlist;
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -15,12 +20,22 @@ fn test_wordlist_from_file() {
     assert_eq!(wordlist.len(), 3);
     assert!(wordlist.words.contains(&"admin".to_string()));
 }
+
+// A line that is not valid UTF-8 is skipped and the words after it still load
+#[test]
+fn test_wordlist_skips_invalid_utf8_lines() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"admin\n\xff\xfebad\nlogin\r\n").unwrap();
+
+    let wordlist = Wordlist::from_file(file.path().to_str().unwrap()).unwrap();
+    assert_eq!(wordlist.words, vec!["admin", "login"]);
+}
 // ensure empty lines are ignored
 #[test]
 fn test_wordlist_filters_empty_lines() {
     let mut file = NamedTempFile::new().unwrap();
     writeln!(file, "admin").unwrap();
-    writeln!(file, "").unwrap();
+    writeln!(file).unwrap();
     writeln!(file, "login").unwrap();
 
     let wordlist = Wordlist::from_file(file.path().to_str().unwrap()).unwrap();
@@ -63,3 +78,190 @@ fn test_wordlist_empty_file() {
     let result = Wordlist::from_file(file.path().to_str().unwrap());
     assert!(result.is_err());
 }
+
+// gzipped wordlists are decompressed transparently
+#[test]
+fn test_wordlist_from_gzip_file() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let file = tempfile::Builder::new().suffix(".txt.gz").tempfile().unwrap();
+    let mut encoder = GzEncoder::new(file.reopen().unwrap(), Compression::default());
+    encoder.write_all(b"admin\n# comment\n\nlogin\ntest\n").unwrap();
+    encoder.finish().unwrap();
+
+    let wordlist = Wordlist::from_file(file.path().to_str().unwrap()).unwrap();
+    assert_eq!(wordlist.words, vec!["admin", "login", "test"]);
+}
+
+fn wordlist_of(words: &[&str]) -> Wordlist {
+    let mut file = NamedTempFile::new().unwrap();
+    for word in words {
+        writeln!(file, "{}", word).unwrap();
+    }
+    Wordlist::from_file(file.path().to_str().unwrap()).unwrap()
+}
+
+// case permutations add lower, upper and capitalized forms without duplicates
+#[test]
+fn test_wordlist_case_permutations() {
+    let mutated = wordlist_of(&["admin", "Login", "API"]).with_case_permutations();
+
+    assert_eq!(mutated.len(), 9);
+    assert_eq!(&mutated.words[..3], ["admin", "ADMIN", "Admin"]);
+    assert!(mutated.words.contains(&"LOGIN".to_string()));
+    assert!(mutated.words.contains(&"api".to_string()));
+    assert!(mutated.words.contains(&"Api".to_string()));
+}
+
+// prefixes and suffixes wrap every word in each combination
+#[test]
+fn test_wordlist_affixes() {
+    let wordlist = wordlist_of(&["env", "config"]);
+    let prefixes = vec!["_".to_string(), ".".to_string()];
+    let suffixes = vec![".bak".to_string()];
+
+    let both = wordlist.with_affixes(&prefixes, &suffixes);
+    assert_eq!(both.words, vec!["_env.bak", ".env.bak", "_config.bak", ".config.bak"]);
+
+    let prefixed = wordlist.with_affixes(&prefixes, &[]);
+    assert_eq!(prefixed.words, vec!["_env", ".env", "_config", ".config"]);
+}
+
+// mutations drop repeated entries and chain in the order the flags describe
+#[test]
+fn test_wordlist_mutations_dedup() {
+    let common = CommonArgs::try_parse_from([
+        "rustbuster",
+        "--wordlist-mutate",
+        "case",
+        "--suffix",
+        "/",
+        "--suffix",
+        "",
+    ])
+    .unwrap();
+    let mutated = wordlist_of(&["admin", "ADMIN"]).with_options(&common);
+
+    assert_eq!(mutated.words, vec!["admin/", "admin", "ADMIN/", "ADMIN", "Admin/", "Admin"]);
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--wordlist-mutate", "leet"]).is_err());
+}
+
+// %EXT% is replaced by each extension in place while plain words still get them appended
+#[test]
+fn test_wordlist_ext_placeholder() {
+    let wordlist = wordlist_of(&["config.%EXT%.bak", "admin"]);
+    let extensions = vec![".php".to_string(), "~".to_string()];
+
+    assert_eq!(
+        wordlist.expand_with_extensions(&extensions),
+        vec!["config.php.bak", "config.~.bak", "admin", "admin.php", "admin~"]
+    );
+    assert_eq!(wordlist.expand_with_extensions(&[]), vec!["admin"]);
+}
+
+// --unique drops repeats in first-seen order and --sort orders what is left
+#[test]
+fn test_wordlist_unique_and_sort() {
+    let mut wordlist = wordlist_of(&["login", "admin", "login", "backup", "admin"]);
+    assert_eq!(wordlist.dedup(), 2);
+    assert_eq!(wordlist.words, vec!["login", "admin", "backup"]);
+
+    wordlist.sort();
+    assert_eq!(wordlist.words, vec!["admin", "backup", "login"]);
+
+    use clap::Parser;
+    let common = rustbuster::cli::CommonArgs::try_parse_from(["rustbuster", "--unique", "--sort"]).unwrap();
+    let prepared = wordlist_of(&["b", "a", "b"]).with_options(&common);
+    assert_eq!(prepared.words, vec!["a", "b"]);
+}
+
+// --no-bare-word keeps only the extension variants; %EXT% words are unaffected
+#[test]
+fn test_wordlist_expand_extensions_only() {
+    let wordlist = wordlist_of(&["admin", "config.%EXT%"]);
+    let words: Vec<String> = wordlist
+        .expand_extensions_only(&[".bak".to_string()])
+        .into_iter()
+        .map(|(word, _)| word)
+        .collect();
+    assert_eq!(words, vec!["admin.bak", "config.bak"]);
+}
+
+/// Loads the files through repeated -w flags, plus `extra`
+fn combined(files: &[&NamedTempFile], extra: &[&str]) -> anyhow::Result<Wordlist> {
+    let mut args = vec!["rustbuster".to_string()];
+    for file in files {
+        args.push("-w".to_string());
+        args.push(file.path().to_str().unwrap().to_string());
+    }
+    args.extend(extra.iter().map(|arg| arg.to_string()));
+    Wordlist::from_common(&CommonArgs::try_parse_from(args).unwrap())
+}
+
+// several -w files are unioned, each word once and tagged with the file it came from first
+#[test]
+fn test_wordlist_union_of_files() {
+    let first = wordlist_file(&["admin", "login", "admin"]);
+    let second = wordlist_file(&["login", "backup"]);
+
+    let wordlist = combined(&[&first, &second], &[]).unwrap();
+    assert_eq!(wordlist.words, vec!["admin", "login", "backup"]);
+    assert_eq!(wordlist.source(1), first.path().to_str());
+    assert_eq!(wordlist.source(2), second.path().to_str());
+}
+
+// --wordlist-intersect keeps the words every file has, and fails when there are none
+#[test]
+fn test_wordlist_intersect_of_files() {
+    let first = wordlist_file(&["admin", "login", "backup", "login"]);
+    let second = wordlist_file(&["backup", "login", "api"]);
+
+    let wordlist = combined(&[&first, &second], &["--wordlist-intersect"]).unwrap();
+    assert_eq!(wordlist.words, vec!["login", "backup"]);
+
+    let disjoint = wordlist_file(&["nothing"]);
+    assert!(combined(&[&first, &disjoint], &["--wordlist-intersect"]).is_err());
+    assert!(combined(&[], &[]).is_err());
+}
+
+// --wordlist-offset/--wordlist-limit keep one range of words, and reject an offset past the end
+#[test]
+fn test_wordlist_offset_and_limit() {
+    let file = wordlist_file(&["a", "b", "c", "d", "e"]);
+
+    let wordlist = combined(&[&file], &["--wordlist-offset", "1", "--wordlist-limit", "2"]).unwrap();
+    assert_eq!(wordlist.words, vec!["b", "c"]);
+    assert_eq!(wordlist.source(0), file.path().to_str());
+
+    let rest = combined(&[&file], &["--wordlist-offset", "3", "--wordlist-limit", "10"]).unwrap();
+    assert_eq!(rest.words, vec!["d", "e"]);
+
+    let first = combined(&[&file], &["--wordlist-limit", "2"]).unwrap();
+    assert_eq!(first.words, vec!["a", "b"]);
+
+    assert!(combined(&[&file], &["--wordlist-offset", "5"]).is_err());
+    assert!(CommonArgs::try_parse_from(["rustbuster", "--wordlist-limit", "0"]).is_err());
+}
+
+// --shard i/n splits the list into n disjoint parts that together hold every word
+#[test]
+fn test_wordlist_shards_cover_the_list() {
+    let words = ["a", "b", "c", "d", "e", "f", "g"];
+    let file = wordlist_file(&words);
+
+    let shards: Vec<Vec<String>> = (1..=3)
+        .map(|index| combined(&[&file], &["--shard", &format!("{}/3", index)]).unwrap().words)
+        .collect();
+    assert_eq!(shards[0], vec!["a", "d", "g"]);
+    assert_eq!(shards[1], vec!["b", "e"]);
+    assert_eq!(shards[2], vec!["c", "f"]);
+
+    let mut union: Vec<String> = shards.concat();
+    union.sort();
+    assert_eq!(union, words);
+
+    for invalid in ["0/3", "4/3", "3", "a/3"] {
+        assert!(CommonArgs::try_parse_from(["rustbuster", "--shard", invalid]).is_err());
+    }
+}